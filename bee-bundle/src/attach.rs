@@ -0,0 +1,218 @@
+use common::{Error, Result, Trit};
+
+use pow::{Cores, Difficulty, InputTrits, PearlDiver, PearlDiverState, PowOutcome};
+
+use ternary::iota_conversion::Trinary;
+
+use crate::constants::*;
+use crate::transaction::{number_trits, Hash, Transaction};
+
+use std::convert::TryFrom;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Attaches the transactions of a bundle, given in index order, on top of `trunk` and `branch`.
+///
+/// The proof of work is done from the last index down to index 0. The last transaction approves `trunk` and `branch`,
+/// every other one approves the next transaction of the bundle as its trunk and `trunk` as its branch. All of them get
+/// the current time as their attachment timestamp. Like IRI's `attachToTangle`, the attached transactions are returned
+/// in the reverse order of the proof of work, i.e. in index order.
+///
+/// Each search runs on `cores`, and the attachment fails once `deadline` has passed.
+pub fn attach_bundle(
+    transactions: &[Transaction],
+    trunk: &Hash,
+    branch: &Hash,
+    difficulty: Difficulty,
+    cores: Cores,
+    deadline: Option<Instant>,
+) -> Result<Vec<PowOutcome<Transaction>>> {
+    let last_index = transactions
+        .len()
+        .checked_sub(1)
+        .ok_or(Error::TransactionError)?;
+
+    for (index, transaction) in transactions.iter().enumerate() {
+        if transaction.index().0 != index || transaction.last_index().0 != last_index {
+            return Err(Error::TransactionError);
+        }
+    }
+
+    let attachment_ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let mut attached = Vec::with_capacity(transactions.len());
+    let mut next: Option<Vec<Trit>> = None;
+
+    for transaction in transactions.iter().rev() {
+        let mut trits = transaction.to_trits();
+
+        let (trunk, branch) = match next.take() {
+            Some(next) => (next, hash_trits(trunk)),
+            None => (hash_trits(trunk), hash_trits(branch)),
+        };
+        write(&mut trits, &TRUNK_HASH, &trunk);
+        write(&mut trits, &BRANCH_HASH, &branch);
+        write(
+            &mut trits,
+            &ATTACHMENT_TS,
+            &number_trits(&ATTACHMENT_TS, attachment_ts)?,
+        );
+        write(
+            &mut trits,
+            &ATTACHMENT_LBTS,
+            &number_trits(&ATTACHMENT_LBTS, 0u64)?,
+        );
+        write(
+            &mut trits,
+            &ATTACHMENT_UBTS,
+            &number_trits(&ATTACHMENT_UBTS, UPPER_BOUND_ATTACHMENT_TIMESTAMP)?,
+        );
+
+        // Can't fail, the trits are exactly one transaction long
+        let input = InputTrits::try_from(&trits[..]).unwrap();
+        let mut pearldiver = PearlDiver::new(cores.clone(), difficulty.clone());
+        if let Some(deadline) = deadline {
+            pearldiver = pearldiver.with_deadline(deadline);
+        }
+        let outcome = match pearldiver.search_transaction(input) {
+            Some(outcome) => outcome,
+            None if pearldiver.state() == PearlDiverState::TimedOut => {
+                return Err(Error::RuntimeError(String::from("proof of work timed out")))
+            }
+            None => return Err(Error::RuntimeError(String::from("no nonce found"))),
+        };
+
+        next = Some(outcome.hash.to_vec());
+        // Can't fail, the stamped trits only differ from a valid transaction by their nonce
+        attached.push(outcome.map(|input| Transaction::from_trits(&input[..]).unwrap()));
+    }
+
+    attached.reverse();
+
+    Ok(attached)
+}
+
+fn hash_trits(hash: &Hash) -> Vec<Trit> {
+    hash.0.iter().collect::<String>().trits()
+}
+
+fn write(trits: &mut [Trit], field: &Field, value: &[Trit]) {
+    trits[field.trit_offset.start..][..field.trit_offset.length].copy_from_slice(value);
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+
+    use crate::transaction::{Address, Index, TransactionBuilder, Value};
+
+    use std::time::Duration;
+
+    fn transaction(index: usize, last_index: usize) -> Transaction {
+        let mut builder = TransactionBuilder::default();
+        builder
+            .address("BEE".parse::<Address>().unwrap())
+            .value(Value(index as i64))
+            .index(Index(index))
+            .last_index(Index(last_index));
+        builder.build()
+    }
+
+    fn hash(trytes: &str) -> Hash {
+        let mut hash = Hash::default();
+        hash.0
+            .iter_mut()
+            .zip(trytes.chars())
+            .for_each(|(h, t)| *h = t);
+        hash
+    }
+
+    fn trytes(hash: &Hash) -> String {
+        hash.0.iter().collect()
+    }
+
+    #[test]
+    fn chain_attached_transactions() {
+        let transactions = (0..3).map(|i| transaction(i, 2)).collect::<Vec<_>>();
+        let trunk = hash(&"A".repeat(TRUNK_HASH.tryte_offset.length));
+        let branch = hash(&"B".repeat(TRUNK_HASH.tryte_offset.length));
+
+        let attached = attach_bundle(
+            &transactions,
+            &trunk,
+            &branch,
+            Difficulty::from(3),
+            Cores::from(1),
+            None,
+        )
+        .unwrap();
+        let hashes = attached
+            .iter()
+            .map(|outcome| (&outcome.hash[..]).trytes().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(trytes(attached[2].transaction.trunk_hash()), trytes(&trunk));
+        assert_eq!(
+            trytes(attached[2].transaction.branch_hash()),
+            trytes(&branch)
+        );
+        for i in 0..2 {
+            assert_eq!(trytes(attached[i].transaction.trunk_hash()), hashes[i + 1]);
+            assert_eq!(
+                trytes(attached[i].transaction.branch_hash()),
+                trytes(&trunk)
+            );
+        }
+
+        for outcome in &attached {
+            assert!(outcome.trailing_zeros >= 3);
+            assert!(outcome.transaction.attachment_ts().0 > 0);
+            assert_eq!(outcome.transaction.attachment_lbts().0, 0);
+            assert_eq!(
+                outcome.transaction.attachment_ubts().0,
+                UPPER_BOUND_ATTACHMENT_TIMESTAMP
+            );
+        }
+        assert_eq!(attached[1].transaction.value().0, 1);
+    }
+
+    #[test]
+    fn reject_inconsistent_indices() {
+        let hash = Hash::default();
+        let attach = |transactions: &[Transaction]| {
+            attach_bundle(
+                transactions,
+                &hash,
+                &hash,
+                Difficulty::from(1),
+                Cores::from(1),
+                None,
+            )
+        };
+
+        assert!(attach(&[]).is_err());
+        assert!(attach(&[transaction(1, 1)]).is_err());
+        assert!(attach(&[transaction(0, 2), transaction(1, 2)]).is_err());
+    }
+
+    #[test]
+    fn time_out_at_deadline() {
+        let hash = Hash::default();
+
+        let result = attach_bundle(
+            &[transaction(0, 0)],
+            &hash,
+            &hash,
+            Difficulty::from(TRUNK_HASH.trit_offset.length),
+            Cores::from(1),
+            Some(Instant::now() + Duration::from_millis(20)),
+        );
+
+        match result {
+            Err(Error::RuntimeError(details)) => assert_eq!(details, "proof of work timed out"),
+            _ => panic!("the attachment didn't time out"),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use common::constants::*;
 
+use ternary::{Encoding, Trits};
+
 pub struct Offset {
     pub start: usize,
     pub length: usize,
@@ -22,6 +24,11 @@ impl Field {
             self.trit_offset.length / 5 + 1
         }
     }
+
+    /// The trits of the field in the trits of a whole transaction, read where they are stored.
+    pub fn trits<'a, E: Encoding>(&self, transaction: Trits<'a, E>) -> Trits<'a, E> {
+        transaction.slice(self.trit_offset.start..self.trit_offset.start + self.trit_offset.length)
+    }
 }
 
 macro_rules! offsets_from_trits {
@@ -71,6 +78,9 @@ pub const ATTACHMENT_UBTS: Field =
     offsets_from_previous_field!(ATTACHMENT_LBTS, TIMESTAMP_TRIT_LEN);
 pub const NONCE: Field = offsets_from_previous_field!(ATTACHMENT_UBTS, NONCE_TRIT_LEN);
 
+/// The largest timestamp that fits into a timestamp field.
+pub const UPPER_BOUND_ATTACHMENT_TIMESTAMP: u64 = (3u64.pow(TIMESTAMP_TRIT_LEN as u32) - 1) / 2;
+
 #[cfg(test)]
 mod should {
     use super::*;
@@ -1,7 +1,9 @@
+mod attach;
 mod bundle;
 mod bundle_builder;
 mod constants;
 mod transaction;
 
+pub use attach::attach_bundle;
 pub use constants::*;
 pub use transaction::*;
@@ -1,9 +1,16 @@
-use common::Tryte;
 use common::constants::*;
-use common::Result;
 use common::Error;
+use common::Result;
+use common::Trit;
+use common::Tryte;
 
-use ternary::IsTryte;
+use pow::{InputTrits, PearlDiver, PowOutcome};
+
+use ternary::iota_conversion::Trinary;
+use ternary::{Encoding, IsTryte, TritBuf, Trits, TryteString, T1B1, T5B1};
+
+use std::convert::TryFrom;
+use std::str::FromStr;
 
 use crate::constants::*;
 
@@ -73,18 +80,14 @@ impl std::fmt::Debug for Address {
     }
 }
 
-impl Address {
-    pub fn from_str(address: &str) -> Self {
-        assert!(address.len() <= ADDRESS.tryte_offset.length);
-        assert!(address.chars().all(|c| c.is_tryte()));
+impl FromStr for Address {
+    type Err = ternary::Error;
 
+    fn from_str(address: &str) -> std::result::Result<Self, Self::Err> {
         let mut trytes = [TRYTE_ZERO; ADDRESS.tryte_offset.length];
+        parse_trytes(address, &mut trytes)?;
 
-        for (i, c) in address.chars().enumerate() {
-            trytes[i] = c;
-        }
-
-        Self(trytes)
+        Ok(Self(trytes))
     }
 }
 
@@ -100,18 +103,14 @@ impl std::fmt::Debug for Tag {
     }
 }
 
-impl Tag {
-    pub fn from_str(tag: &str) -> Self {
-        assert!(tag.len() <= TAG.tryte_offset.length);
-        assert!(tag.chars().all(|c| c.is_tryte()));
+impl FromStr for Tag {
+    type Err = ternary::Error;
 
+    fn from_str(tag: &str) -> std::result::Result<Self, Self::Err> {
         let mut trytes = [TRYTE_ZERO; TAG.tryte_offset.length];
+        parse_trytes(tag, &mut trytes)?;
 
-        for (i, c) in tag.chars().enumerate() {
-            trytes[i] = c;
-        }
-
-        Self(trytes)
+        Ok(Self(trytes))
     }
 }
 
@@ -127,6 +126,17 @@ impl std::fmt::Debug for Hash {
     }
 }
 
+impl FromStr for Hash {
+    type Err = ternary::Error;
+
+    fn from_str(hash: &str) -> std::result::Result<Self, Self::Err> {
+        let mut trytes = [TRYTE_ZERO; BUNDLE_HASH.tryte_offset.length];
+        parse_trytes(hash, &mut trytes)?;
+
+        Ok(Self(trytes))
+    }
+}
+
 impl Default for Nonce {
     fn default() -> Self {
         Self([TRYTE_ZERO; NONCE.tryte_offset.length])
@@ -139,35 +149,167 @@ impl std::fmt::Debug for Nonce {
     }
 }
 
-impl Nonce {
-    pub fn from_str(nonce: &str) -> Self {
-        assert!(nonce.len() <= NONCE.tryte_offset.length);
-        assert!(nonce.chars().all(|c| c.is_tryte()));
+impl FromStr for Nonce {
+    type Err = ternary::Error;
 
+    fn from_str(nonce: &str) -> std::result::Result<Self, Self::Err> {
         let mut trytes = [TRYTE_ZERO; NONCE.tryte_offset.length];
+        parse_trytes(nonce, &mut trytes)?;
 
-        for (i, c) in nonce.chars().enumerate() {
-            trytes[i] = c;
-        }
-
-        Self(trytes)
+        Ok(Self(trytes))
     }
 }
 
 impl Transaction {
-    pub fn from_tryte_str(tx_trytes: &str) -> Self {
-        assert_eq!(TRANSACTION_TRYT_LEN, tx_trytes.len());
+    /// Create a `Transaction` from its `TRANSACTION_TRYT_LEN` trytes.
+    pub fn from_tryte_str(tx_trytes: &str) -> Result<Self> {
+        if tx_trytes.len() != TRANSACTION_TRYT_LEN || !tx_trytes.chars().all(|c| c.is_tryte()) {
+            return Err(Error::TransactionError);
+        }
 
-        /*
-        let payload = Payload::from_tx_tryte_str(tx_trytes, PAYLOAD.tryte_offset);
-        
-        Self {
+        let trits = tx_trytes.to_string().trits();
+
+        Self::from_trits_view(
+            Trits::<T1B1>::try_from(&trits[..]).map_err(|_| Error::TransactionError)?,
+        )
+    }
+
+    /// Create a `Transaction` from the `TRANSACTION_BYTE_LEN` bytes of its trits packed in T5B1, e.g. as stored.
+    pub fn from_t5b1(bytes: &[u8]) -> Result<Self> {
+        // Every byte is reinterpreted as the signed byte of packed trits it was written from
+        let bytes = bytes.iter().map(|&byte| byte as i8).collect::<Vec<_>>();
+
+        Self::from_trits_view(
+            Trits::<T5B1>::try_from_raw(&bytes, TRANSACTION_TRIT_LEN)
+                .map_err(|_| Error::TransactionError)?,
+        )
+    }
+
+    /// Reads every field straight out of the trits of a whole transaction, whatever their encoding.
+    fn from_trits_view<E: Encoding>(trits: Trits<'_, E>) -> Result<Self>
+    where
+        i64: for<'a> TryFrom<Trits<'a, E>>,
+        u64: for<'a> TryFrom<Trits<'a, E>>,
+        usize: for<'a> TryFrom<Trits<'a, E>>,
+    {
+        let read_trytes = |field: &Field, trytes: &mut [Tryte]| {
+            for (tryte, field_tryte) in trytes.iter_mut().zip(field.trits(trits).trytes()) {
+                *tryte = char::from(field_tryte);
+            }
+        };
+
+        let mut payload = Payload::default();
+        read_trytes(&PAYLOAD, &mut payload.0);
+        let mut address = Address::default();
+        read_trytes(&ADDRESS, &mut address.0);
+        let mut obsolete_tag = Tag::default();
+        read_trytes(&OBSOLETE_TAG, &mut obsolete_tag.0);
+        let mut bundle_hash = Hash::default();
+        read_trytes(&BUNDLE_HASH, &mut bundle_hash.0);
+        let mut trunk_hash = Hash::default();
+        read_trytes(&TRUNK_HASH, &mut trunk_hash.0);
+        let mut branch_hash = Hash::default();
+        read_trytes(&BRANCH_HASH, &mut branch_hash.0);
+        let mut tag = Tag::default();
+        read_trytes(&TAG, &mut tag.0);
+        let mut nonce = Nonce::default();
+        read_trytes(&NONCE, &mut nonce.0);
+
+        Ok(Self {
             payload,
+            address,
+            value: Value(field_number(trits, &VALUE)?),
+            obsolete_tag,
+            timestamp: Timestamp(field_number(trits, &TIMESTAMP)?),
+            index: Index(field_number(trits, &INDEX)?),
+            last_index: Index(field_number(trits, &LAST_INDEX)?),
+            bundle_hash,
+            trunk_hash,
+            branch_hash,
+            tag,
+            attachment_ts: Timestamp(field_number(trits, &ATTACHMENT_TS)?),
+            attachment_lbts: Timestamp(field_number(trits, &ATTACHMENT_LBTS)?),
+            attachment_ubts: Timestamp(field_number(trits, &ATTACHMENT_UBTS)?),
+            nonce,
+        })
+    }
+
+    /// Create a `Transaction` from its `TRANSACTION_TRIT_LEN` trits.
+    pub fn from_trits(trits: &[Trit]) -> Result<Self> {
+        if trits.len() != TRANSACTION_TRIT_LEN {
+            return Err(Error::TransactionError);
         }
-        */
 
-        unimplemented!()
+        let tx_trytes = trits.trytes().map_err(|_| Error::TransactionError)?;
+
+        Self::from_tryte_str(&tx_trytes)
+    }
+
+    /// The `TRANSACTION_TRIT_LEN` trits of the transaction.
+    pub fn to_trits(&self) -> Vec<Trit> {
+        let mut trits = vec![0; TRANSACTION_TRIT_LEN];
+
+        let write = |trits: &mut [Trit], field: &Field, value: Vec<Trit>| {
+            trits[field.trit_offset.start..][..field.trit_offset.length].copy_from_slice(&value)
+        };
+        let trytes = |trytes: &[Tryte]| trytes.iter().collect::<String>().trits();
+        // A number that doesn't fit its field wraps around
+        let number = |field: &Field, value: i128| {
+            let mut trits = TritBuf::<T1B1>::from(value).into_bytes();
+            trits.resize(field.trit_offset.length, 0);
+            trits
+        };
+
+        write(&mut trits, &PAYLOAD, trytes(&self.payload.0));
+        write(&mut trits, &ADDRESS, trytes(&self.address.0));
+        write(&mut trits, &VALUE, number(&VALUE, self.value.0.into()));
+        write(&mut trits, &OBSOLETE_TAG, trytes(&self.obsolete_tag.0));
+        write(
+            &mut trits,
+            &TIMESTAMP,
+            number(&TIMESTAMP, self.timestamp.0 as i128),
+        );
+        write(&mut trits, &INDEX, number(&INDEX, self.index.0 as i128));
+        write(
+            &mut trits,
+            &LAST_INDEX,
+            number(&LAST_INDEX, self.last_index.0 as i128),
+        );
+        write(&mut trits, &BUNDLE_HASH, trytes(&self.bundle_hash.0));
+        write(&mut trits, &TRUNK_HASH, trytes(&self.trunk_hash.0));
+        write(&mut trits, &BRANCH_HASH, trytes(&self.branch_hash.0));
+        write(&mut trits, &TAG, trytes(&self.tag.0));
+        write(
+            &mut trits,
+            &ATTACHMENT_TS,
+            number(&ATTACHMENT_TS, self.attachment_ts.0 as i128),
+        );
+        write(
+            &mut trits,
+            &ATTACHMENT_LBTS,
+            number(&ATTACHMENT_LBTS, self.attachment_lbts.0 as i128),
+        );
+        write(
+            &mut trits,
+            &ATTACHMENT_UBTS,
+            number(&ATTACHMENT_UBTS, self.attachment_ubts.0 as i128),
+        );
+        write(&mut trits, &NONCE, trytes(&self.nonce.0));
+
+        trits
+    }
+
+    /// Searches for a nonce with `pearldiver` and returns the transaction stamped with it, if one was found.
+    pub fn search_nonce(&self, pearldiver: &mut PearlDiver) -> Option<PowOutcome<Transaction>> {
+        // Can't fail, the trits are exactly one transaction long
+        let input = InputTrits::try_from(&self.to_trits()[..]).unwrap();
+
+        pearldiver
+            .search_transaction(input)
+            // Can't fail, the stamped trits only differ from a valid transaction by their nonce
+            .map(|outcome| outcome.map(|input| Transaction::from_trits(&input[..]).unwrap()))
     }
+
     /// Create a `Transaction` from a reader object.
     pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
         Err(Error::TransactionError)
@@ -247,20 +389,45 @@ impl Transaction {
 
 impl std::fmt::Debug for Transaction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "address={:?}\nvalue={:?}\ntimestamp={:?}\nindex={:?}\nlast_index={:?}\ntag={:?}\nbundle_hash={:?}\ntrunk_hash={:?}\nbranch_hash={:?}\nnonce={:?}", 
-        self.address, 
-        self.value, 
-        self.timestamp, 
-        self.index, 
-        self.last_index, 
-        self.tag, 
-        self.bundle_hash, 
-        self.trunk_hash, 
-        self.branch_hash, 
+        write!(f, "address={:?}\nvalue={:?}\ntimestamp={:?}\nindex={:?}\nlast_index={:?}\ntag={:?}\nbundle_hash={:?}\ntrunk_hash={:?}\nbranch_hash={:?}\nnonce={:?}",
+        self.address,
+        self.value,
+        self.timestamp,
+        self.index,
+        self.last_index,
+        self.tag,
+        self.bundle_hash,
+        self.trunk_hash,
+        self.branch_hash,
         self.nonce)
     }
 }
 
+/// Parses `s` into the trytes of a field, padded with '9's.
+fn parse_trytes(s: &str, trytes: &mut [Tryte]) -> std::result::Result<(), ternary::Error> {
+    let parsed = s.parse::<TryteString>()?.pad(trytes.len())?;
+    for (tryte, c) in trytes.iter_mut().zip(parsed.as_str().chars()) {
+        *tryte = c;
+    }
+
+    Ok(())
+}
+
+/// The number in a field of the trits of a transaction, which has to fit `T`.
+fn field_number<T, E: Encoding>(trits: Trits<'_, E>, field: &Field) -> Result<T>
+where
+    T: for<'a> TryFrom<Trits<'a, E>>,
+{
+    T::try_from(field.trits(trits)).map_err(|_| Error::TransactionError)
+}
+
+/// The trits of a number that fills a field, which it has to fit.
+pub(crate) fn number_trits<I: Into<i128>>(field: &Field, value: I) -> Result<Vec<Trit>> {
+    TritBuf::<T1B1>::try_from_int(value, field.trit_offset.length)
+        .map(TritBuf::into_bytes)
+        .map_err(|_| Error::TransactionError)
+}
+
 pub struct TransactionBuilder {
     payload: Option<Payload>,
     address: Option<Address>,
@@ -404,14 +571,16 @@ impl TransactionBuilder {
 mod should {
     use super::*;
 
+    use pow::{Cores, Difficulty};
+
     #[test]
     fn create_transaction_from_builder() {
         let mut builder = TransactionBuilder::default();
         builder
             .value(Value(10))
-            .address(Address::from_str("ME"))
-            .tag(Tag::from_str("HELLO"))
-            .nonce(Nonce::from_str("ABCDEF"));
+            .address(Address::from_str("ME").unwrap())
+            .tag(Tag::from_str("HELLO").unwrap())
+            .nonce(Nonce::from_str("ABCDEF").unwrap());
 
         let tx = builder.build();
         println!("{:?}", tx);
@@ -419,9 +588,101 @@ mod should {
 
     #[test]
     fn create_transaction_from_tryte_string() {
-        //let tx = Transaction::from_tryte_str(TX_TRYTES);
-        
+        let tx = Transaction::from_tryte_str(TX_TRYTES).unwrap();
+
+        assert_eq!(tx.value().0, -7_297_419_313);
+        assert_eq!(tx.index().0, 1);
+        assert_eq!(tx.last_index().0, 2);
+        assert_eq!(format!("{:?}", tx.tag()), "TRINITY99999999999999999999");
+        assert_eq!(
+            format!("{:?}", tx.trunk_hash()),
+            &TX_TRYTES[TRUNK_HASH.tryte_offset.start..][..TRUNK_HASH.tryte_offset.length]
+        );
+        assert_eq!(
+            format!("{:?}", tx.nonce()),
+            &TX_TRYTES[NONCE.tryte_offset.start..]
+        );
+    }
+
+    #[test]
+    fn convert_to_and_from_trits() {
+        let trits = Transaction::from_tryte_str(TX_TRYTES).unwrap().to_trits();
+
+        assert_eq!(trits.trytes().unwrap(), TX_TRYTES);
+        assert_eq!(Transaction::from_trits(&trits).unwrap().to_trits(), trits);
+        assert!(Transaction::from_trits(&trits[1..]).is_err());
+    }
+
+    #[test]
+    fn read_fields_from_t5b1_bytes() {
+        let trits = Transaction::from_tryte_str(TX_TRYTES).unwrap().to_trits();
+        let bytes = Trits::<T1B1>::try_from(&trits[..])
+            .unwrap()
+            .encode::<T5B1>()
+            .into_bytes();
+        let packed = Trits::<T5B1>::try_from_raw(&bytes, TRANSACTION_TRIT_LEN).unwrap();
+
+        assert_eq!(bytes.len(), TRANSACTION_BYTE_LEN);
+        assert_eq!(i64::try_from(VALUE.trits(packed)).unwrap(), -7_297_419_313);
+        assert_eq!(
+            ADDRESS
+                .trits(packed)
+                .trytes()
+                .map(char::from)
+                .collect::<String>(),
+            &TX_TRYTES[ADDRESS.tryte_offset.start..][..ADDRESS.tryte_offset.length]
+        );
+
+        // As stored, e.g. by bee-storage
+        let bytes = bytes.iter().map(|&byte| byte as u8).collect::<Vec<_>>();
+        let tx = Transaction::from_t5b1(&bytes).unwrap();
+        assert_eq!(tx.to_trits(), trits);
+        assert!(Transaction::from_t5b1(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn stamp_nonce_into_transaction() {
+        let tx = Transaction::from_tryte_str(TX_TRYTES).unwrap();
+        let mut pearldiver = PearlDiver::new(Cores::from(2), Difficulty::from(5));
+
+        let outcome = tx.search_nonce(&mut pearldiver).unwrap();
+
+        assert!(outcome.trailing_zeros >= 5);
+        assert_eq!(
+            outcome.transaction.to_trits()[..NONCE.trit_offset.start],
+            tx.to_trits()[..NONCE.trit_offset.start]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_tryte_string() {
+        assert!(Transaction::from_tryte_str(&TX_TRYTES[1..]).is_err());
+        assert!(Transaction::from_tryte_str(&TX_TRYTES.replace("TRINITY", "trinity")).is_err());
+    }
+
+    #[test]
+    fn reject_invalid_field_strings() {
+        assert_eq!(
+            Tag::from_str("HELLO-BEE").unwrap_err(),
+            ternary::Error::InvalidTryteAt {
+                index: 5,
+                character: '-'
+            }
+        );
+        assert_eq!(
+            Nonce::from_str(&"A".repeat(28)).unwrap_err(),
+            ternary::Error::TooLong { len: 28, max: 27 }
+        );
+    }
+
+    #[test]
+    fn reject_negative_index() {
+        let mut trytes = TX_TRYTES.to_string();
+        // 'Z' is the tryte -1
+        trytes.replace_range(INDEX.tryte_offset.start..INDEX.tryte_offset.start + 1, "Z");
+
+        assert!(Transaction::from_tryte_str(&trytes).is_err());
     }
 
     const TX_TRYTES: &str = "SEGQSWYCJHRLJYEGZLRYQAZPLVRAYIWGWJUMFFX99UZUKBQNFYAOQLOFARIKNEBKDRHJJWDJARXTNPHPAODJRSGJBVVYBVJHZALJWDCJHZRSACOVCVVAVHZVTPFTAJWVGFSVLSYXHNNXEGSMJHDBZKGFQNYJJJBAPDHFFGZ9POSOMWTDPGXI9KQRLMUVWNEQDANMXROVORJVALWVGDDJAFOOBXUKVCCIVXSSHZUCZV9XVBASLWX9NXPWGMGYCRD9ILQMKIGPBGGMKAIJKNALBLABATYFVIRBKTXTWNUZAUXRASB9EEIQHWBD9ZYUDBUPBSWXVYXQXECRCHQAYH9ZBUZBASPOIGBSGWJYFKFRITUBVMCYGCMAPTXOIWEVTUXSUOUPTUQOPMMPUTHXMOP9CW9THAZXEPMOMNEOBLUBPOAIOBEBERRZCIKHSTDWUSUPUWNJOCLNZDCEKWWAAJDPJXJEHHSYFN9MH9BGUDQ9CSZBIHRC9PSQJPGKH9ILZDWUWLEKWFKUFFFIMOQKRMKOYXEJHXLCEGCGGKHGJUHOXINSWCKRNMUNAJDCVLZGEBII9ASTYFTDYDZIZSNHIWHSQ9HODQMVNDKMKHCFDXIIGDIVJSBOOE9GRIXCD9ZUTWCUDKFTETSYSRBQABXCXZFOWQMQFXHYZWD9JZXUWHILMRNWXSGUMIIXZYCTWWHCWMSSTCNSQXQXMQPTM9MOQMIVDYNNARDCVNQEDTBKWOIOSKPKPOZHJGJJGNYWQWUWAZMBZJ9XEJMRVRYFQPJ9NOIIXEGIKMMN9DXYQUILRSCSJDIDN9DCTFGQIYWROZQIEQTKMRVLGGDGA9UVZPNRGSVTZYAPMWFUWDEUULSEEGAGITPJQ9DBEYEN9NVJPUWZTOTJHEQIXAPDOICBNNCJVDNM9YRNXMMPCOYHJDUFNCYTZGRCBZKOLHHUK9VOZWHEYQND9WUHDNGFTAS99MRCAU9QOYVUZKTIBDNAAPNEZBQPIRUFUMAWVTCXSXQQIYQPRFDUXCLJNMEIKVAINVCCZROEWEX9XVRM9IHLHQCKC9VLK9ZZWFBJUZKGJCSOPQPFVVAUDLKFJIJKMLZXFBMXLMWRSNDXRMMDLE9VBPUZB9SVLTMHA9DDDANOKIPY9ULDWAKOUDFEDHZDKMU9VMHUSFG9HRGZAZULEJJTEH9SLQDOMZTLVMBCXVNQPNKXRLBOUCCSBZRJCZIUFTFBKFVLKRBPDKLRLZSMMIQNMOZYFBGQFKUJYIJULGMVNFYJWPKPTSMYUHSUEXIPPPPPJTMDQLFFSFJFEPNUBDEDDBPGAOEJGQTHIWISLRDAABO9H9CSIAXPPJYCRFRCIH9TVBZKTCK9SPQZUYMUOKMZYOMPRHRGF9UAKZTZZG9VVVTIHMSNDREUOUOSLKUHTNFXTNSJVPVWCQXUDIMJIAMBPXUGBNDTBYPKYQYJJCDJSCTTWHOJKORLHGKRJMDCMRHSXHHMQBFJWZWHNUHZLYOAFQTRZFXDBYASYKWEVHKYDTJIAUKNCCEPSW9RITZXBOFKBAQOWHKTALQSCHARLUUGXISDMBVEUKOVXTKTEVKLGYVYHPNYWKNLCVETWIHHVTBWT9UPMTQWBZPRPRSISUBIBECVDNIZQULAGLONGVFLVZPBMHJND9CEVIXSYGFZAGGN9MQYOAKMENSEOGCUNKEJTDLEDCD9LGKYANHMZFSSDDZJKTKUJSFL9GYFDICTPJEPDSBXDQTARJQEWUVWDWSQPKIHPJONKHESSQH9FNQEO9WUCFDWPPPTIQPWCVDYTTWPLCJJVYNKE9ZEJNQBEJBMDBLNJKQDOQOHVS9VY9UPSU9KZVDFOESHNRRWBK9EZCYALAUYFGPCEWJQDXFENSNQEAUWDXJGOMCLQUQWMCPHOBZZ9SZJ9KZXSHDLPHPNYMVUJQSQETTN9SG9SIANJHWUYQXZXAJLYHCZYRGITZYQLAAYDVQVNKCDIYWAYBAFBMAYEAEAGMTJGJRSNHBHCEVIQRXEFVWJWOPU9FPDOWIFL9EWGHICRBNRITJDZNYACOGTUDBZYIYZZWAOCDBQFFNTTSTGKECWTVWZSPHX9HNRUYEAEWXENEIDLVVFMZFVPUNHMQPAIOKVIBDIHQIHFGRJOHHONPLGBSJUD9HHDTQQUZN9NVJYOAUMXMMOCNUFLZ9BAJSZMDMPQHPWSFVWOJQDPHV9DYSQPIBL9LYZHQKKOVF9TFVTTXQEUWFQSLGLVTGK99VSUEDXIBIWCQHDQQSQLDHZ9999999999999999999TRINITY99999999999999999999TNXSQ9D99A99999999B99999999MXKZAGDGKVADXOVCAXEQYZGOGQKDLKIUPYXIL9PXYBQXGYDEGNXTFURSWQYLJDFKEV9VVBBQLTLHIBTFYOGBHPUUHS9CKWSAPIMDIRNSUJ9CFPGKTUFAGQYVMFKOZSVAHIFJXWCFBZLICUWF9GNDZWCOWDUIIZ9999OXNRVXLBKJXEZMVABR9UQBVSTBDFSAJVRRNFEJRL9UFTOFPJHQMQKAJHDBIQAETS9OUVTQ9DSPAOZ9999TRINITY99999999999999999999LPZYMWQME999999999MMMMMMMMMDTIZE9999999999999999999999";
-}
\ No newline at end of file
+}
@@ -1,6 +1,6 @@
 pub const ENV_VAR: &str = "BEE";
 pub const DEBUG: &str = "debug";
-pub const CONFIG: &str = "./config";
+pub const CONFIG: &str = "./config.toml";
 
 pub const TRYTE_ZERO: char = '9';
 
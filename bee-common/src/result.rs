@@ -1,8 +1,24 @@
 #[derive(Debug)]
 pub enum Error {
-    ConfigError,
+    ConfigError(String),
     NetworkError,
+    RuntimeError(String),
+    StorageError(String),
     TransactionError,
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::ConfigError(details) => write!(f, "configuration error: {}", details),
+            Error::NetworkError => write!(f, "network error"),
+            Error::RuntimeError(details) => write!(f, "runtime error: {}", details),
+            Error::StorageError(details) => write!(f, "storage error: {}", details),
+            Error::TransactionError => write!(f, "transaction error"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub type Result<T> = std::result::Result<T, Error>;
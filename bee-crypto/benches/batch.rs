@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate criterion;
+
+use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+use criterion::{Benchmark, Criterion, Throughput};
+use crypto::curl::constants::CURL_P_81;
+use crypto::hashes_preview::{BatchHasher, CurlP, Sponge, Trits, TritsMut, BATCH_SIZE};
+
+/// A full batch of arbitrary, distinct transactions.
+fn transactions() -> Vec<Vec<i8>> {
+    (0..BATCH_SIZE)
+        .map(|seed| {
+            (0..TRANSACTION_TRIT_LEN)
+                .map(|i| ((i * i + seed * i + seed) % 3) as i8 - 1)
+                .collect()
+        })
+        .collect()
+}
+
+fn scalar(transactions: &[Vec<i8>]) {
+    let mut curlp = CurlP::new(CURL_P_81);
+    let mut hash = [0; HASH_TRIT_LEN];
+
+    for transaction in transactions {
+        curlp
+            .digest_into(
+                &Trits::from_i8_unchecked(transaction),
+                &mut TritsMut::from_i8_unchecked(&mut hash),
+            )
+            .unwrap();
+    }
+}
+
+fn batched(hasher: &mut BatchHasher, transactions: &[Vec<i8>]) {
+    for transaction in transactions {
+        hasher.add(&Trits::from_i8_unchecked(transaction)).unwrap();
+    }
+    hasher.hash_batched();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let scalar_transactions = transactions();
+    let batched_transactions = transactions();
+
+    c.bench(
+        "CurlP81 of 64 transactions",
+        Benchmark::new("scalar", move |b| b.iter(|| scalar(&scalar_transactions)))
+            .with_function("batched", move |b| {
+                let mut hasher = BatchHasher::new(TRANSACTION_TRIT_LEN, CURL_P_81);
+                b.iter(|| batched(&mut hasher, &batched_transactions))
+            })
+            .throughput(Throughput::Elements(BATCH_SIZE as u32))
+            .sample_size(20),
+    );
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate criterion;
+
+use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+use criterion::{Benchmark, Criterion, Throughput};
+use crypto::curl::constants::CURL_P_81;
+use crypto::hashes_preview::{
+    CurlP, Sponge, Trits, TritsMut, Troika, ROUND_CONSTANT_LEN, TROIKA_ROUNDS,
+};
+
+/// Arbitrary round constants, NOT the reference ones; the constants are only added to the state, so they don't change
+/// the cost of the permutation being timed.
+fn round_constants() -> Vec<[u8; ROUND_CONSTANT_LEN]> {
+    (0..TROIKA_ROUNDS)
+        .map(|round| {
+            let mut constant = [0; ROUND_CONSTANT_LEN];
+            for (i, trit) in constant.iter_mut().enumerate() {
+                *trit = ((i * i + round * i + round) % 3) as u8;
+            }
+            constant
+        })
+        .collect()
+}
+
+fn transaction() -> Vec<i8> {
+    (0..TRANSACTION_TRIT_LEN)
+        .map(|i| ((i * i + i) % 3) as i8 - 1)
+        .collect()
+}
+
+fn hash<S: Sponge>(sponge: &mut S, transaction: &[i8]) {
+    let mut hash = [0; HASH_TRIT_LEN];
+
+    sponge
+        .digest_into(
+            &Trits::from_i8_unchecked(transaction),
+            &mut TritsMut::from_i8_unchecked(&mut hash),
+        )
+        .unwrap();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let curlp_transaction = transaction();
+    let troika_transaction = transaction();
+
+    c.bench(
+        "Hash of a transaction",
+        Benchmark::new("CurlP81", move |b| {
+            let mut curlp = CurlP::new(CURL_P_81);
+            b.iter(|| hash(&mut curlp, &curlp_transaction))
+        })
+        .with_function("Troika", move |b| {
+            let mut troika = Troika::new(TROIKA_ROUNDS, &round_constants()).unwrap();
+            b.iter(|| hash(&mut troika, &troika_transaction))
+        })
+        .throughput(Throughput::Elements(1))
+        .sample_size(20),
+    );
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
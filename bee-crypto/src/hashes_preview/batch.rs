@@ -0,0 +1,360 @@
+//! Batched `CurlP`, hashing up to 64 inputs of the same length at once.
+//!
+//! The inputs are bit-sliced into a `BctState`, like the states of the proof of work, bit `n` of its words holding
+//! the trits of the `n`th input.
+
+use super::bct::BctState;
+use super::{Trits, TritsBuf, HASH_LEN};
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The maximum number of inputs hashed at once, one per bit of the words of the state.
+pub const BATCH_SIZE: usize = 64;
+
+const BITS_1: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+
+/// The errors of `BatchHasher` and `BatchQueue`.
+#[derive(Debug, PartialEq)]
+pub enum BatchError {
+    /// All inputs of a batch must have the same length, `expected`, but this one was `len` trits long.
+    InvalidLength { expected: usize, len: usize },
+    /// The batch already holds `BATCH_SIZE` inputs.
+    Full,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::InvalidLength { expected, len } => write!(
+                f,
+                "invalid input length {}, the batch hashes inputs of {} trits",
+                len, expected
+            ),
+            BatchError::Full => write!(f, "the batch already holds {} inputs", BATCH_SIZE),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Collects inputs of the same length and hashes them with `CurlP` in batches of up to `BATCH_SIZE`.
+///
+/// ```
+/// use crypto::curl::constants::CURL_P_81;
+/// use crypto::hashes_preview::{BatchHasher, Trits};
+///
+/// let mut hasher = BatchHasher::new(243, CURL_P_81);
+/// hasher.add(&Trits::from_i8_unchecked(&[1; 243])).unwrap();
+/// hasher.add(&Trits::from_i8_unchecked(&[-1; 243])).unwrap();
+///
+/// let hashes = hasher.hash_batched();
+/// assert_eq!(hashes.len(), 2);
+/// ```
+pub struct BatchHasher {
+    input_len: usize,
+    rounds: usize,
+    inputs: Vec<TritsBuf>,
+    state: Box<BctState>,
+    scratchpad: Box<BctState>,
+}
+
+impl BatchHasher {
+    /// Create a hasher for inputs of `input_len` trits, with `rounds` rounds of `CurlP`.
+    pub fn new(input_len: usize, rounds: usize) -> Self {
+        Self {
+            input_len,
+            rounds,
+            inputs: Vec::with_capacity(BATCH_SIZE),
+            state: Box::new(BctState::new(BITS_1)),
+            scratchpad: Box::new(BctState::new(BITS_1)),
+        }
+    }
+
+    /// The length of the inputs, in trits.
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// The number of inputs waiting to be hashed.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.inputs.len() == BATCH_SIZE
+    }
+
+    /// Add an input to the batch.
+    pub fn add(&mut self, input: &Trits) -> Result<(), BatchError> {
+        if input.len() != self.input_len {
+            return Err(BatchError::InvalidLength {
+                expected: self.input_len,
+                len: input.len(),
+            });
+        }
+        if self.is_full() {
+            return Err(BatchError::Full);
+        }
+
+        self.inputs.push(TritsBuf::from_i8_unchecked(input.0));
+
+        Ok(())
+    }
+
+    /// Hash all the inputs of the batch at once and empty it. The hashes are returned in the order the inputs were
+    /// added.
+    pub fn hash_batched(&mut self) -> Vec<TritsBuf> {
+        let inputs = std::mem::take(&mut self.inputs);
+
+        self.state.reset(BITS_1);
+
+        let mut offset = 0;
+        while offset < self.input_len {
+            let chunk_len = HASH_LEN.min(self.input_len - offset);
+
+            for i in 0..chunk_len {
+                let (mut hi, mut lo) = (0, 0);
+                for (lane, input) in inputs.iter().enumerate() {
+                    match input.0[offset + i] {
+                        1 => hi |= 1 << lane,
+                        -1 => lo |= 1 << lane,
+                        _ => {
+                            hi |= 1 << lane;
+                            lo |= 1 << lane;
+                        }
+                    }
+                }
+                self.state.set(i, hi, lo);
+            }
+
+            self.state.transform(&mut self.scratchpad, self.rounds);
+            offset += chunk_len;
+        }
+
+        (0..inputs.len())
+            .map(|lane| {
+                let trits = (0..HASH_LEN)
+                    .map(|i| {
+                        let (hi, lo) = self.state.get(i);
+                        match ((hi >> lane) & 1, (lo >> lane) & 1) {
+                            (1, 0) => 1,
+                            (0, 1) => -1,
+                            (_, _) => 0,
+                        }
+                    })
+                    .collect();
+                TritsBuf(trits)
+            })
+            .collect()
+    }
+}
+
+/// Queues identified inputs, e.g. transactions received from neighbors, and hashes them with a `BatchHasher` when
+/// the batch is full or when the oldest input has waited `max_wait`, whichever comes first.
+///
+/// The queue doesn't own a timer: the caller waits until `deadline` and then calls `poll`, e.g. from its event loop.
+/// Pushing never hashes, so that an asynchronous caller can move the queue to a blocking thread for the `flush` of a
+/// full batch.
+pub struct BatchQueue<T> {
+    hasher: BatchHasher,
+    ids: Vec<T>,
+    max_wait: Duration,
+    oldest: Option<Instant>,
+}
+
+impl<T> BatchQueue<T> {
+    /// Create a queue for inputs of `input_len` trits, hashed with `rounds` rounds of `CurlP`.
+    pub fn new(input_len: usize, rounds: usize, max_wait: Duration) -> Self {
+        Self {
+            hasher: BatchHasher::new(input_len, rounds),
+            ids: Vec::with_capacity(BATCH_SIZE),
+            max_wait,
+            oldest: None,
+        }
+    }
+
+    /// The number of queued inputs.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The instant at which the queue should be flushed, if there is anything to flush.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.oldest.map(|oldest| oldest + self.max_wait)
+    }
+
+    /// Whether the batch is full, and has to be flushed before more inputs can be pushed.
+    pub fn is_full(&self) -> bool {
+        self.hasher.is_full()
+    }
+
+    /// Queue `input`, identified by `id`. Returns whether the batch is full now.
+    pub fn push(&mut self, id: T, input: &Trits) -> Result<bool, BatchError> {
+        self.hasher.add(input)?;
+        self.ids.push(id);
+        self.oldest.get_or_insert_with(Instant::now);
+
+        Ok(self.is_full())
+    }
+
+    /// Flush the queue if its deadline has passed.
+    pub fn poll(&mut self) -> Option<Vec<(T, TritsBuf)>> {
+        match self.deadline() {
+            Some(deadline) if deadline <= Instant::now() => Some(self.flush()),
+            _ => None,
+        }
+    }
+
+    /// Hash all queued inputs now, regardless of the deadline.
+    pub fn flush(&mut self) -> Vec<(T, TritsBuf)> {
+        self.oldest = None;
+
+        std::mem::take(&mut self.ids)
+            .into_iter()
+            .zip(self.hasher.hash_batched())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::curl::constants::{CURL_P_27, CURL_P_81};
+    use crate::hashes_preview::{CurlP, Sponge, TritsMut};
+
+    fn input(len: usize, seed: usize) -> Vec<i8> {
+        (0..len)
+            .map(|i| ((i * i + seed * i + seed) % 3) as i8 - 1)
+            .collect()
+    }
+
+    fn curlp(input: &[i8], rounds: usize) -> Vec<i8> {
+        let mut curlp = CurlP::new(rounds);
+        let mut hash = vec![0; HASH_LEN];
+
+        curlp.absorb(&Trits::from_i8_unchecked(input)).unwrap();
+        curlp
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+            .unwrap();
+
+        hash
+    }
+
+    #[test]
+    fn batched_equals_scalar() {
+        // A whole transaction, a single chunk, and a partial last chunk
+        for &(len, rounds, count) in [
+            (8019, CURL_P_81, 64),
+            (243, CURL_P_27, 5),
+            (300, CURL_P_81, 17),
+        ]
+        .iter()
+        {
+            let mut hasher = BatchHasher::new(len, rounds);
+            let inputs = (0..count).map(|seed| input(len, seed)).collect::<Vec<_>>();
+
+            for input in &inputs {
+                hasher.add(&Trits::from_i8_unchecked(input)).unwrap();
+            }
+            let hashes = hasher.hash_batched();
+
+            assert!(hasher.is_empty());
+            assert_eq!(hashes.len(), count);
+            for (input, hash) in inputs.iter().zip(hashes.iter()) {
+                assert_eq!(hash.as_i8_slice(), &curlp(input, rounds)[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn hash_consecutive_batches() {
+        let mut hasher = BatchHasher::new(486, CURL_P_81);
+
+        for seed in 0..3 {
+            let input = input(486, seed);
+            hasher.add(&Trits::from_i8_unchecked(&input)).unwrap();
+            assert_eq!(
+                hasher.hash_batched()[0].as_i8_slice(),
+                &curlp(&input, CURL_P_81)[..]
+            );
+        }
+    }
+
+    #[test]
+    fn reject_invalid_inputs() {
+        let mut hasher = BatchHasher::new(243, CURL_P_81);
+
+        assert_eq!(
+            hasher.add(&Trits::from_i8_unchecked(&[0; 242])),
+            Err(BatchError::InvalidLength {
+                expected: 243,
+                len: 242
+            })
+        );
+        for _ in 0..BATCH_SIZE {
+            hasher.add(&Trits::from_i8_unchecked(&[0; 243])).unwrap();
+        }
+        assert_eq!(
+            hasher.add(&Trits::from_i8_unchecked(&[0; 243])),
+            Err(BatchError::Full)
+        );
+    }
+
+    #[test]
+    fn flush_queue_when_full() {
+        let mut queue = BatchQueue::new(243, CURL_P_81, Duration::from_secs(3600));
+
+        for id in 0..BATCH_SIZE - 1 {
+            let input = input(243, id);
+            assert_eq!(queue.push(id, &Trits::from_i8_unchecked(&input)), Ok(false));
+        }
+        assert_eq!(queue.poll(), None);
+
+        let input = input(243, BATCH_SIZE);
+        assert_eq!(
+            queue.push(BATCH_SIZE - 1, &Trits::from_i8_unchecked(&input)),
+            Ok(true)
+        );
+        assert_eq!(
+            queue.push(BATCH_SIZE, &Trits::from_i8_unchecked(&input)),
+            Err(BatchError::Full)
+        );
+        let hashes = queue.flush();
+
+        assert!(queue.is_empty());
+        assert_eq!(queue.deadline(), None);
+        assert_eq!(hashes.len(), BATCH_SIZE);
+        assert_eq!(hashes[BATCH_SIZE - 1].0, BATCH_SIZE - 1);
+        assert_eq!(
+            hashes[BATCH_SIZE - 1].1.as_i8_slice(),
+            &curlp(&input, CURL_P_81)[..]
+        );
+    }
+
+    #[test]
+    fn flush_queue_after_deadline() {
+        let mut queue = BatchQueue::new(243, CURL_P_81, Duration::from_millis(0));
+
+        assert_eq!(queue.poll(), None);
+        queue
+            .push("tx", &Trits::from_i8_unchecked(&input(243, 1)))
+            .unwrap();
+        assert!(queue.deadline().is_some());
+
+        let hashes = queue.poll().unwrap();
+
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes[0].0, "tx");
+        assert_eq!(queue.poll(), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! Bit-sliced `CurlP` states, transforming up to 64 states at once.
+//!
+//! Every trit of the `CurlP` state is represented by a `hi` and a `lo` word, and bit `n` of both words holds the trit
+//! of the `n`th state. A `1` is encoded as `(1, 0)`, a `-1` as `(0, 1)` and a `0` as `(1, 1)`. A single
+//! transformation of the 64-bit words then transforms all the states. Both the proof of work and `BatchHasher` are
+//! built on it.
+
+use super::curlp::INDICES;
+use super::HASH_LEN;
+use crate::curl::constants::CURL_STAT_TRIT_LEN as STATE_LEN;
+
+/// 64 bit-sliced `CurlP` states.
+#[derive(Clone)]
+pub struct BctState {
+    hi: [u64; STATE_LEN],
+    lo: [u64; STATE_LEN],
+}
+
+impl BctState {
+    /// Create a state whose `hi` and `lo` words are all `value`.
+    pub fn new(value: u64) -> Self {
+        Self {
+            hi: [value; STATE_LEN],
+            lo: [value; STATE_LEN],
+        }
+    }
+
+    /// Set all `hi` and `lo` words to `value`.
+    pub fn reset(&mut self, value: u64) {
+        self.hi.iter_mut().for_each(|w| *w = value);
+        self.lo.iter_mut().for_each(|w| *w = value);
+    }
+
+    /// The `hi` and `lo` words of the trit at `index`.
+    pub fn get(&self, index: usize) -> (u64, u64) {
+        (self.hi[index], self.lo[index])
+    }
+
+    pub fn set(&mut self, index: usize, hi: u64, lo: u64) {
+        self.hi[index] = hi;
+        self.lo[index] = lo;
+    }
+
+    pub fn copy_from(&mut self, other: &Self) {
+        self.hi.copy_from_slice(&other.hi);
+        self.lo.copy_from_slice(&other.lo);
+    }
+
+    /// Apply `rounds` rounds of `CurlP` to the whole state, using `scratchpad` as the buffer of every other round.
+    pub fn transform(&mut self, scratchpad: &mut Self, rounds: usize) {
+        for round in 0..rounds {
+            // Alternate between both states, starting from `self`
+            let (src, dst) = if round.is_multiple_of(2) {
+                (&*self, &mut *scratchpad)
+            } else {
+                (&*scratchpad, &mut *self)
+            };
+            src.round(dst, STATE_LEN);
+        }
+
+        if !rounds.is_multiple_of(2) {
+            self.copy_from(scratchpad);
+        }
+    }
+
+    /// Like `transform`, but only computes the `HASH_LEN` trits of the hash in the last round, so that nothing can be
+    /// absorbed afterwards.
+    pub fn transform_hash(&mut self, scratchpad: &mut Self, rounds: usize) {
+        if rounds == 0 {
+            return;
+        }
+
+        self.transform(scratchpad, rounds - 1);
+        self.round(scratchpad, HASH_LEN);
+
+        self.hi[..HASH_LEN].copy_from_slice(&scratchpad.hi[..HASH_LEN]);
+        self.lo[..HASH_LEN].copy_from_slice(&scratchpad.lo[..HASH_LEN]);
+    }
+
+    /// Compute the first `len` trits of the next round into `dst`.
+    #[inline(always)]
+    fn round(&self, dst: &mut Self, len: usize) {
+        let indices = INDICES[..=len].windows(2);
+
+        for ((lo, hi), index) in dst.lo[..len]
+            .iter_mut()
+            .zip(dst.hi[..len].iter_mut())
+            .zip(indices)
+        {
+            // The indices are those of the state, and checking them would cost a third of the transform
+            let (alpha, kappa, sigma, gamma) = unsafe {
+                (
+                    *self.lo.get_unchecked(index[0]),
+                    *self.hi.get_unchecked(index[0]),
+                    *self.lo.get_unchecked(index[1]),
+                    *self.hi.get_unchecked(index[1]),
+                )
+            };
+
+            let delta = (alpha | !gamma) & (sigma ^ kappa);
+
+            *lo = !delta;
+            *hi = (alpha ^ gamma) | delta;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::curl::constants::{CURL_P_27, CURL_P_81};
+    use crate::hashes_preview::{CurlP, Sponge, Trits, TritsMut};
+
+    const BITS_1: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+
+    /// Sets the trits of every lane to `input`, shifted by the index of the lane.
+    fn state(input: &[i8]) -> BctState {
+        let mut state = BctState::new(BITS_1);
+
+        for i in 0..input.len() {
+            let (mut hi, mut lo) = (0, 0);
+            for lane in 0..64 {
+                match input[(i + lane) % input.len()] {
+                    1 => hi |= 1 << lane,
+                    -1 => lo |= 1 << lane,
+                    _ => {
+                        hi |= 1 << lane;
+                        lo |= 1 << lane;
+                    }
+                }
+            }
+            state.set(i, hi, lo);
+        }
+
+        state
+    }
+
+    fn trit(state: &BctState, index: usize, lane: usize) -> i8 {
+        let (hi, lo) = state.get(index);
+        match ((hi >> lane) & 1, (lo >> lane) & 1) {
+            (1, 0) => 1,
+            (0, 1) => -1,
+            _ => 0,
+        }
+    }
+
+    fn curlp(input: &[i8], rounds: usize) -> Vec<i8> {
+        let mut curlp = CurlP::new(rounds);
+        let mut hash = vec![0; HASH_LEN];
+
+        curlp.absorb(&Trits::from_i8_unchecked(input)).unwrap();
+        curlp
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+            .unwrap();
+
+        hash
+    }
+
+    #[test]
+    fn transform_equals_curlp() {
+        let input = (0..HASH_LEN)
+            .map(|i| ((i * i + 7) % 3) as i8 - 1)
+            .collect::<Vec<_>>();
+
+        // An even number of rounds ends in the scratchpad
+        for &rounds in &[26, CURL_P_27, CURL_P_81] {
+            let mut full = state(&input);
+            let mut hash = full.clone();
+            let mut scratchpad = BctState::new(BITS_1);
+
+            full.transform(&mut scratchpad, rounds);
+            hash.transform_hash(&mut scratchpad, rounds);
+
+            for lane in &[0, 1, 63] {
+                let shifted = (0..HASH_LEN)
+                    .map(|i| input[(i + lane) % HASH_LEN])
+                    .collect::<Vec<_>>();
+                let expected = curlp(&shifted, rounds);
+
+                for (i, expected) in expected.iter().enumerate() {
+                    assert_eq!(trit(&full, i, *lane), *expected);
+                    assert_eq!(trit(&hash, i, *lane), *expected);
+                }
+            }
+        }
+    }
+}
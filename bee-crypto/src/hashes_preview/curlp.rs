@@ -0,0 +1,281 @@
+use super::{Sponge, Trits, TritsBuf, TritsMut, ValidTrits, HASH_LEN};
+use crate::curl::constants::{CURL_P_27, CURL_P_81, CURL_STAT_TRIT_LEN as STATE_LEN, TRUTH_TABLE};
+
+use std::convert::Infallible;
+
+/// The indices of the state trits that feed the substitution box: trit `i` of a round is computed from the trits at
+/// `INDICES[i]` and `INDICES[i + 1]` of the previous round. The bit-sliced transforms, `BctState` and the wider
+/// ones of the proof of work, share them.
+pub const INDICES: [usize; STATE_LEN + 1] = indices();
+
+const fn indices() -> [usize; STATE_LEN + 1] {
+    let mut indices = [0; STATE_LEN + 1];
+    let mut i = 1;
+
+    while i <= STATE_LEN {
+        indices[i] = if indices[i - 1] < 365 {
+            indices[i - 1] + 364
+        } else {
+            indices[i - 1] - 365
+        };
+        i += 1;
+    }
+
+    indices
+}
+
+pub struct CurlP {
+    /// The number of rounds of hashing to apply before a hash is squeezed.
+    rounds: usize,
+
+    /// The internal state.
+    state: TritsBuf,
+
+    /// Workspace for performing transformations
+    work_state: TritsBuf,
+}
+
+impl CurlP {
+    /// Create a new `CurlP` sponge with `rounds` of iterations.
+    pub fn new(rounds: usize) -> Self {
+        Self {
+            rounds,
+            state: TritsBuf::with_capacity(STATE_LEN),
+            work_state: TritsBuf::with_capacity(STATE_LEN),
+        }
+    }
+
+    /// Return the number of rounds used in this `CurlP` instacnce.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Transforms the internal state of the `CurlP` sponge after the input was copied
+    /// into the internal state.
+    ///
+    /// The essence of this transformation is the application of a so-called substitution box to
+    /// the internal state, which happens `round` number of times.
+    fn transform(&mut self) {
+        for _ in 0..self.rounds {
+            let (input, output) = (&self.state.0, &mut self.work_state.0);
+
+            for (i, trit) in output.iter_mut().enumerate() {
+                *trit =
+                    TRUTH_TABLE[(input[INDICES[i]] + (input[INDICES[i + 1]] << 2) + 5) as usize];
+            }
+
+            // Swapping the buffers leaves the result of the round in the state
+            std::mem::swap(&mut self.state, &mut self.work_state);
+        }
+    }
+}
+
+impl Sponge for CurlP {
+    const HASH_LEN: usize = HASH_LEN;
+
+    type Error = Infallible;
+
+    /// Absorb `input` into the sponge by copying `HASH_LEN` chunks of it into its internal
+    /// state and transforming the state before moving on to the next chunk.
+    ///
+    /// If `input` is not a multiple of `HASH_LEN` with the last chunk having `n < HASH_LEN` trits,
+    /// the last chunk will be copied to the first `n` slots of the internal state. The remaining
+    /// data in the internal state is then just the result of the last transformation before the
+    /// data was copied, and will be reused for the next transformation.
+    fn absorb(&mut self, input: &Trits) -> Result<(), Self::Error> {
+        for chunk in input.0.chunks(Self::HASH_LEN) {
+            self.state.0[0..chunk.len()].copy_from_slice(chunk);
+            self.transform();
+        }
+        Ok(())
+    }
+
+    /// Reset the internal state by overwriting it with zeros.
+    fn reset(&mut self) {
+        self.state.fill(ValidTrits::Zero);
+    }
+
+    /// Squeeze the sponge by copying the calculated hash into the provided `buf`. This will fill
+    /// the buffer in chunks of `HASH_LEN` at a time.
+    ///
+    /// If the last chunk is smaller than `HASH_LEN`, then only the fraction that fits is written
+    /// into it.
+    fn squeeze_into(&mut self, buf: &mut TritsMut) -> Result<(), Self::Error> {
+        for chunk in buf.0.chunks_mut(Self::HASH_LEN) {
+            chunk.copy_from_slice(&self.state.0[0..chunk.len()]);
+            self.transform()
+        }
+        Ok(())
+    }
+}
+
+/// `CurlP` with a fixed number of 27 rounds.
+pub struct CurlP27(CurlP);
+
+impl CurlP27 {
+    pub fn new() -> Self {
+        Self(CurlP::new(CURL_P_27))
+    }
+}
+
+impl Default for CurlP27 {
+    fn default() -> Self {
+        CurlP27::new()
+    }
+}
+
+/// `CurlP` with a fixed number of 81 rounds.
+pub struct CurlP81(CurlP);
+
+impl CurlP81 {
+    pub fn new() -> Self {
+        Self(CurlP::new(CURL_P_81))
+    }
+}
+
+impl Default for CurlP81 {
+    fn default() -> Self {
+        CurlP81::new()
+    }
+}
+
+macro_rules! forward_sponge_impl {
+    ($($t:ty),+) => {
+
+    $(
+        impl $t {
+            /// Return the number of rounds used in this `CurlP` instacnce.
+            pub fn rounds(&self) -> usize {
+                self.0.rounds
+            }
+        }
+
+        impl Sponge for $t {
+            const HASH_LEN: usize = 243;
+
+            type Error = Infallible;
+
+            fn absorb(&mut self, input: &Trits) -> Result<(), Self::Error> {
+                self.0.absorb(input)
+            }
+
+            fn reset(&mut self) {
+                self.0.reset()
+            }
+
+            fn squeeze_into(&mut self, buf: &mut TritsMut) -> Result<(), Self::Error> {
+                self.0.squeeze_into(buf)
+            }
+        }
+    )+
+    }
+}
+
+forward_sponge_impl!(CurlP27, CurlP81);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use iota_conversion::Trinary;
+    use ternary::{T1B1, T5B1};
+
+    use std::convert::TryFrom;
+
+    const TRANSACTION: &str = "RSWWSFXPQJUBJROQBRQZWZXZJWMUBVIVMHPPTYSNW9YQIQQF9RCSJJCVZG9ZWITXNCSBBDHEEKDRBHVTWCZ9SZOOZHVBPCQNPKTWFNZAWGCZ9QDIMKRVINMIRZBPKRKQAIPGOHBTHTGYXTBJLSURDSPEOJ9UKJECUKCCPVIQQHDUYKVKISCEIEGVOQWRBAYXWGSJUTEVG9RPQLPTKYCRAJ9YNCUMDVDYDQCKRJOAPXCSUDAJGETALJINHEVNAARIPONBWXUOQUFGNOCUSSLYWKOZMZUKLNITZIFXFWQAYVJCVMDTRSHORGNSTKX9Z9DLWNHZSMNOYTU9AUCGYBVIITEPEKIXBCOFCMQPBGXYJKSHPXNUKFTXIJVYRFILAVXEWTUICZCYYPCEHNTK9SLGVL9RLAMYTAEPONCBHDXSEQZOXO9XCFUCPPMKEBR9IEJGQOPPILHFXHMIULJYXZJASQEGCQDVYFOM9ETXAGVMSCHHQLFPATWOSMZIDL9AHMSDCE9UENACG9OVFAEIPPQYBCLXDMXXA9UBJFQQBCYKETPNKHNOUKCSSYLWZDLKUARXNVKKKHNRBVSTVKQCZL9RY9BDTDTPUTFUBGRMSTOTXLWUHDMSGYRDSZLIPGQXIDMNCNBOAOI9WFUCXSRLJFIVTIPIAZUK9EDUJJ9B9YCJEZQQELLHVCWDNRH9FUXDGZRGOVXGOKORTCQQA9JXNROLETYCNLRMBGXBL9DQKMOAZCBJGWLNJLGRSTYBKLGFVRUF9QOPZVQFGMDJA9TBVGFJDBAHEVOLW9GNU9NICLCQJBOAJBAHHBZJGOFUCQMBGYQLCWNKSZPPBQMSJTJLM9GXOZHTNDLGIRCSIJAZTENQVQDHFSOQM9WVNWQQJNOPZMEISSCLOADMRNWALBBSLSWNCTOSNHNLWZBVCFIOGFPCPRKQSRGKFXGTWUSCPZSKQNLQJGKDLOXSBJMEHQPDZGSENUKWAHRNONDTBLHNAKGLOMCFYRCGMDOVANPFHMQRFCZIQHCGVORJJNYMTORDKPJPLA9LWAKAWXLIFEVLKHRKCDG9QPQCPGVKIVBENQJTJGZKFTNZHIMQISVBNLHAYSSVJKTIELGTETKPVRQXNAPWOBGQGFRMMK9UQDWJHSQMYQQTCBMVQKUVGJEAGTEQDN9TCRRAZHDPSPIYVNKPGJSJZASZQBM9WXEDWGAOQPPZFLAMZLEZGXPYSOJRWL9ZH9NOJTUKXNTCRRDO9GKULXBAVDRIZBOKJYVJUSHIX9F9O9ACYCAHUKBIEPVZWVJAJGSDQNZNWLIWVSKFJUMOYDMVUFLUXT9CEQEVRFBJVPCTJQCORM9JHLYFSMUVMFDXZFNCUFZZIKREIUIHUSHRPPOUKGFKWX9COXBAZMQBBFRFIBGEAVKBWKNTBMLPHLOUYOXPIQIZQWGOVUWQABTJT9ZZPNBABQFYRCQLXDHDEX9PULVTCQLWPTJLRSVZQEEYVBVY9KCNEZXQLEGADSTJBYOXEVGVTUFKNCNWMEDKDUMTKCMRPGKDCCBDHDVVSMPOPUBZOMZTXJSQNVVGXNPPBVSBL9WWXWQNMHRMQFEQYKWNCSW9URI9FYPT9UZMAFMMGUKFYTWPCQKVJ9DIHRJFMXRZUGI9TMTFUQHGXNBITDSORZORQIAMKY9VRYKLEHNRNFSEFBHF9KXIQAEZEJNQOENJVMWLMHI9GNZPXYUIFAJIVCLAGKUZIKTJKGNQVTXJORWIQDHUPBBPPYOUPFAABBVMMYATXERQHPECDVYGWDGXFJKOMOBXKRZD9MCQ9LGDGGGMYGUAFGMQTUHZOAPLKPNPCIKUNEMQIZOCM9COAOMZSJ9GVWZBZYXMCNALENZ9PRYMHENPWGKX9ULUIGJUJRKFJPBTTHCRZQKEAHT9DC9GSWQEGDTZFHACZMLFYDVOWZADBNMEM9XXEOMHCNJMDSUAJRQTBUWKJF9RZHK9ACGUNI9URFIHLXBXCEODONPXBSCWP9WNAEYNALKQHGULUQGAFL9LB9NBLLCACLQFGQMXRHGBTMI9YKAJKVELRWWKJAPKMSYMJTDYMZ9PJEEYIRXRMMFLRSFSHIXUL9NEJABLRUGHJFL9RASMSKOI9VCFRZ9GWTMODUUESIJBHWWHZYCLDENBFSJQPIOYC9MBGOOXSWEMLVU9L9WJXKZKVDBDMFSVHHISSSNILUMWULMVMESQUIHDGBDXROXGH9MTNFSLWJZRAPOKKRGXAAQBFPYPAAXLSTMNSNDTTJQSDQORNJS9BBGQ9KQJZYPAQ9JYQZJ9B9KQDAXUACZWRUNGMBOQLQZUHFNCKVQGORRZGAHES9PWJUKZWUJSBMNZFILBNBQQKLXITCTQDDBV9UDAOQOUPWMXTXWFWVMCXIXLRMRWMAYYQJPCEAAOFEOGZQMEDAGYGCTKUJBS9AGEXJAFHWWDZRYEN9DN9HVCMLFURISLYSWKXHJKXMHUWZXUQARMYPGKRKQMHVR9JEYXJRPNZINYNCGZHHUNHBAIJHLYZIZGGIDFWVNXZQADLEDJFTIUTQWCQSX9QNGUZXGXJYUUTFSZPQKXBA9DFRQRLTLUJENKESDGTZRGRSLTNYTITXRXRGVLWBTEWPJXZYLGHLQBAVYVOSABIVTQYQM9FIQKCBRRUEMVVTMERLWOK";
+    const TRANSACTION_HASH: &str =
+        "TIXEPIEYMGURTQ9ABVYVQSWMNGCVQFASMFAEQWUZCLIWLCDIGYVXOEJBBEMZOIHAYSUQMEFOGZBXUMHQW";
+
+    /// Arbitrary trits to hash, the same for every implementation.
+    fn input(len: usize) -> Vec<i8> {
+        (0..len).map(|i| ((i * i + 7 * i) % 3) as i8 - 1).collect()
+    }
+
+    fn squeeze_trytes(curlp: &mut CurlP, input: &[i8], squeeze_len: usize) -> String {
+        let mut output = vec![0; squeeze_len];
+
+        curlp.absorb(&Trits::from_i8_unchecked(input)).unwrap();
+        curlp
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut output))
+            .unwrap();
+
+        output.trytes().unwrap()
+    }
+
+    #[test]
+    fn curlp_indices() {
+        assert_eq!(&INDICES[..6], &[0, 364, 728, 363, 727, 362]);
+        assert_eq!(INDICES[STATE_LEN], 0);
+    }
+
+    #[test]
+    fn curlp81_transaction_hash() {
+        let trits = TRANSACTION.trits();
+
+        assert_eq!(
+            squeeze_trytes(&mut CurlP::new(CURL_P_81), &trits[..8019], HASH_LEN),
+            TRANSACTION_HASH
+        );
+    }
+
+    #[test]
+    fn curlp81_packed_transaction_hash() {
+        let trits = TRANSACTION.trits();
+        let packed = ternary::Trits::<T1B1>::try_from(&trits[..8019])
+            .unwrap()
+            .encode::<T5B1>();
+        let mut hash = [0; HASH_LEN];
+
+        CurlP::new(CURL_P_81)
+            .digest_into(
+                &TritsBuf::from(packed.as_trits()).as_trits(),
+                &mut TritsMut::from_i8_unchecked(&mut hash),
+            )
+            .unwrap();
+
+        assert_eq!(hash.trytes().unwrap(), TRANSACTION_HASH);
+    }
+
+    // Computed with the former `curl::Curl`, and with `iota_crypto::Curl` except for the partial squeezes it didn't
+    // support.
+    #[test]
+    fn curlp_known_answers() {
+        let known_answers = [
+            (CURL_P_27, 243, 243, "RDVGFXBYJLWUJYPFBKSITOWSKJNKOEKDGAOSJJGJSFQFGBAWGULKOQOSNTLUVEQHUQTSVUB9UJ9LNWOLR"),
+            (CURL_P_27, 486, 486, "GGPPEQSCOSZAQMCYLTKAZGPHGWNDLGNEBSBVBOSAM9SFL9ABVFCZZOCMAORTFDFZSSITHLXBWQNOADDWTTSZZGPTUQXDEDT9HWSCTLANDIEIFTRXDPHSGXSGHAXUFOSEYJRWMJCDJACLMJQHVKME9NXUBUIHJJKWRT"),
+            (CURL_P_27, 8019, 243, "CMATERXDIVKEVXBTSMPXCWSQJWTYIC9XICCUNGTOJAZJDWPFP9ZKYTULRS9ZAMQZGNTPSBDBNXTNZJSIH"),
+            (CURL_P_27, 102, 243, "FPYBNXEAZEWRLNJLAFRHNK99PLARVQGLMFKMECCKBCSOPLHCWQAYHEIRCGKXJSHKHBAP9DYANYHEFAAAL"),
+            (CURL_P_27, 300, 252, "VUWYHSIJAGMUSTXTPHTQX9KUZTWAEEJPQLAAAXMG9NSSDZLIUONNRYXTJU9ZREBUHYMPEQTLLMSCYAJXJYEO"),
+            (CURL_P_81, 243, 243, "99TLLSGVUDKQWAHSWYETWDVNWDYFZAEXHSOQXPECWMASRSIDHYUZARJYIIIRSRNFJQWYBWJSQ9WHUYIBS"),
+            (CURL_P_81, 486, 486, "9FMALOOZLYCID9MNCPRLTIUXNMTY9YVEBWFSIZTMIXIAQTWUDNSFNCV9T9RCQQMOPFZHJPMZYDMBNMGPPZZZITEJCEB9PCVNPOVETEISUAGXBIVANJWVUYUCCYONFKDJJGTYVMJZCVHJTPVXBHAFMQZALTCRAVMKJB"),
+            (CURL_P_81, 8019, 243, "JEEFTTOSHCPFIOED9UYGFDCKPIRNZKQYBXVWLGJLSJQFFEOYZZRZXTLPSUVZQJZICCMZYMBJKDUFGVVWI"),
+            (CURL_P_81, 102, 243, "VXXAMFMRANTGIIGBIOCUKPOZCAZDUOYEDKWVQOCQRRKYIOEGOPVDYSWOBOVBDTMOFUSXCWOOCWLNGXTVO"),
+            (CURL_P_81, 300, 252, "FPVAYXEU9LGGUEEFUISHDUECQVWSHJPYDVURRP9XQJQUQUBMOXGQDYGEFLMJOEFAOKMBYRXB9EXVIZCA9OLS"),
+        ];
+
+        for &(rounds, absorb_len, squeeze_len, expected) in known_answers.iter() {
+            assert_eq!(
+                squeeze_trytes(&mut CurlP::new(rounds), &input(absorb_len), squeeze_len),
+                expected,
+                "{} rounds, {} trits absorbed, {} trits squeezed",
+                rounds,
+                absorb_len,
+                squeeze_len
+            );
+        }
+    }
+
+    #[test]
+    fn curlp_reset() {
+        let mut curlp = CurlP::new(CURL_P_81);
+        let first = squeeze_trytes(&mut curlp, &input(486), HASH_LEN);
+
+        curlp.reset();
+
+        assert_eq!(squeeze_trytes(&mut curlp, &input(486), HASH_LEN), first);
+        assert_eq!(
+            squeeze_trytes(&mut CurlP81::new().0, &input(486), HASH_LEN),
+            first
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! A minimal Keccak-384, the original Keccak submission that `Kerl` is built on, not the FIPS-202 SHA3-384 (they only
+//! differ in their padding delimiter).
+//!
+//! Only what `Kerl` needs is implemented: absorbing bytes, and padding, permuting and squeezing a single block that is
+//! shorter than the rate.
+
+/// The number of 64-bit lanes of the Keccak-f[1600] state.
+const LANES: usize = 25;
+
+/// The rate of Keccak-384 in bytes, i.e. 200 bytes of state minus twice the 48 bytes of output.
+const RATE: usize = 200 - 2 * 48;
+
+/// The padding delimiter of the original Keccak.
+const DELIMITER: u8 = 0x01;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f(a: &mut [u64; LANES]) {
+    for round_constant in ROUND_CONSTANTS.iter() {
+        let mut array = [0u64; 5];
+
+        // Theta
+        for x in 0..5 {
+            for y in 0..5 {
+                array[x] ^= a[5 * y + x];
+            }
+        }
+        for x in 0..5 {
+            let d = array[(x + 4) % 5] ^ array[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = a[1];
+        for (&pi, &rho) in PI.iter().zip(RHO.iter()) {
+            let next = a[pi];
+            a[pi] = last.rotate_left(rho);
+            last = next;
+        }
+
+        // Chi
+        for y in 0..5 {
+            array.copy_from_slice(&a[5 * y..5 * y + 5]);
+            for x in 0..5 {
+                a[5 * y + x] = array[x] ^ (!array[(x + 1) % 5] & array[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        a[0] ^= round_constant;
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Keccak384 {
+    state: [u64; LANES],
+    /// The number of bytes absorbed into the current block.
+    offset: usize,
+}
+
+impl Default for Keccak384 {
+    fn default() -> Self {
+        Self {
+            state: [0; LANES],
+            offset: 0,
+        }
+    }
+}
+
+impl Keccak384 {
+    /// XORs `byte` into the state at byte position `index`, lanes being little-endian.
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= u64::from(byte) << (8 * (index % 8));
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        (self.state[index / 8] >> (8 * (index % 8))) as u8
+    }
+
+    /// Absorbs `input`, permuting the state every time a block of `RATE` bytes is full.
+    pub(crate) fn update(&mut self, input: &[u8]) {
+        for &byte in input {
+            self.xor_byte(self.offset, byte);
+            self.offset += 1;
+            if self.offset == RATE {
+                keccak_f(&mut self.state);
+                self.offset = 0;
+            }
+        }
+    }
+
+    /// Pads and permutes the absorbed input, writes the first `output.len()` bytes of the state into `output` and
+    /// resets the hasher.
+    ///
+    /// Panics if `output` is longer than the rate.
+    pub(crate) fn finalize_into(&mut self, output: &mut [u8]) {
+        assert!(output.len() <= RATE);
+
+        self.xor_byte(self.offset, DELIMITER);
+        self.xor_byte(RATE - 1, 0x80);
+        keccak_f(&mut self.state);
+
+        for (index, byte) in output.iter_mut().enumerate() {
+            *byte = self.byte(index);
+        }
+
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keccak384(input: &[u8]) -> Vec<u8> {
+        let mut keccak = Keccak384::default();
+        let mut output = vec![0; 48];
+        keccak.update(input);
+        keccak.finalize_into(&mut output);
+        output
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn keccak384_known_answers() {
+        assert_eq!(
+            hex(&keccak384(b"")),
+            "2c23146a63a29acf99e73b88f8c24eaa7dc60aa771780ccc006afbfa8fe2479b2dd2b21362337441ac12b515911957ff"
+        );
+        assert_eq!(
+            hex(&keccak384(b"abc")),
+            "f7df1165f033337be098e7d288ad6a2f74409d7a60b49c36642218de161b1f99f8c681e4afaf31a34db29fb763e3c28e"
+        );
+    }
+
+    #[test]
+    fn keccak384_multiple_blocks() {
+        let input = (0..=255).collect::<Vec<u8>>();
+        let mut keccak = Keccak384::default();
+        let mut output = vec![0; 48];
+
+        for chunk in input.chunks(7) {
+            keccak.update(chunk);
+        }
+        keccak.finalize_into(&mut output);
+
+        assert_eq!(output, keccak384(&input));
+    }
+}
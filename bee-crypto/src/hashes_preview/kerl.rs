@@ -0,0 +1,334 @@
+//! `Kerl`, the ternary sponge built on Keccak-384.
+//!
+//! Every chunk of 243 trits is read as a balanced ternary number of 242 trits, the last trit being ignored, and
+//! converted to the 384-bit two's complement big-endian integer that Keccak-384 absorbs. Squeezing converts the 384-bit
+//! digest back into 242 trits followed by a zero trit, then negates the digest bytes and absorbs them again so that the
+//! next chunk differs from the previous one.
+
+use super::keccak::Keccak384;
+use super::{Sponge, Trits, TritsMut, HASH_LEN};
+
+use std::cmp::Ordering;
+use std::fmt;
+
+const BIT_HASH_LEN: usize = 384;
+const BYTE_HASH_LEN: usize = BIT_HASH_LEN / 8;
+const U32_HASH_LEN: usize = BYTE_HASH_LEN / 4;
+
+/// `(3^242 - 1) / 2`, the largest number representable by 242 balanced trits, as little-endian 32-bit words.
+const HALF_3: [u32; U32_HASH_LEN] = [
+    0xa5ce_8964,
+    0x9f00_7669,
+    0x1484_504f,
+    0x3ade_00d9,
+    0x0c24_486e,
+    0x5097_9d57,
+    0x79a4_c702,
+    0x48bb_ae36,
+    0xa9f6_808b,
+    0xaa06_a805,
+    0xa87f_abdf,
+    0x5e69_ebef,
+];
+
+/// The errors of `Kerl`.
+#[derive(Debug, PartialEq)]
+pub enum KerlError {
+    /// `Kerl` only absorbs and squeezes whole chunks of 243 trits, this is the length that was given instead.
+    InvalidLength(usize),
+}
+
+impl fmt::Display for KerlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KerlError::InvalidLength(len) => write!(
+                f,
+                "invalid length {}, Kerl only works on multiples of {} trits",
+                len, HASH_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KerlError {}
+
+#[derive(Clone, Default)]
+pub struct Kerl {
+    keccak: Keccak384,
+}
+
+impl Kerl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Sponge for Kerl {
+    const HASH_LEN: usize = HASH_LEN;
+
+    type Error = KerlError;
+
+    /// Absorb `input` into the sponge, one chunk of `HASH_LEN` trits at a time.
+    ///
+    /// Returns `KerlError::InvalidLength` if `input` isn't a multiple of `HASH_LEN`, in which case nothing is absorbed.
+    fn absorb(&mut self, input: &Trits) -> Result<(), Self::Error> {
+        check_len(input.len())?;
+
+        let mut bytes = [0; BYTE_HASH_LEN];
+        for chunk in input.0.chunks(HASH_LEN) {
+            trits_to_bytes(chunk, &mut bytes);
+            self.keccak.update(&bytes);
+        }
+
+        Ok(())
+    }
+
+    /// Reset the inner Keccak-384 state.
+    fn reset(&mut self) {
+        self.keccak = Keccak384::default();
+    }
+
+    /// Squeeze the sponge into `buf`, one chunk of `HASH_LEN` trits at a time.
+    ///
+    /// Returns `KerlError::InvalidLength` if `buf` isn't a multiple of `HASH_LEN`, in which case nothing is squeezed.
+    fn squeeze_into(&mut self, buf: &mut TritsMut) -> Result<(), Self::Error> {
+        check_len(buf.len())?;
+
+        let mut bytes = [0; BYTE_HASH_LEN];
+        for chunk in buf.0.chunks_mut(HASH_LEN) {
+            self.keccak.finalize_into(&mut bytes);
+            bytes_to_trits(&bytes, chunk);
+            bytes.iter_mut().for_each(|b| *b = !*b);
+            self.keccak.update(&bytes);
+        }
+
+        Ok(())
+    }
+}
+
+fn check_len(len: usize) -> Result<(), KerlError> {
+    if len.is_multiple_of(HASH_LEN) {
+        Ok(())
+    } else {
+        Err(KerlError::InvalidLength(len))
+    }
+}
+
+/// Converts the first 242 trits of a chunk into a 384-bit two's complement big-endian integer.
+fn trits_to_bytes(trits: &[i8], bytes: &mut [u8; BYTE_HASH_LEN]) {
+    let trits = &trits[..HASH_LEN - 1];
+    let mut base = [0u32; U32_HASH_LEN];
+
+    if trits.iter().all(|&t| t == -1) {
+        // The only number whose unbalanced value, `3^242 - 1`, doesn't fit in 384 bits, this is `-HALF_3`
+        base = HALF_3;
+        bigint_not(&mut base);
+        bigint_add_small(&mut base, 1);
+    } else {
+        // Computes the unbalanced value, i.e. with trits shifted to 0, 1 and 2, most significant trit first
+        let mut size = 1;
+        for &trit in trits.iter().rev() {
+            let mut carry = 0u32;
+            for word in base.iter_mut().take(size) {
+                let v = u64::from(*word) * 3 + u64::from(carry);
+                *word = v as u32;
+                carry = (v >> 32) as u32;
+            }
+            if carry > 0 {
+                base[size] = carry;
+                size += 1;
+            }
+
+            size = size.max(bigint_add_small(&mut base, (trit + 1) as u32));
+        }
+
+        // Shifts the unbalanced value back by `HALF_3` to get the balanced one in two's complement
+        if base.iter().any(|&word| word != 0) {
+            if bigint_cmp(&HALF_3, &base) != Ordering::Greater {
+                bigint_sub(&mut base, &HALF_3);
+            } else {
+                let mut tmp = HALF_3;
+                bigint_sub(&mut tmp, &base);
+                bigint_not(&mut tmp);
+                bigint_add_small(&mut tmp, 1);
+                base = tmp;
+            }
+        }
+    }
+
+    for (chunk, word) in bytes.chunks_mut(4).zip(base.iter().rev()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+/// Converts a 384-bit two's complement big-endian integer into 242 balanced trits and a last zero trit.
+fn bytes_to_trits(bytes: &[u8; BYTE_HASH_LEN], trits: &mut [i8]) {
+    let mut base = [0u32; U32_HASH_LEN];
+    for (word, chunk) in base.iter_mut().rev().zip(bytes.chunks(4)) {
+        *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    // Shifts the balanced value by `HALF_3` to get the unbalanced one, keeping track of whether it was out of range
+    let mut flip_trits = false;
+    if base[U32_HASH_LEN - 1] >> 31 == 0 {
+        bigint_add(&mut base, &HALF_3);
+    } else {
+        bigint_not(&mut base);
+        if bigint_cmp(&base, &HALF_3) == Ordering::Greater {
+            bigint_sub(&mut base, &HALF_3);
+            flip_trits = true;
+        } else {
+            bigint_add_small(&mut base, 1);
+            let mut tmp = HALF_3;
+            bigint_sub(&mut tmp, &base);
+            base = tmp;
+        }
+    }
+
+    for trit in trits.iter_mut().take(HASH_LEN - 1) {
+        let mut remainder = 0u32;
+        for word in base.iter_mut().rev() {
+            let v = (u64::from(remainder) << 32) | u64::from(*word);
+            *word = (v / 3) as u32;
+            remainder = (v % 3) as u32;
+        }
+        *trit = remainder as i8 - 1;
+    }
+    trits[HASH_LEN - 1] = 0;
+
+    if flip_trits {
+        trits.iter_mut().for_each(|t| *t = -*t);
+    }
+}
+
+fn bigint_not(base: &mut [u32]) {
+    base.iter_mut().for_each(|word| *word = !*word);
+}
+
+/// Adds `rhs` to `base` and returns the number of words up to the last one the carry reached.
+fn bigint_add_small(base: &mut [u32], rhs: u32) -> usize {
+    let (v, mut carry) = full_add(base[0], rhs, false);
+    base[0] = v;
+
+    let mut j = 0;
+    while carry {
+        j += 1;
+        let (v, c) = full_add(base[j], 0, true);
+        base[j] = v;
+        carry = c;
+    }
+
+    j + 1
+}
+
+fn bigint_add(base: &mut [u32], rhs: &[u32]) {
+    let mut carry = false;
+    for (a, &b) in base.iter_mut().zip(rhs.iter()) {
+        let (v, c) = full_add(*a, b, carry);
+        *a = v;
+        carry = c;
+    }
+}
+
+fn bigint_sub(base: &mut [u32], rhs: &[u32]) {
+    let mut no_borrow = true;
+    for (a, &b) in base.iter_mut().zip(rhs.iter()) {
+        let (v, c) = full_add(*a, !b, no_borrow);
+        *a = v;
+        no_borrow = c;
+    }
+    assert!(no_borrow);
+}
+
+fn bigint_cmp(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.iter().rev().cmp(rhs.iter().rev())
+}
+
+fn full_add(lhs: u32, rhs: u32, carry: bool) -> (u32, bool) {
+    let v = u64::from(lhs) + u64::from(rhs) + u64::from(carry);
+    (v as u32, v >> 32 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use iota_conversion::Trinary;
+
+    fn squeeze_trytes(input: &str, squeeze_len: usize) -> String {
+        let mut kerl = Kerl::new();
+        let mut output = vec![0; squeeze_len];
+
+        kerl.absorb(&Trits::from_i8_unchecked(&input.trits()))
+            .unwrap();
+        kerl.squeeze_into(&mut TritsMut::from_i8_unchecked(&mut output))
+            .unwrap();
+
+        output.trytes().unwrap()
+    }
+
+    #[test]
+    fn kerl_one_absorb() {
+        assert_eq!(
+            squeeze_trytes(
+                "GYOMKVTSNHVJNCNFBBAH9AAMXLPLLLROQY99QN9DLSJUHDPBLCFFAIQXZA9BKMBJCYSFHFPXAHDWZFEIZ",
+                243
+            ),
+            "OXJCNFHUNAHWDLKKPELTBFUCVW9KLXKOGWERKTJXQMXTKFKNWNNXYD9DMJJABSEIONOSJTTEVKVDQEWTW"
+        );
+    }
+
+    #[test]
+    fn kerl_multi_squeeze_multi_absorb() {
+        assert_eq!(
+            squeeze_trytes(
+                "G9JYBOMPUXHYHKSNRNMMSSZCSHOFYOYNZRSZMAAYWDYEIMVVOGKPJBVBM9TDPULSFUNMTVXRKFIDOHUXX\
+                 VYDLFSZYZTWQYTE9SPYYWYTXJYQ9IFGYOLZXWZBKWZN9QOOTBQMWMUBLEWUEEASRHRTNIQWJQNDWRYLCA",
+                486
+            ),
+            "LUCKQVACOGBFYSPPVSSOXJEKNSQQRQKPZC9NXFSMQNRQCGGUL9OHVVKBDSKEQEBKXRNUJSRXYVHJTXBPD\
+             WQGNSCDCBAIRHAQCOWZEBSNHIJIGPZQITIBJQ9LNTDIBTCQ9EUWKHFLGFUVGGUWJONK9GBCDUIMAYMMQX"
+        );
+    }
+
+    #[test]
+    fn kerl_multi_squeeze() {
+        assert_eq!(
+            squeeze_trytes(
+                "9MIDYNHBWMBCXVDEFOFWINXTERALUKYYPPHKP9JJFGJEIUY9MUDVNFZHMMWZUYUSWAIOWEVTHNWMHANBH",
+                486
+            ),
+            "G9JYBOMPUXHYHKSNRNMMSSZCSHOFYOYNZRSZMAAYWDYEIMVVOGKPJBVBM9TDPULSFUNMTVXRKFIDOHUXX\
+             VYDLFSZYZTWQYTE9SPYYWYTXJYQ9IFGYOLZXWZBKWZN9QOOTBQMWMUBLEWUEEASRHRTNIQWJQNDWRYLCA"
+        );
+    }
+
+    #[test]
+    fn kerl_extreme_trits_round_trip() {
+        let mut bytes = [0; BYTE_HASH_LEN];
+        let mut trits = [0; HASH_LEN];
+
+        for &value in &[-1, 0, 1] {
+            let input = [value; HASH_LEN];
+            trits_to_bytes(&input, &mut bytes);
+            bytes_to_trits(&bytes, &mut trits);
+            assert_eq!(&trits[..HASH_LEN - 1], &input[..HASH_LEN - 1]);
+            assert_eq!(trits[HASH_LEN - 1], 0);
+        }
+    }
+
+    #[test]
+    fn kerl_reject_partial_chunks() {
+        let mut kerl = Kerl::new();
+        let mut output = vec![0; 242];
+
+        assert_eq!(
+            kerl.absorb(&Trits::from_i8_unchecked(&[0; 244])),
+            Err(KerlError::InvalidLength(244))
+        );
+        assert_eq!(
+            kerl.squeeze_into(&mut TritsMut::from_i8_unchecked(&mut output)),
+            Err(KerlError::InvalidLength(242))
+        );
+    }
+}
@@ -7,22 +7,29 @@
 //!
 //! [PR #21]: https://github.com/iotaledger/bee-rfcs/pull/21
 
+mod batch;
+mod bct;
+mod curlp;
+mod keccak;
+mod kerl;
+mod troika;
+
+pub use batch::{BatchError, BatchHasher, BatchQueue, BATCH_SIZE};
+pub use bct::BctState;
+pub use curlp::{CurlP, CurlP27, CurlP81, INDICES};
+pub use kerl::{Kerl, KerlError};
+pub use troika::{Troika, TroikaError, ROUND_CONSTANT_LEN, TROIKA_ROUNDS};
+
+use ternary::Encoding;
+
 use std::convert::TryFrom;
-use std::default::Default;
 
 /// The length of a hash as returned by the hash functions implemented in this RFC (in
 /// units of binary-coded, balanced trits).
 const HASH_LEN: usize = 243;
 
-/// The length internal state of the `CurlP` sponge construction (in units of binary-coded,
-/// balanced trits).
-const CURLP_STATE_LEN: usize = HASH_LEN * 3;
-const CURLP_HALF_STATE_LEN: usize = CURLP_STATE_LEN / 2;
-
-const TRUTH_TABLE: [i8; 11] = [1, 0, -1, 2, 1, -1, 0, 2, -1, 1, 0];
-
 /// An owned, mutable
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TritsBuf(Vec<i8>);
 
 pub enum ValidTrits {
@@ -60,6 +67,11 @@ impl TritsBuf {
         TritsMut(&mut self.0)
     }
 
+    /// Return the binary-coded trits of the buffer.
+    pub fn as_i8_slice(&self) -> &[i8] {
+        &self.0
+    }
+
     pub fn fill(&mut self, v: ValidTrits) {
         let v = v.into();
         self.0.iter_mut().for_each(|x| *x = v);
@@ -91,14 +103,23 @@ impl TritsBuf {
     ///
     /// **WARNING:** If used incorrectly (that is, if the bytes are not correctly encoding trits), the
     /// usage of `Trits` might lead to unexpected behaviour.
+    #[deprecated(note = "use the `i8` trits, or unpack packed trits into a `TritsBuf`")]
     pub fn from_u8_unchecked(v: &[u8]) -> Self {
         Self::from_i8_unchecked(unsafe { &*(v as *const _ as *const [i8]) })
     }
 }
 
+/// Unpacks trits of any encoding, e.g. a field viewed in the T5B1 bytes of a stored transaction.
+impl<E: Encoding> From<ternary::Trits<'_, E>> for TritsBuf {
+    fn from(trits: ternary::Trits<'_, E>) -> Self {
+        Self(trits.iter().map(i8::from).collect())
+    }
+}
+
 pub struct Trits<'a>(&'a [i8]);
 pub struct TritsMut<'a>(&'a mut [i8]);
 
+#[deprecated(note = "use the `i8` trits, or unpack packed trits into a `TritsBuf`")]
 pub struct FromU8Error;
 pub struct FromI8Error;
 
@@ -108,6 +129,15 @@ impl<'a> Trits<'a> {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return the binary-coded trits of the view.
+    pub fn as_i8_slice(&self) -> &'a [i8] {
+        self.0
+    }
+
     /// Create a `Trits` from a `&[i8]` slice without verifying that its bytes are
     /// correctly binary-coded balanced trits (-1, 0, and +1).
     ///
@@ -134,11 +164,14 @@ impl<'a> Trits<'a> {
     ///
     /// **WARNING:** If used incorrectly (that is, if the bytes are not correctly encoding trits), the
     /// usage of `Trits` might lead to unexpected behaviour.
+    #[deprecated(note = "use the `i8` trits, or unpack packed trits into a `TritsBuf`")]
     pub fn from_u8_unchecked(v: &[u8]) -> Self {
         Self::from_i8_unchecked(unsafe { &*(v as *const _ as *const [i8]) })
     }
 }
 
+/// Deprecated along with `FromU8Error`.
+#[allow(deprecated)]
 impl<'a> TryFrom<&'a [u8]> for Trits<'a> {
     type Error = FromU8Error;
 
@@ -174,10 +207,25 @@ impl<'a> TritsMut<'a> {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return the binary-coded trits of the view.
+    pub fn as_i8_slice(&self) -> &[i8] {
+        self.0
+    }
+
+    /// Return the binary-coded trits of the view, to be written to.
+    pub fn as_i8_slice_mut(&mut self) -> &mut [i8] {
+        self.0
+    }
+
     pub fn from_i8_unchecked(v: &'a mut [i8]) -> Self {
         Self(v)
     }
 
+    #[deprecated(note = "use the `i8` trits, or unpack packed trits into a `TritsBuf`")]
     pub fn from_u8_unchecked(v: &mut [u8]) -> Self {
         Self::from_i8_unchecked(unsafe { &mut *(v as *mut _ as *mut [i8]) })
     }
@@ -198,6 +246,8 @@ impl<'a> TryFrom<&'a mut [i8]> for TritsMut<'a> {
     }
 }
 
+/// Deprecated along with `FromU8Error`.
+#[allow(deprecated)]
 impl<'a> TryFrom<&'a mut [u8]> for TritsMut<'a> {
     type Error = FromU8Error;
 
@@ -215,197 +265,45 @@ impl<'a> TryFrom<&'a mut [u8]> for TritsMut<'a> {
 
 /// The common interface of cryptographic hash functions that follow the sponge construction,
 /// and that absorb and return binary-coded, balanced ternary.
-trait Sponge {
+pub trait Sponge {
     const HASH_LEN: usize;
 
+    /// The error returned when the sponge can't absorb an input or squeeze into a buffer, e.g.
+    /// because of its length.
+    type Error: std::fmt::Debug;
+
     /// Absorb `input` into the sponge.
-    fn absorb(&mut self, input: &Trits);
+    fn absorb(&mut self, input: &Trits) -> Result<(), Self::Error>;
 
     /// Reset the inner state of the sponge.
     fn reset(&mut self);
 
     /// Squeeze the sponge into a buffer
-    fn squeeze_into(&mut self, buf: &mut TritsMut);
+    fn squeeze_into(&mut self, buf: &mut TritsMut) -> Result<(), Self::Error>;
 
     /// Convenience function using `Sponge::squeeze_into` to to return an owned
     /// version of the hash.
-    fn squeeze(&mut self) -> TritsBuf {
+    fn squeeze(&mut self) -> Result<TritsBuf, Self::Error> {
         let mut output = TritsBuf::with_capacity(Self::HASH_LEN);
-        self.squeeze_into(&mut output.as_trits_mut());
-        output
+        self.squeeze_into(&mut output.as_trits_mut())?;
+        Ok(output)
     }
 
     /// Convenience function to absorb `input`, squeeze the sponge into a
     /// buffer, and reset the sponge in one go.
-    fn digest_into(&mut self, input: &Trits, buf: &mut TritsMut) {
-        self.absorb(input);
-        self.squeeze_into(buf);
+    fn digest_into(&mut self, input: &Trits, buf: &mut TritsMut) -> Result<(), Self::Error> {
+        self.absorb(input)?;
+        self.squeeze_into(buf)?;
         self.reset();
+        Ok(())
     }
 
     /// Convenience function to absorb `input`, squeeze the sponge, and reset the sponge in one go.
     /// Returns an owned versin of the hash.
-    fn digest(&mut self, input: &Trits) -> TritsBuf {
-        self.absorb(input);
-        let output = self.squeeze();
+    fn digest(&mut self, input: &Trits) -> Result<TritsBuf, Self::Error> {
+        self.absorb(input)?;
+        let output = self.squeeze()?;
         self.reset();
-        output
-    }
-}
-
-pub struct CurlP {
-    /// The number of rounds of hashing to apply before a hash is squeezed.
-    rounds: usize,
-
-    /// The internal state.
-    state: TritsBuf,
-
-    /// Workspace for performing transformations
-    work_state: TritsBuf,
-}
-
-impl CurlP {
-    /// Create a new `CurlP` sponge with `rounds` of iterations.
-    pub fn new(rounds: usize) -> Self {
-        Self {
-            rounds,
-            state: TritsBuf::with_capacity(CURLP_STATE_LEN),
-            work_state: TritsBuf::with_capacity(CURLP_STATE_LEN),
-        }
-    }
-
-    /// Return the number of rounds used in this `CurlP` instacnce.
-    pub fn rounds(&self) -> usize {
-        self.rounds
-    }
-
-    /// Transforms the internal state of the `CurlP` sponge after the input was copied
-    /// into the internal state.
-    ///
-    /// The essence of this transformation is the application of a so-called substitution box to
-    /// the internal state, which happens `round` number of times.
-    fn transform(&mut self) {
-        fn apply_substitution_box(input: &[i8], output: &mut [i8]) {
-            assert!(input.len() <= CURLP_STATE_LEN);
-            assert!(output.len() <= CURLP_STATE_LEN);
-
-            output[0] = TRUTH_TABLE[(input[0] + (input[364] << 2) + 5) as usize];
-
-            for state_index in 0..CURLP_HALF_STATE_LEN {
-                let rhs_index_a = CURLP_HALF_STATE_LEN - state_index;
-                let rhs_index_b = CURLP_STATE_LEN - state_index - 1;
-
-                output[2 * state_index + 1] =
-                    TRUTH_TABLE[{ (input[rhs_index_a] + input[rhs_index_b] << 2) + 5 } as usize];
-
-                let rhs_index_a = 364 - state_index - 1;
-                output[2 * state_index + 2] =
-                    TRUTH_TABLE[{ (input[rhs_index_b] + input[rhs_index_a] << 2) + 5 } as usize];
-            }
-        }
-
-        let (mut lhs, mut rhs) = (&mut self.state.0, &mut self.work_state.0);
-
-        for _ in 0..self.rounds {
-            apply_substitution_box(lhs, rhs);
-            std::mem::swap(&mut lhs, &mut rhs);
-        }
-    }
-}
-
-impl Sponge for CurlP {
-    const HASH_LEN: usize = HASH_LEN;
-
-    /// Absorb `input` into the sponge by copying `HASH_LEN` chunks of it into its internal
-    /// state and transforming the state before moving on to the next chunk.
-    ///
-    /// If `input` is not a multiple of `HASH_LEN` with the last chunk having `n < HASH_LEN` trits,
-    /// the last chunk will be copied to the first `n` slots of the internal state. The remaining
-    /// data in the internal state is then just the result of the last transformation before the
-    /// data was copied, and will be reused for the next transformation.
-    fn absorb(&mut self, input: &Trits) {
-        for chunk in input.0.chunks(Self::HASH_LEN) {
-            self.state.0[0..chunk.len()].copy_from_slice(chunk);
-            self.transform();
-        }
-    }
-
-    /// Reset the internal state by overwriting it with zeros.
-    fn reset(&mut self) {
-        self.state.fill(ValidTrits::Zero);
-    }
-
-    /// Squeeze the sponge by copying the calculated hash into the provided `buf`. This will fill
-    /// the buffer in chunks of `HASH_LEN` at a time.
-    ///
-    /// If the last chunk is smaller than `HASH_LEN`, then only the fraction that fits is written
-    /// into it.
-    fn squeeze_into(&mut self, buf: &mut TritsMut) {
-        for chunk in buf.0.chunks_mut(Self::HASH_LEN) {
-            chunk.copy_from_slice(&self.state.0[0..chunk.len()]);
-            self.transform()
-        }
-    }
-}
-
-/// `CurlP` with a fixed number of 27 rounds.
-pub struct CurlP27(CurlP);
-
-impl CurlP27 {
-    pub fn new() -> Self {
-        Self(CurlP::new(27))
-    }
-}
-
-impl Default for CurlP27 {
-    fn default() -> Self {
-        CurlP27::new()
-    }
-}
-
-/// `CurlP` with a fixed number of 81 rounds.
-pub struct CurlP81(CurlP);
-
-impl CurlP81 {
-    pub fn new() -> Self {
-        Self(CurlP::new(81))
-    }
-}
-
-impl Default for CurlP81 {
-    fn default() -> Self {
-        CurlP81::new()
+        Ok(output)
     }
 }
-
-macro_rules! forward_sponge_impl {
-    ($($t:ty),+) => {
-
-    $(
-        impl $t {
-            /// Return the number of rounds used in this `CurlP` instacnce.
-            pub fn rounds(&self) -> usize {
-                self.0.rounds
-            }
-        }
-
-        impl Sponge for $t {
-            const HASH_LEN: usize = 243;
-
-            fn absorb(&mut self, input: &Trits) {
-                self.0.absorb(input)
-            }
-
-            fn reset(&mut self) {
-                self.0.reset()
-            }
-
-            fn squeeze_into(&mut self, buf: &mut TritsMut) {
-                self.0.squeeze_into(buf);
-            }
-        }
-    )+
-    }
-}
-
-forward_sponge_impl!(CurlP27, CurlP81);
@@ -0,0 +1,978 @@
+//! An HTTP JSON API compatible with the command set of the IRI API.
+//!
+//! Every request is a `POST` with a JSON body holding the `command` and its parameters. Successful responses carry the
+//! command specific fields, failed ones an `error` message; both report the `duration` of the call in milliseconds.
+
+use crate::node::{NetworkCommand, Node, ParsedTransaction};
+use crate::workers::Shutdown;
+
+use common::constants::{TAG_TRIT_LEN, TRANSACTION_TRIT_LEN};
+use common::{Error, Result, Trit};
+
+use bundle::{attach_bundle, Transaction};
+use pow::{Cores, Difficulty};
+use ternary::iota_conversion::Trinary;
+use ternary::TryteStr;
+
+use signing::address::remove_checksum;
+
+use bee_tangle::{TxHash, TX_HASH_LEN};
+
+use network::PeerId;
+
+use log::{debug, error};
+
+use hyper::body::HttpBody;
+use hyper::header::CONTENT_LENGTH;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The maximum depth accepted by `getTransactionsToApprove`.
+const MAX_DEPTH: usize = 15;
+/// The maximum size of a request body.
+const MAX_BODY_SIZE: usize = 20_000_000;
+
+/// The commands of the API and their parameters.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum Command {
+    GetNodeInfo,
+    GetNeighbors,
+    #[serde(rename_all = "camelCase")]
+    AddNeighbors {
+        uris: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RemoveNeighbors {
+        uris: Vec<String>,
+    },
+    GetTips,
+    #[serde(rename_all = "camelCase")]
+    FindTransactions {
+        addresses: Option<Vec<String>>,
+        bundles: Option<Vec<String>>,
+        tags: Option<Vec<String>>,
+        approvees: Option<Vec<String>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetTrytes {
+        hashes: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetInclusionStates {
+        transactions: Vec<String>,
+        #[serde(default)]
+        tips: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetBalances {
+        addresses: Vec<String>,
+        #[serde(default)]
+        threshold: Option<u8>,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetTransactionsToApprove {
+        depth: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    AttachToTangle {
+        trunk_transaction: String,
+        branch_transaction: String,
+        min_weight_magnitude: usize,
+        trytes: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    BroadcastTransactions {
+        trytes: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StoreTransactions {
+        trytes: Vec<String>,
+    },
+}
+
+/// An error reported to the client, together with the HTTP status it is reported with.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    fn too_large() -> Self {
+        Self {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            message: format!("request larger than {} bytes", MAX_BODY_SIZE),
+        }
+    }
+
+    fn internal(error: Error) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: error.to_string(),
+        }
+    }
+}
+
+type ApiResult = std::result::Result<Value, ApiError>;
+
+/// Serves the API on `listener` until shutdown.
+pub async fn api_worker(node: Node, listener: TcpListener, shutdown: Shutdown) -> Result<()> {
+    let service = make_service_fn(move |_| {
+        let node = node.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| serve(node.clone(), request))) }
+    });
+
+    Server::from_tcp(listener)
+        .map_err(|e| Error::RuntimeError(format!("cannot serve the API: {}", e)))?
+        .serve(service)
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| Error::RuntimeError(format!("API server failed: {}", e)))
+}
+
+/// Binds the listener of the API, so that a taken address is reported before any worker is started.
+pub fn bind(address: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(address)
+        .map_err(|e| Error::RuntimeError(format!("cannot bind the API to {}: {}", address, e)))
+}
+
+async fn serve(
+    node: Node,
+    request: Request<Body>,
+) -> std::result::Result<Response<Body>, Infallible> {
+    let start = Instant::now();
+
+    let result = match *request.method() {
+        Method::POST => match read_body(request, MAX_BODY_SIZE).await {
+            Ok(body) => match serde_json::from_slice::<Command>(&body) {
+                Ok(command) => handle(&node, command).await,
+                Err(e) => Err(ApiError::bad_request(format!("invalid command: {}", e))),
+            },
+            Err(e) => Err(e),
+        },
+        _ => Err(ApiError {
+            status: StatusCode::METHOD_NOT_ALLOWED,
+            message: String::from("only POST requests are supported"),
+        }),
+    };
+
+    let duration = start.elapsed().as_millis() as u64;
+
+    let (status, mut body) = match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err(e) => {
+            if e.status.is_server_error() {
+                error!(status = e.status.as_u16(), error = e.message.as_str(); "API request failed");
+            } else {
+                debug!(status = e.status.as_u16(), error = e.message.as_str(); "API request rejected");
+            }
+
+            (e.status, json!({ "error": e.message }))
+        }
+    };
+
+    if let Value::Object(fields) = &mut body {
+        fields.insert(String::from("duration"), json!(duration));
+    }
+
+    // Can't fail, the status and header are valid
+    Ok(Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap())
+}
+
+/// Reads the body of a request, rejecting it as soon as it is known to be larger than `max_size` bytes.
+async fn read_body(
+    request: Request<Body>,
+    max_size: usize,
+) -> std::result::Result<Vec<u8>, ApiError> {
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<usize>().ok());
+
+    if content_length.is_some_and(|len| len > max_size) {
+        return Err(ApiError::too_large());
+    }
+
+    let mut body = request.into_body();
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or(0));
+
+    // The content length is only a hint, the body is streamed in case it is missing or wrong
+    while let Some(chunk) = body.data().await {
+        let chunk =
+            chunk.map_err(|e| ApiError::bad_request(format!("cannot read request: {}", e)))?;
+
+        if bytes.len() + chunk.len() > max_size {
+            return Err(ApiError::too_large());
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+/// Executes a single command.
+pub async fn handle(node: &Node, command: Command) -> ApiResult {
+    match command {
+        Command::GetNodeInfo => get_node_info(node),
+        Command::GetNeighbors => get_neighbors(node),
+        Command::AddNeighbors { uris } => add_neighbors(node, uris).await,
+        Command::RemoveNeighbors { uris } => remove_neighbors(node, uris).await,
+        Command::GetTips => get_tips(node),
+        Command::FindTransactions {
+            addresses,
+            bundles,
+            tags,
+            approvees,
+        } => find_transactions(node, addresses, bundles, tags, approvees),
+        Command::GetTrytes { hashes } => get_trytes(node, hashes),
+        Command::GetInclusionStates { transactions, tips } => {
+            get_inclusion_states(node, transactions, tips)
+        }
+        Command::GetBalances {
+            addresses,
+            threshold,
+        } => get_balances(node, addresses, threshold),
+        Command::GetTransactionsToApprove { depth } => get_transactions_to_approve(node, depth),
+        Command::AttachToTangle {
+            trunk_transaction,
+            branch_transaction,
+            min_weight_magnitude,
+            trytes,
+        } => {
+            attach_to_tangle(
+                node,
+                trunk_transaction,
+                branch_transaction,
+                min_weight_magnitude,
+                trytes,
+            )
+            .await
+        }
+        Command::BroadcastTransactions { trytes } => broadcast_transactions(node, trytes).await,
+        Command::StoreTransactions { trytes } => store_transactions(node, trytes).await,
+    }
+}
+
+fn get_node_info(node: &Node) -> ApiResult {
+    let latest_solid_milestone = *node.latest_solid_milestone.read().unwrap();
+    let (milestone, milestone_index) = match latest_solid_milestone {
+        Some((hash, index)) => (hash, index),
+        None => (TxHash::genesis(), 0),
+    };
+    let tangle = node.tangle.read().unwrap();
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    Ok(json!({
+        "appName": "bee",
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "latestMilestone": milestone.as_trytes(),
+        "latestMilestoneIndex": milestone_index,
+        "latestSolidSubtangleMilestone": milestone.as_trytes(),
+        "latestSolidSubtangleMilestoneIndex": milestone_index,
+        "neighbors": node.peers.lock().unwrap().len(),
+        "tips": tangle.tips().len(),
+        "transactions": tangle.len(),
+        "time": time,
+        "coordinatorAddress": node.config.coordinator.address,
+    }))
+}
+
+fn get_neighbors(node: &Node) -> ApiResult {
+    let neighbors = node
+        .peers
+        .lock()
+        .unwrap()
+        .keys()
+        .map(|PeerId(address)| json!({ "address": address.to_string(), "connectionType": "tcp" }))
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "neighbors": neighbors }))
+}
+
+async fn add_neighbors(node: &Node, uris: Vec<String>) -> ApiResult {
+    let addresses = parse_uris(&uris)?;
+    let mut added = 0;
+
+    for address in addresses {
+        if !node.peers.lock().unwrap().contains_key(&PeerId(address)) {
+            node.network_command(NetworkCommand::AddPeer(address))
+                .await
+                .map_err(ApiError::internal)?;
+            added += 1;
+        }
+    }
+
+    Ok(json!({ "addedNeighbors": added }))
+}
+
+async fn remove_neighbors(node: &Node, uris: Vec<String>) -> ApiResult {
+    let addresses = parse_uris(&uris)?;
+    let mut removed = 0;
+
+    for address in addresses {
+        if node.peers.lock().unwrap().contains_key(&PeerId(address)) {
+            node.network_command(NetworkCommand::RemovePeer(address))
+                .await
+                .map_err(ApiError::internal)?;
+            removed += 1;
+        }
+    }
+
+    Ok(json!({ "removedNeighbors": removed }))
+}
+
+fn get_tips(node: &Node) -> ApiResult {
+    let hashes = node
+        .tangle
+        .read()
+        .unwrap()
+        .tips()
+        .iter()
+        .map(|hash| hash.as_trytes().to_string())
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "hashes": hashes }))
+}
+
+fn find_transactions(
+    node: &Node,
+    addresses: Option<Vec<String>>,
+    bundles: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    approvees: Option<Vec<String>>,
+) -> ApiResult {
+    let mut results: Vec<HashSet<TxHash>> = Vec::new();
+
+    {
+        let indices = node.indices.read().unwrap();
+
+        if let Some(addresses) = addresses {
+            let addresses = addresses
+                .iter()
+                .map(|address| parse_address(address))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            results.push(
+                addresses
+                    .iter()
+                    .flat_map(|a| indices.by_address(a.as_trytes()))
+                    .collect(),
+            );
+        }
+
+        if let Some(bundles) = bundles {
+            let bundles = parse_hashes(&bundles)?;
+            results.push(
+                bundles
+                    .iter()
+                    .flat_map(|b| indices.by_bundle(b.as_trytes()))
+                    .collect(),
+            );
+        }
+
+        if let Some(tags) = tags {
+            let tags = tags
+                .iter()
+                .map(|tag| parse_tag(tag))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            results.push(tags.iter().flat_map(|t| indices.by_tag(t)).collect());
+        }
+    }
+
+    if let Some(approvees) = approvees {
+        let approvees = parse_hashes(&approvees)?;
+        let tangle = node.tangle.read().unwrap();
+        results.push(
+            approvees
+                .iter()
+                .flat_map(|a| tangle.approvers(*a).to_vec())
+                .collect(),
+        );
+    }
+
+    let mut results = results.into_iter();
+    let first = results.next().ok_or_else(|| {
+        ApiError::bad_request("at least one of addresses, bundles, tags or approvees is required")
+    })?;
+
+    // Only transactions matching all given criteria are returned
+    let hashes = results
+        .fold(first, |acc, result| {
+            acc.intersection(&result).copied().collect()
+        })
+        .iter()
+        .map(|hash| hash.as_trytes().to_string())
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "hashes": hashes }))
+}
+
+fn get_trytes(node: &Node, hashes: Vec<String>) -> ApiResult {
+    let trytes = parse_hashes(&hashes)?
+        .into_iter()
+        .map(|hash| node.transaction_trytes(hash))
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "trytes": trytes }))
+}
+
+fn get_inclusion_states(node: &Node, transactions: Vec<String>, tips: Vec<String>) -> ApiResult {
+    let transactions = parse_hashes(&transactions)?;
+    let mut tips = parse_hashes(&tips)?;
+
+    if tips.is_empty() {
+        tips.extend(
+            node.latest_solid_milestone
+                .read()
+                .unwrap()
+                .map(|(hash, _)| hash),
+        );
+    }
+
+    let tangle = node.tangle.read().unwrap();
+    let states = transactions
+        .iter()
+        .map(|transaction| tips.iter().any(|tip| tangle.approves(*tip, *transaction)))
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "states": states }))
+}
+
+fn get_balances(node: &Node, addresses: Vec<String>, threshold: Option<u8>) -> ApiResult {
+    if threshold.map(|t| t != 100).unwrap_or(false) {
+        return Err(ApiError::bad_request(
+            "only a threshold of 100 is supported",
+        ));
+    }
+
+    let addresses = addresses
+        .iter()
+        .map(|address| parse_address(address))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let latest_solid_milestone = *node.latest_solid_milestone.read().unwrap();
+
+    let balances = match latest_solid_milestone {
+        Some((milestone, _)) => {
+            let indices = node.indices.read().unwrap();
+            let tangle = node.tangle.read().unwrap();
+
+            addresses
+                .iter()
+                .map(|address| {
+                    indices
+                        .by_address(address.as_trytes())
+                        .into_iter()
+                        .filter(|hash| tangle.approves(milestone, *hash))
+                        .filter_map(|hash| node.transaction_trytes(hash))
+                        .filter_map(|tx_trytes| ParsedTransaction::from_trytes(&tx_trytes).ok())
+                        .map(|parsed| parsed.transaction.value().0)
+                        .sum::<i64>()
+                })
+                .collect::<Vec<_>>()
+        }
+        None => vec![0; addresses.len()],
+    };
+
+    Ok(json!({
+        "balances": balances.iter().map(|balance| balance.to_string()).collect::<Vec<_>>(),
+        "references": latest_solid_milestone.iter().map(|(hash, _)| hash.as_trytes()).collect::<Vec<_>>(),
+        "milestoneIndex": latest_solid_milestone.map(|(_, index)| index).unwrap_or(0),
+    }))
+}
+
+fn get_transactions_to_approve(node: &Node, depth: usize) -> ApiResult {
+    if depth > MAX_DEPTH {
+        return Err(ApiError::bad_request(format!(
+            "depth must not exceed {}",
+            MAX_DEPTH
+        )));
+    }
+
+    let tangle = node.tangle.read().unwrap();
+    let tips = tangle
+        .tips()
+        .into_iter()
+        .filter(|tip| {
+            tangle
+                .get(*tip)
+                .map(|vertex| vertex.is_solid())
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+
+    let mut rng = rand::thread_rng();
+    let trunk = tips
+        .choose(&mut rng)
+        .copied()
+        .unwrap_or_else(TxHash::genesis);
+    let branch = tips
+        .choose(&mut rng)
+        .copied()
+        .unwrap_or_else(TxHash::genesis);
+
+    Ok(json!({
+        "trunkTransaction": trunk.as_trytes(),
+        "branchTransaction": branch.as_trytes(),
+    }))
+}
+
+/// Attaches the transactions of a bundle, which are given like IRI expects them, from the last index down to index 0.
+///
+/// Like IRI, the attached transactions are returned in the reverse order, i.e. in index order.
+async fn attach_to_tangle(
+    node: &Node,
+    trunk_transaction: String,
+    branch_transaction: String,
+    min_weight_magnitude: usize,
+    trytes: Vec<String>,
+) -> ApiResult {
+    let trunk = parse_bundle_hash(&trunk_transaction)?;
+    let branch = parse_bundle_hash(&branch_transaction)?;
+
+    if min_weight_magnitude == 0 || min_weight_magnitude > node.config.pow.mwm {
+        return Err(ApiError::bad_request(format!(
+            "minWeightMagnitude must be in 1..={}",
+            node.config.pow.mwm
+        )));
+    }
+
+    let transactions = trytes
+        .iter()
+        .rev()
+        .map(|tx_trytes| {
+            Transaction::from_tryte_str(tx_trytes)
+                .map_err(|_| ApiError::bad_request("invalid transaction trytes"))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let cores = node.config.pow.cores.map(Cores::from).unwrap_or_default();
+    let deadline = node
+        .config
+        .pow
+        .timeout
+        .map(|timeout| Instant::now() + Duration::from_secs(timeout));
+
+    let attached = tokio::task::spawn_blocking(move || {
+        attach_bundle(
+            &transactions,
+            &trunk,
+            &branch,
+            Difficulty::from(min_weight_magnitude),
+            cores,
+            deadline,
+        )
+    })
+    .await
+    .map_err(|e| ApiError::internal(Error::RuntimeError(e.to_string())))?
+    .map_err(|e| match e {
+        Error::TransactionError => ApiError::bad_request(
+            "the transactions must be given from the last index of their bundle down to index 0",
+        ),
+        e => ApiError::internal(e),
+    })?;
+
+    let attached = attached
+        .iter()
+        .map(|outcome| trytes_of(&outcome.transaction.to_trits()))
+        .collect::<Result<Vec<_>>>()
+        .map_err(ApiError::internal)?;
+
+    Ok(json!({ "trytes": attached }))
+}
+
+async fn broadcast_transactions(node: &Node, trytes: Vec<String>) -> ApiResult {
+    let transactions = trytes
+        .iter()
+        .map(|tx_trytes| parse_transaction(tx_trytes))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for parsed in &transactions {
+        node.broadcast_transaction(parsed)
+            .await
+            .map_err(ApiError::internal)?;
+    }
+
+    Ok(json!({}))
+}
+
+async fn store_transactions(node: &Node, trytes: Vec<String>) -> ApiResult {
+    let transactions = trytes
+        .iter()
+        .map(|tx_trytes| parse_transaction(tx_trytes))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    if let Some(i) = node
+        .verify_pow(&transactions)
+        .iter()
+        .position(|valid| !valid)
+    {
+        return Err(ApiError::bad_request(format!(
+            "transaction {} does not satisfy the minimum weight magnitude",
+            i
+        )));
+    }
+
+    for parsed in &transactions {
+        node.store_transaction(parsed)
+            .await
+            .map_err(ApiError::internal)?;
+    }
+
+    Ok(json!({}))
+}
+
+fn parse_transaction(tx_trytes: &str) -> std::result::Result<ParsedTransaction, ApiError> {
+    ParsedTransaction::from_trytes(tx_trytes)
+        .map_err(|_| ApiError::bad_request("invalid transaction trytes"))
+}
+
+fn parse_hash(hash: &str) -> std::result::Result<TxHash, ApiError> {
+    TxHash::from_trytes(hash)
+        .ok_or_else(|| ApiError::bad_request(format!("invalid hash '{}'", hash)))
+}
+
+fn parse_bundle_hash(hash: &str) -> std::result::Result<bundle::Hash, ApiError> {
+    parse_hash(hash)?
+        .as_trytes()
+        .parse()
+        .map_err(|_| ApiError::bad_request(format!("invalid hash '{}'", hash)))
+}
+
+fn parse_hashes(hashes: &[String]) -> std::result::Result<Vec<TxHash>, ApiError> {
+    hashes.iter().map(|hash| parse_hash(hash)).collect()
+}
+
+/// Parses an address, with or without its 9-tryte checksum, which is validated when given.
+fn parse_address(address: &str) -> std::result::Result<TxHash, ApiError> {
+    let invalid = |details: String| {
+        ApiError::bad_request(format!("invalid address '{}': {}", address, details))
+    };
+    let trytes = TryteStr::try_from_str(address).map_err(|e| invalid(e.to_string()))?;
+
+    // Trytes are ASCII, so there are as many trytes as bytes
+    if trytes.len() == TX_HASH_LEN {
+        return parse_hash(trytes.as_str());
+    }
+
+    remove_checksum(trytes.as_str())
+        .map_err(|e| invalid(e.to_string()))
+        .and_then(parse_hash)
+}
+
+/// Parses a tag, padding it with `9`s to its full length.
+fn parse_tag(tag: &str) -> std::result::Result<String, ApiError> {
+    let len = TAG_TRIT_LEN / 3;
+
+    if tag.len() > len || !tag.chars().all(|c| c == '9' || c.is_ascii_uppercase()) {
+        return Err(ApiError::bad_request(format!("invalid tag '{}'", tag)));
+    }
+
+    Ok(format!("{:9<width$}", tag, width = len))
+}
+
+fn parse_uris(uris: &[String]) -> std::result::Result<Vec<SocketAddr>, ApiError> {
+    uris.iter()
+        .map(|uri| {
+            uri.strip_prefix("tcp://")
+                .and_then(|address| address.parse().ok())
+                .ok_or_else(|| ApiError::bad_request(format!("invalid neighbor uri '{}'", uri)))
+        })
+        .collect()
+}
+
+fn trytes_of(trits: &[Trit]) -> Result<String> {
+    debug_assert_eq!(trits.len(), TRANSACTION_TRIT_LEN);
+    trits.trytes().map_err(|_| Error::TransactionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node::tests::{test_node, tx_trytes};
+    use crate::workers::Workers;
+
+    use bundle::{Address, Index, TransactionBuilder};
+
+    use hyper::Client;
+    use tokio::runtime::Runtime;
+
+    async fn post(address: SocketAddr, body: Value) -> (StatusCode, Value) {
+        let request = Request::post(format!("http://{}", address))
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = Client::new().request(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn serve_on_localhost() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("serve");
+
+        runtime.block_on(async {
+            let listener = bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+            let address = listener.local_addr().unwrap();
+
+            let mut workers = Workers::new();
+            workers.spawn("api", |shutdown| {
+                api_worker(test.node.clone(), listener, shutdown)
+            });
+
+            let (status, body) = post(address, json!({ "command": "getNodeInfo" })).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["appName"], "bee");
+            assert_eq!(body["transactions"], 0);
+            assert!(body["duration"].is_u64());
+
+            let (status, body) = post(address, json!({ "command": "getSomething" })).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(body["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("invalid command"));
+
+            workers.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn reject_large_bodies() {
+        let mut runtime = Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let request = Request::post("/")
+                .header(CONTENT_LENGTH, 11)
+                .body(Body::from("{}"))
+                .unwrap();
+            let error = read_body(request, 10).await.unwrap_err();
+            assert_eq!(error.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+            // Without a content length, the body is read until it gets too large
+            let (mut sender, body) = Body::channel();
+            tokio::spawn(async move {
+                for chunk in &["12345", "67890", "1", "2"] {
+                    if sender.send_data((*chunk).into()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+            let request = Request::post("/").body(body).unwrap();
+            let error = read_body(request, 10).await.unwrap_err();
+            assert_eq!(error.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+            let request = Request::post("/").body(Body::from("1234567890")).unwrap();
+            assert_eq!(read_body(request, 10).await.unwrap(), b"1234567890");
+        });
+    }
+
+    #[test]
+    fn store_and_find_transactions() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("store");
+        let address = "A".repeat(TX_HASH_LEN);
+        let tx_trytes = tx_trytes(&address);
+
+        runtime.block_on(async {
+            let node = &test.node;
+
+            let command = json!({ "command": "storeTransactions", "trytes": [tx_trytes] });
+            handle(node, serde_json::from_value(command).unwrap()).await.unwrap();
+
+            // The first transaction whose hash does not end with TEST_MWM zero trits
+            let invalid = (0..)
+                .map(|i| tx_trytes.replacen("9", &((b'A' + i) as char).to_string(), 1))
+                .find(|tx_trytes| !ParsedTransaction::from_trytes(tx_trytes).unwrap().hash.as_trytes().ends_with('9'))
+                .unwrap();
+            let command = json!({ "command": "storeTransactions", "trytes": [tx_trytes, invalid] });
+            let error = handle(node, serde_json::from_value(command).unwrap()).await.unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+            assert!(error.message.contains("transaction 1"));
+
+            let command = json!({ "command": "findTransactions", "addresses": [address], "tags": [""] });
+            let hashes = handle(node, serde_json::from_value(command).unwrap()).await.unwrap()["hashes"].clone();
+            assert_eq!(hashes.as_array().unwrap().len(), 1);
+
+            let command = json!({ "command": "getTrytes", "hashes": [hashes[0], TxHash::genesis().as_trytes()] });
+            let trytes = handle(node, serde_json::from_value(command).unwrap()).await.unwrap()["trytes"].clone();
+            assert_eq!(trytes, json!([tx_trytes, null]));
+
+            let command = json!({ "command": "findTransactions", "bundles": [TxHash::genesis().as_trytes()], "addresses": [address] });
+            let hashes = handle(node, serde_json::from_value(command).unwrap()).await.unwrap()["hashes"].clone();
+            assert_eq!(hashes.as_array().unwrap().len(), 1);
+
+            let command = json!({ "command": "findTransactions", "tags": ["BEE"] });
+            let hashes = handle(node, serde_json::from_value(command).unwrap()).await.unwrap()["hashes"].clone();
+            assert!(hashes.as_array().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn reject_invalid_parameters() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("invalid");
+
+        runtime.block_on(async {
+            let node = &test.node;
+
+            for command in &[
+                json!({ "command": "storeTransactions", "trytes": ["ABC"] }),
+                json!({ "command": "getTrytes", "hashes": ["abc"] }),
+                json!({ "command": "findTransactions" }),
+                json!({ "command": "getTransactionsToApprove", "depth": MAX_DEPTH + 1 }),
+                json!({ "command": "getBalances", "addresses": [], "threshold": 50 }),
+                json!({ "command": "addNeighbors", "uris": ["udp://127.0.0.1:15600"] }),
+            ] {
+                let error = handle(node, serde_json::from_value(command.clone()).unwrap())
+                    .await
+                    .unwrap_err();
+                assert_eq!(error.status, StatusCode::BAD_REQUEST);
+            }
+        });
+    }
+
+    #[test]
+    fn parse_addresses() {
+        let address = "A".repeat(TX_HASH_LEN);
+        let with_checksum = signing::address::add_checksum(&address).unwrap();
+
+        assert_eq!(parse_address(&address).unwrap().as_trytes(), address);
+        assert_eq!(parse_address(&with_checksum).unwrap().as_trytes(), address);
+
+        let bad_checksum = format!("{}{}", address, "9".repeat(9));
+        let non_ascii = format!("{}Ł", "A".repeat(TX_HASH_LEN - 1));
+        for invalid in &[bad_checksum, non_ascii, "A".repeat(TX_HASH_LEN + 1)] {
+            let error = parse_address(invalid).unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn add_and_remove_neighbors() {
+        let mut runtime = Runtime::new().unwrap();
+        let mut test = test_node("neighbors");
+
+        runtime.block_on(async {
+            let uri = "tcp://127.0.0.1:15600";
+
+            let command = json!({ "command": "addNeighbors", "uris": [uri] });
+            let body = handle(&test.node, serde_json::from_value(command).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(body["addedNeighbors"], 1);
+
+            match test.network.recv().await {
+                Some(NetworkCommand::AddPeer(address)) => {
+                    assert_eq!(address.to_string(), "127.0.0.1:15600")
+                }
+                other => panic!("unexpected command {:?}", other),
+            }
+
+            // Only the network worker adds the peer once it received the command
+            let command = json!({ "command": "removeNeighbors", "uris": [uri] });
+            let body = handle(&test.node, serde_json::from_value(command).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(body["removedNeighbors"], 0);
+        });
+    }
+
+    /// The trytes of a bundle of `len` transactions, from the last index down to index 0.
+    fn bundle_trytes(len: usize) -> Vec<String> {
+        (0..len)
+            .rev()
+            .map(|index| {
+                let mut builder = TransactionBuilder::default();
+                builder
+                    .address("BEE".parse::<Address>().unwrap())
+                    .value(bundle::Value(index as i64))
+                    .index(Index(index))
+                    .last_index(Index(len - 1));
+                trytes_of(&builder.build().to_trits()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn attach_chained_transactions() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("attach");
+        let trunk = "B".repeat(TX_HASH_LEN);
+        let branch = "C".repeat(TX_HASH_LEN);
+
+        runtime.block_on(async {
+            let command = json!({
+                "command": "attachToTangle",
+                "trunkTransaction": trunk,
+                "branchTransaction": branch,
+                "minWeightMagnitude": 3,
+                "trytes": bundle_trytes(2),
+            });
+            let body = handle(&test.node, serde_json::from_value(command).unwrap())
+                .await
+                .unwrap();
+            let attached = body["trytes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|tx_trytes| {
+                    ParsedTransaction::from_trytes(tx_trytes.as_str().unwrap()).unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            // Like IRI, the transactions are returned in index order, the reverse of the given one
+            assert_eq!(attached[0].transaction.index().0, 0);
+            assert_eq!(attached[1].trunk().unwrap().as_trytes(), trunk);
+            assert_eq!(attached[1].branch().unwrap().as_trytes(), branch);
+            assert_eq!(attached[0].trunk().unwrap(), attached[1].hash);
+            assert_eq!(attached[0].branch().unwrap().as_trytes(), trunk);
+            assert!(attached[0].transaction.attachment_ts().0 > 0);
+            // Three trailing zero trits make one trailing '9' tryte
+            assert!(attached
+                .iter()
+                .all(|parsed| parsed.hash.as_trytes().ends_with('9')));
+
+            let mut in_index_order = bundle_trytes(2);
+            in_index_order.reverse();
+            let command = json!({
+                "command": "attachToTangle",
+                "trunkTransaction": trunk,
+                "branchTransaction": branch,
+                "minWeightMagnitude": 3,
+                "trytes": in_index_order,
+            });
+            let error = handle(&test.node, serde_json::from_value(command).unwrap())
+                .await
+                .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        });
+    }
+}
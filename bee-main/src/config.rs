@@ -0,0 +1,607 @@
+use crate::logger::{Filter, Format};
+
+use common::constants::{DEBUG, ENV_VAR, HASH_TRIT_LEN, NETWORK_DIFFICULTY};
+use common::{Error, Result};
+
+use ternary::IsTryte;
+
+use serde::Deserialize;
+
+use std::env;
+use std::fs;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+/// The command line flag to pass a different configuration file.
+pub const CONFIG_FLAG: &str = "--config";
+
+/// The keys that can be overridden by environment variables and command line flags.
+///
+/// A key `section.name` maps to the environment variable `BEE_SECTION_NAME` and to the command line
+/// flag `--section.name`.
+pub const KEYS: [&str; 15] = [
+    "network.port",
+    "network.neighbors",
+    "pow.mwm",
+    "pow.cores",
+    "pow.timeout",
+    "storage.path",
+    "snapshot.path",
+    "coordinator.address",
+    "coordinator.security_level",
+    "coordinator.depth",
+    "logging.level",
+    "logging.format",
+    "logging.file",
+    "api.bind_address",
+    "metrics.bind_address",
+];
+
+const COORDINATOR_ADDRESS_LEN: usize = HASH_TRIT_LEN / 3;
+
+/// The largest Merkle tree depth whose siblings fit in the signature of a single transaction.
+const MAX_COORDINATOR_DEPTH: u8 = 27;
+
+/// The Bee node configuration, usually read from a TOML file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub pow: PowConfig,
+    pub storage: StorageConfig,
+    pub snapshot: SnapshotConfig,
+    pub coordinator: CoordinatorConfig,
+    pub logging: LoggingConfig,
+    pub api: ApiConfig,
+    pub metrics: MetricsConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// The port to listen on for incoming neighbor connections.
+    pub port: u16,
+    /// The addresses (`host:port`) of the static neighbors.
+    pub neighbors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowConfig {
+    /// The minimum weight magnitude (number of trailing zero trits) a transaction hash must have.
+    pub mwm: usize,
+    /// The number of cores used for proof of work; all available cores if not set.
+    pub cores: Option<usize>,
+    /// The number of seconds after which the proof of work of a transaction is given up; never if not set.
+    pub timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotConfig {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoordinatorConfig {
+    /// The address (81 trytes) of the coordinator issuing milestones, the root of its Merkle tree.
+    pub address: String,
+    /// The security level (1, 2 or 3) of the keys the coordinator signs milestones with.
+    pub security_level: u8,
+    /// The depth of the Merkle tree of the coordinator; it can issue `2^depth` milestones.
+    pub depth: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// The default level (`error`, `warn`, `info`, `debug` or `trace`), optionally followed by per-target levels,
+    /// e.g. `info,network=debug`. Overridden by the `BEE` environment variable.
+    pub level: String,
+    /// Either `plain` or `json`.
+    pub format: Format,
+    /// The file the logs are appended to; standard error if not set.
+    pub file: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    pub bind_address: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// The address the Prometheus metrics are served on.
+    pub bind_address: String,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            port: 15600,
+            neighbors: Vec::new(),
+        }
+    }
+}
+
+impl Default for PowConfig {
+    fn default() -> Self {
+        Self {
+            mwm: NETWORK_DIFFICULTY,
+            cores: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            path: String::from("./storage"),
+        }
+    }
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            path: String::from("./snapshot.txt"),
+        }
+    }
+}
+
+impl Default for CoordinatorConfig {
+    fn default() -> Self {
+        Self {
+            address: String::from(
+                "EQSAUZXULTTYZCLNJNTXQTQHOMOFZERHTCGTXOLTVAHKSA9OGAZDEKECURBRIXIJWNPFCQIOVFVVXJVD9",
+            ),
+            security_level: 1,
+            depth: 23,
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            // Debug builds are meant for development, so they are more verbose by default
+            level: String::from(if cfg!(debug_assertions) {
+                DEBUG
+            } else {
+                "info"
+            }),
+            format: Format::Plain,
+            file: None,
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: String::from("127.0.0.1:14265"),
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: String::from("127.0.0.1:9311"),
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| Error::ConfigError(format!("invalid TOML: {}", e)))
+    }
+}
+
+impl Config {
+    /// Reads the configuration file at `filepath`, applies the overrides from the environment and from `args`, and
+    /// validates the result.
+    pub fn load(filepath: &str, args: &[String]) -> Result<Self> {
+        let mut config = Self::from_file(filepath)?;
+
+        config.apply_env()?;
+        config.apply_args(args)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Reads the configuration file at `filepath` without applying any overrides.
+    pub fn from_file(filepath: &str) -> Result<Self> {
+        let content = fs::read_to_string(filepath)
+            .map_err(|e| Error::ConfigError(format!("cannot read '{}': {}", filepath, e)))?;
+
+        toml::from_str(&content)
+            .map_err(|e| Error::ConfigError(format!("{}: invalid TOML: {}", filepath, e)))
+    }
+
+    /// Sets the value of one of the `KEYS` from its string representation.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "network.port" => self.network.port = parse(key, value)?,
+            "network.neighbors" => {
+                self.network.neighbors = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            "pow.mwm" => self.pow.mwm = parse(key, value)?,
+            "pow.cores" => self.pow.cores = Some(parse(key, value)?),
+            "pow.timeout" => self.pow.timeout = Some(parse(key, value)?),
+            "storage.path" => self.storage.path = value.to_string(),
+            "snapshot.path" => self.snapshot.path = value.to_string(),
+            "coordinator.address" => self.coordinator.address = value.to_string(),
+            "coordinator.security_level" => self.coordinator.security_level = parse(key, value)?,
+            "coordinator.depth" => self.coordinator.depth = parse(key, value)?,
+            "logging.level" => self.logging.level = value.to_string(),
+            "logging.format" => self.logging.format = parse(key, value)?,
+            "logging.file" => {
+                self.logging.file = Some(value.to_string()).filter(|file| !file.is_empty())
+            }
+            "api.bind_address" => self.api.bind_address = value.to_string(),
+            "metrics.bind_address" => self.metrics.bind_address = value.to_string(),
+            _ => return Err(Error::ConfigError(format!("unknown key '{}'", key))),
+        }
+
+        Ok(())
+    }
+
+    /// Applies the overrides found in `BEE_<SECTION>_<NAME>` environment variables.
+    pub fn apply_env(&mut self) -> Result<()> {
+        for key in KEYS.iter() {
+            if let Ok(value) = env::var(env_var(key)) {
+                self.set(key, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the overrides given as `--section.name value` command line flags.
+    pub fn apply_args(&mut self, args: &[String]) -> Result<()> {
+        let mut args = args.iter();
+
+        while let Some(flag) = args.next() {
+            let key = match flag.strip_prefix("--") {
+                Some(key) => key,
+                None => {
+                    return Err(Error::ConfigError(format!(
+                        "unexpected argument '{}'",
+                        flag
+                    )))
+                }
+            };
+
+            let value = args
+                .next()
+                .ok_or_else(|| Error::ConfigError(format!("missing value for '{}'", flag)))?;
+
+            if flag != CONFIG_FLAG {
+                self.set(key, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that all values are in range and well-formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.network.port == 0 {
+            return Err(Error::ConfigError(String::from(
+                "network.port: must not be 0",
+            )));
+        }
+
+        for neighbor in &self.network.neighbors {
+            resolve("network.neighbors", neighbor)?;
+        }
+
+        if self.pow.mwm == 0 || self.pow.mwm > HASH_TRIT_LEN {
+            return Err(Error::ConfigError(format!(
+                "pow.mwm: {} is not in 1..={}",
+                self.pow.mwm, HASH_TRIT_LEN
+            )));
+        }
+
+        if self.pow.cores == Some(0) {
+            return Err(Error::ConfigError(String::from("pow.cores: must not be 0")));
+        }
+
+        if self.pow.timeout == Some(0) {
+            return Err(Error::ConfigError(String::from(
+                "pow.timeout: must not be 0",
+            )));
+        }
+
+        if self.storage.path.is_empty() {
+            return Err(Error::ConfigError(String::from(
+                "storage.path: must not be empty",
+            )));
+        }
+
+        if self.snapshot.path.is_empty() {
+            return Err(Error::ConfigError(String::from(
+                "snapshot.path: must not be empty",
+            )));
+        }
+
+        if self.coordinator.address.len() != COORDINATOR_ADDRESS_LEN {
+            return Err(Error::ConfigError(format!(
+                "coordinator.address: expected {} trytes, found {}",
+                COORDINATOR_ADDRESS_LEN,
+                self.coordinator.address.len()
+            )));
+        }
+
+        if let Some((i, c)) = self
+            .coordinator
+            .address
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_tryte())
+        {
+            return Err(Error::ConfigError(format!(
+                "coordinator.address: invalid tryte '{}' at position {}",
+                c, i
+            )));
+        }
+
+        if self.coordinator.security_level == 0 || self.coordinator.security_level > 3 {
+            return Err(Error::ConfigError(format!(
+                "coordinator.security_level: {} is not in 1..=3",
+                self.coordinator.security_level
+            )));
+        }
+
+        if self.coordinator.depth == 0 || self.coordinator.depth > MAX_COORDINATOR_DEPTH {
+            return Err(Error::ConfigError(format!(
+                "coordinator.depth: {} is not in 1..={}",
+                self.coordinator.depth, MAX_COORDINATOR_DEPTH
+            )));
+        }
+
+        if let Err(Error::ConfigError(e)) = Filter::from_str(&self.logging.level) {
+            return Err(Error::ConfigError(format!("logging.level: {}", e)));
+        }
+
+        resolve("api.bind_address", &self.api.bind_address)?;
+        resolve("metrics.bind_address", &self.metrics.bind_address)?;
+
+        Ok(())
+    }
+
+    /// Returns the resolved socket addresses of the static neighbors.
+    pub fn neighbors(&self) -> Result<Vec<SocketAddr>> {
+        self.network
+            .neighbors
+            .iter()
+            .map(|n| resolve("network.neighbors", n))
+            .collect()
+    }
+
+    /// Returns the resolved socket address the API is served on.
+    pub fn api_address(&self) -> Result<SocketAddr> {
+        resolve("api.bind_address", &self.api.bind_address)
+    }
+
+    /// Returns the resolved socket address the metrics are served on.
+    pub fn metrics_address(&self) -> Result<SocketAddr> {
+        resolve("metrics.bind_address", &self.metrics.bind_address)
+    }
+}
+
+/// Returns the configuration file given with `--config`, if any.
+pub fn config_path(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == CONFIG_FLAG)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn env_var(key: &str) -> String {
+    format!("{}_{}", ENV_VAR, key.replace('.', "_").to_uppercase())
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| Error::ConfigError(format!("{}: invalid value '{}': {}", key, value, e)))
+}
+
+fn resolve(key: &str, address: &str) -> Result<SocketAddr> {
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| Error::ConfigError(format!("{}: cannot resolve '{}'", key, address)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [network]
+        port = 15601
+        neighbors = ["127.0.0.1:15602", "127.0.0.1:15603"]
+
+        [pow]
+        mwm = 9
+        cores = 2
+
+        [logging]
+        level = "debug"
+    "#;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn assert_config_error(result: Result<impl std::fmt::Debug>, expected: &str) {
+        match result {
+            Err(Error::ConfigError(details)) => assert!(
+                details.contains(expected),
+                "'{}' does not contain '{}'",
+                details,
+                expected
+            ),
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = CONFIG.parse::<Config>().unwrap();
+
+        assert_eq!(config.network.port, 15601);
+        assert_eq!(config.neighbors().unwrap().len(), 2);
+        assert_eq!(config.pow.mwm, 9);
+        assert_eq!(config.pow.cores, Some(2));
+        assert_eq!(config.logging.level, "debug");
+        assert_eq!(config.storage, StorageConfig::default());
+        assert_eq!(config.api, ApiConfig::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn reject_unknown_field() {
+        assert_config_error("[network]\nprot = 1".parse::<Config>(), "prot");
+    }
+
+    #[test]
+    fn reject_invalid_values() {
+        let mut config = Config::default();
+        config.pow.mwm = 244;
+        assert_config_error(config.validate(), "pow.mwm");
+
+        let mut config = Config::default();
+        config.pow.timeout = Some(0);
+        assert_config_error(config.validate(), "pow.timeout");
+
+        let mut config = Config::default();
+        config.coordinator.address.replace_range(3..4, "a");
+        assert_config_error(config.validate(), "position 3");
+
+        let mut config = Config::default();
+        config.coordinator.security_level = 4;
+        assert_config_error(config.validate(), "coordinator.security_level");
+
+        let mut config = Config::default();
+        config.coordinator.depth = 28;
+        assert_config_error(config.validate(), "coordinator.depth");
+
+        let mut config = Config::default();
+        config.logging.level = String::from("verbose");
+        assert_config_error(config.validate(), "logging.level");
+
+        let mut config = Config::default();
+        config.logging.level = String::from("info,network=loud");
+        assert_config_error(config.validate(), "logging.level");
+
+        let mut config = Config::default();
+        config
+            .network
+            .neighbors
+            .push(String::from("not an address"));
+        assert_config_error(config.validate(), "network.neighbors");
+    }
+
+    #[test]
+    fn override_from_args() {
+        let mut config = CONFIG.parse::<Config>().unwrap();
+
+        config
+            .apply_args(&args(&[
+                "--config",
+                "bee.toml",
+                "--network.port",
+                "16000",
+                "--network.neighbors",
+                "127.0.0.1:1, 127.0.0.1:2, 127.0.0.1:3",
+                "--pow.cores",
+                "1",
+                "--logging.format",
+                "json",
+                "--logging.file",
+                "bee.log",
+            ]))
+            .unwrap();
+
+        assert_eq!(config.network.port, 16000);
+        assert_eq!(config.network.neighbors.len(), 3);
+        assert_eq!(config.pow.cores, Some(1));
+        assert_eq!(config.pow.mwm, 9);
+        assert_eq!(config.logging.format, Format::Json);
+        assert_eq!(config.logging.file.as_deref(), Some("bee.log"));
+
+        config.apply_args(&args(&["--logging.file", ""])).unwrap();
+        assert_eq!(config.logging.file, None);
+    }
+
+    #[test]
+    fn reject_invalid_args() {
+        let mut config = Config::default();
+
+        assert_config_error(
+            config.apply_args(&args(&["--network.port", "x"])),
+            "network.port",
+        );
+        assert_config_error(
+            config.apply_args(&args(&["--foo", "1"])),
+            "unknown key 'foo'",
+        );
+        assert_config_error(config.apply_args(&args(&["--pow.mwm"])), "missing value");
+        assert_config_error(
+            config.apply_args(&args(&["pow.mwm"])),
+            "unexpected argument",
+        );
+    }
+
+    #[test]
+    fn override_from_env() {
+        let mut config = Config::default();
+
+        env::set_var(env_var("snapshot.path"), "/tmp/snapshot.txt");
+        config.apply_env().unwrap();
+        env::remove_var(env_var("snapshot.path"));
+
+        assert_eq!(env_var("snapshot.path"), "BEE_SNAPSHOT_PATH");
+        assert_eq!(config.snapshot.path, "/tmp/snapshot.txt");
+    }
+
+    #[test]
+    fn find_config_path() {
+        assert_eq!(
+            config_path(&args(&["--config", "bee.toml"])),
+            Some("bee.toml")
+        );
+        assert_eq!(config_path(&args(&["--pow.mwm", "9"])), None);
+    }
+}
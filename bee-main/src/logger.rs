@@ -0,0 +1,296 @@
+//! The logger of the node.
+//!
+//! Records are filtered per target with a spec like `info,network=debug,bee_tangle=trace`, taken from the `BEE`
+//! environment variable or, if that is not set, from `logging.level`. A target matches a directive if it is the named
+//! crate or module, or one of its submodules; the most specific directive wins.
+//!
+//! Key-value pairs attached to a record, e.g. `info!(peer:% = address; "connected")`, are written as structured
+//! fields, either as `key=value` after the message or as a `fields` object in JSON output.
+
+use crate::config::LoggingConfig;
+
+use common::constants::ENV_VAR;
+use common::{Error, Result};
+
+use log::kv::{self, Key, Value as KvValue, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The output format of the log records.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Plain,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::ConfigError(format!(
+                "'{}' is not one of [\"plain\", \"json\"]",
+                s
+            ))),
+        }
+    }
+}
+
+/// The levels enabled per target.
+#[derive(Debug, PartialEq)]
+pub struct Filter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut filter = Filter {
+            default: LevelFilter::Info,
+            targets: Vec::new(),
+        };
+
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (target, level) = match directive.find('=') {
+                Some(i) => (Some(&directive[..i]), &directive[i + 1..]),
+                None => (None, directive),
+            };
+
+            let level = LevelFilter::from_str(level).map_err(|_| {
+                Error::ConfigError(format!("invalid level '{}' in '{}'", level, directive))
+            })?;
+
+            match target {
+                Some("") => {
+                    return Err(Error::ConfigError(format!(
+                        "missing target in '{}'",
+                        directive
+                    )))
+                }
+                Some(target) => filter.targets.push((target.to_string(), level)),
+                None => filter.default = level,
+            }
+        }
+
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    /// The most verbose level enabled for `target`.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(name, _)| {
+                target
+                    .strip_prefix(name.as_str())
+                    .map(|rest| rest.is_empty() || rest.starts_with("::"))
+                    .unwrap_or(false)
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// The most verbose level enabled for any target.
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+struct Logger {
+    filter: Filter,
+    format: Format,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format_record(self.format, timestamp(), record);
+        let mut output = self.output.lock().unwrap();
+
+        // There is nowhere left to report a failing log output to
+        let _ = writeln!(output, "{}", line);
+    }
+
+    fn flush(&self) {
+        let _ = self.output.lock().unwrap().flush();
+    }
+}
+
+/// Installs the logger configured by `config`, overridden by the `BEE` environment variable.
+pub fn init(config: &LoggingConfig) -> Result<()> {
+    // The environment variable has already been validated as part of the configuration if it is not set
+    let filter = match env::var(ENV_VAR) {
+        Ok(spec) => Filter::from_str(&spec).map_err(|e| match e {
+            Error::ConfigError(details) => Error::ConfigError(format!("{}: {}", ENV_VAR, details)),
+            e => e,
+        })?,
+        Err(_) => Filter::from_str(&config.level)?,
+    };
+
+    let output: Box<dyn Write + Send> = match &config.file {
+        Some(path) => Box::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| {
+                    Error::ConfigError(format!("cannot open log file '{}': {}", path, e))
+                })?,
+        ),
+        None => Box::new(io::stderr()),
+    };
+
+    let max_level = filter.max_level();
+
+    log::set_boxed_logger(Box::new(Logger {
+        filter,
+        format: config.format,
+        output: Mutex::new(output),
+    }))
+    .map_err(|e| Error::RuntimeError(format!("cannot install the logger: {}", e)))?;
+    log::set_max_level(max_level);
+
+    Ok(())
+}
+
+/// Milliseconds since the Unix epoch.
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn format_record(format: Format, timestamp: u64, record: &Record) -> String {
+    let mut fields = Fields(Vec::new());
+    // Can't fail, collecting the fields never returns an error
+    let _ = record.key_values().visit(&mut fields);
+
+    match format {
+        Format::Plain => {
+            let mut line = format!(
+                "{} {:<5} {}: {}",
+                timestamp,
+                record.level(),
+                record.target(),
+                record.args()
+            );
+
+            for (key, value) in fields.0 {
+                line.push_str(&format!(" {}={}", key, value));
+            }
+
+            line
+        }
+        Format::Json => json!({
+            "timestamp": timestamp,
+            "level": record.level().to_string(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+            "fields": fields.0.into_iter().map(|(k, v)| (k, Value::String(v))).collect::<Map<_, _>>(),
+        })
+        .to_string(),
+    }
+}
+
+struct Fields(Vec<(String, String)>);
+
+impl<'kvs> VisitSource<'kvs> for Fields {
+    fn visit_pair(
+        &mut self,
+        key: Key<'kvs>,
+        value: KvValue<'kvs>,
+    ) -> std::result::Result<(), kv::Error> {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use log::Level;
+
+    #[test]
+    fn parse_filter() {
+        let filter =
+            Filter::from_str("warn, network=debug,network::packet=trace,bee_tangle=off").unwrap();
+
+        assert_eq!(filter.level("main::api"), LevelFilter::Warn);
+        assert_eq!(filter.level("network"), LevelFilter::Debug);
+        assert_eq!(filter.level("network::peer"), LevelFilter::Debug);
+        assert_eq!(filter.level("network::packet"), LevelFilter::Trace);
+        assert_eq!(filter.level("networking"), LevelFilter::Warn);
+        assert_eq!(filter.level("bee_tangle"), LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+
+        assert_eq!(
+            Filter::from_str("").unwrap().level("pow"),
+            LevelFilter::Info
+        );
+    }
+
+    #[test]
+    fn reject_invalid_filter() {
+        assert!(Filter::from_str("verbose").is_err());
+        assert!(Filter::from_str("network=loud").is_err());
+        assert!(Filter::from_str("=debug").is_err());
+    }
+
+    #[test]
+    fn format_fields() {
+        let fields: &[(&str, &str)] = &[("peer", "127.0.0.1:15600"), ("hash", "ABC")];
+        let args = format_args!("connected");
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("network")
+            .args(args)
+            .key_values(&fields)
+            .build();
+
+        assert_eq!(
+            format_record(Format::Plain, 42, &record),
+            "42 INFO  network: connected peer=127.0.0.1:15600 hash=ABC"
+        );
+
+        let json: Value = serde_json::from_str(&format_record(Format::Json, 42, &record)).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "timestamp": 42,
+                "level": "INFO",
+                "target": "network",
+                "message": "connected",
+                "fields": { "peer": "127.0.0.1:15600", "hash": "ABC" },
+            })
+        );
+    }
+}
@@ -1,17 +1,28 @@
+mod api;
+mod config;
+mod logger;
+mod metrics;
+mod milestone;
+mod node;
 mod prototype;
+mod workers;
 
-use common::constants::{CONFIG, DEBUG, ENV_VAR};
+use common::constants::CONFIG;
 
+use crate::config::config_path;
 use crate::prototype::Prototype;
 
 use std::env;
+use std::process;
 
 fn main() {
-    env::set_var(ENV_VAR, DEBUG);
+    let args = env::args().skip(1).collect::<Vec<String>>();
 
-    let mut prototype = Prototype::from_config(CONFIG);
+    let result = Prototype::from_config(config_path(&args).unwrap_or(CONFIG), &args)
+        .and_then(|mut prototype| prototype.run());
 
-    assert!(prototype.run().is_ok());
-
-    env::remove_var(ENV_VAR);
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
 }
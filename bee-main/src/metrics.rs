@@ -0,0 +1,337 @@
+//! Metrics of the node internals, served in the Prometheus text exposition format on `GET /metrics`.
+
+use crate::node::Node;
+use crate::workers::Shutdown;
+
+use common::{Error, Result};
+
+use network::PeerId;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+
+use std::convert::Infallible;
+use std::fmt::{Display, Write};
+use std::net::{SocketAddr, TcpListener};
+
+/// The content type of the text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Serves the metrics on `listener` until shutdown.
+pub async fn metrics_worker(node: Node, listener: TcpListener, shutdown: Shutdown) -> Result<()> {
+    let service = make_service_fn(move |_| {
+        let node = node.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| serve(node.clone(), request))) }
+    });
+
+    Server::from_tcp(listener)
+        .map_err(|e| Error::RuntimeError(format!("cannot serve the metrics: {}", e)))?
+        .serve(service)
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| Error::RuntimeError(format!("metrics server failed: {}", e)))
+}
+
+/// Binds the listener of the metrics endpoint, so that a taken address is reported before any worker is started.
+pub fn bind(address: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(address)
+        .map_err(|e| Error::RuntimeError(format!("cannot bind the metrics to {}: {}", address, e)))
+}
+
+async fn serve(
+    node: Node,
+    request: Request<Body>,
+) -> std::result::Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header("Content-Type", CONTENT_TYPE)
+            .body(Body::from(render(&node))),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+
+    // Can't fail, the status and header are valid
+    Ok(response.unwrap())
+}
+
+/// Renders all metrics of the node.
+pub fn render(node: &Node) -> String {
+    let mut out = String::new();
+
+    {
+        let peers = node.peers.lock().unwrap();
+        let mut peers = peers.iter().collect::<Vec<_>>();
+        peers.sort_by_key(|(PeerId(address), _)| *address);
+
+        header(&mut out, "bee_neighbors", "gauge", "Number of neighbors.");
+        sample(&mut out, "bee_neighbors", &[], peers.len());
+
+        header(
+            &mut out,
+            "bee_transactions_received_total",
+            "counter",
+            "Transactions received per neighbor.",
+        );
+        for (PeerId(address), peer) in &peers {
+            let address = address.to_string();
+            let received = peer.metrics().transactions_received();
+            sample(
+                &mut out,
+                "bee_transactions_received_total",
+                &[("peer", &address)],
+                received,
+            );
+        }
+
+        header(
+            &mut out,
+            "bee_transactions_sent_total",
+            "counter",
+            "Transactions sent per neighbor.",
+        );
+        for (PeerId(address), peer) in &peers {
+            let address = address.to_string();
+            let sent = peer.metrics().transactions_sent();
+            sample(
+                &mut out,
+                "bee_transactions_sent_total",
+                &[("peer", &address)],
+                sent,
+            );
+        }
+    }
+
+    {
+        let tangle = node.tangle.read().unwrap();
+
+        header(
+            &mut out,
+            "bee_tangle_transactions",
+            "gauge",
+            "Transactions in the Tangle.",
+        );
+        sample(
+            &mut out,
+            "bee_tangle_transactions",
+            &[("state", "solid")],
+            tangle.solid_len(),
+        );
+        sample(
+            &mut out,
+            "bee_tangle_transactions",
+            &[("state", "unsolid")],
+            tangle.len() - tangle.solid_len(),
+        );
+
+        header(
+            &mut out,
+            "bee_tangle_missing_transactions",
+            "gauge",
+            "Transactions approved by the Tangle, but not received yet.",
+        );
+        sample(
+            &mut out,
+            "bee_tangle_missing_transactions",
+            &[],
+            tangle.missing_len(),
+        );
+    }
+
+    let latest_solid_milestone_index = node
+        .latest_solid_milestone
+        .read()
+        .unwrap()
+        .map(|(_, index)| index);
+    header(
+        &mut out,
+        "bee_latest_solid_milestone_index",
+        "gauge",
+        "Index of the latest solid milestone.",
+    );
+    sample(
+        &mut out,
+        "bee_latest_solid_milestone_index",
+        &[],
+        latest_solid_milestone_index.unwrap_or(0),
+    );
+
+    let pow = pow::metrics();
+    header(
+        &mut out,
+        "bee_pow_hashes_total",
+        "counter",
+        "Hashes computed by the proof of work.",
+    );
+    sample(&mut out, "bee_pow_hashes_total", &[], pow.hashes());
+    header(
+        &mut out,
+        "bee_pow_last_search_hashes_per_second",
+        "gauge",
+        "Hash rate of the last finished proof of work.",
+    );
+    sample(
+        &mut out,
+        "bee_pow_last_search_hashes_per_second",
+        &[],
+        pow.last_search_hashes_per_second(),
+    );
+
+    {
+        let storage = node.storage.lock().unwrap();
+
+        header(
+            &mut out,
+            "bee_storage_records",
+            "gauge",
+            "Records in the storage.",
+        );
+        sample(&mut out, "bee_storage_records", &[], storage.len());
+        header(
+            &mut out,
+            "bee_storage_bytes",
+            "gauge",
+            "Size of the storage on disk.",
+        );
+        sample(&mut out, "bee_storage_bytes", &[], storage.size());
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    // Can't fail, writing to a `String` never does
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl Display) {
+    out.push_str(name);
+
+    if !labels.is_empty() {
+        let labels = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+            .collect::<Vec<_>>();
+        out.push('{');
+        out.push_str(&labels.join(","));
+        out.push('}');
+    }
+
+    let _ = writeln!(out, " {}", value);
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node::tests::test_node;
+    use crate::workers::Workers;
+
+    use bee_tangle::{Tx, TxHash, Vertex, TX_HASH_LEN};
+    use network::Peer;
+
+    use hyper::Client;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn render_node_metrics() {
+        let test = test_node("metrics");
+        let address = SocketAddr::from(([127, 0, 0, 1], 15600));
+        let hash = TxHash::from_trytes(&"A".repeat(TX_HASH_LEN)).unwrap();
+        let missing = TxHash::from_trytes(&"B".repeat(TX_HASH_LEN)).unwrap();
+
+        let peer = Peer::new(address);
+        peer.metrics().transaction_received();
+        peer.metrics().transaction_received();
+        peer.metrics().transaction_sent();
+        test.node
+            .peers
+            .lock()
+            .unwrap()
+            .insert(PeerId(address), peer);
+
+        test.node
+            .tangle
+            .write()
+            .unwrap()
+            .insert(Vertex::new(hash, Tx::new(missing, TxHash::genesis())));
+        test.node.storage.lock().unwrap().insert(b"key", b"value");
+        *test.node.latest_solid_milestone.write().unwrap() = Some((hash, 42));
+
+        let metrics = render(&test.node);
+
+        for line in &[
+            "# TYPE bee_neighbors gauge",
+            "bee_neighbors 1",
+            "bee_transactions_received_total{peer=\"127.0.0.1:15600\"} 2",
+            "bee_transactions_sent_total{peer=\"127.0.0.1:15600\"} 1",
+            "bee_tangle_transactions{state=\"solid\"} 0",
+            "bee_tangle_transactions{state=\"unsolid\"} 1",
+            "bee_tangle_missing_transactions 1",
+            "bee_latest_solid_milestone_index 42",
+            "# TYPE bee_pow_hashes_total counter",
+            "# TYPE bee_pow_last_search_hashes_per_second gauge",
+            "bee_storage_records 1",
+            "bee_storage_bytes 16",
+        ] {
+            assert!(
+                metrics.lines().any(|l| l == *line),
+                "'{}' not found in\n{}",
+                line,
+                metrics
+            );
+        }
+    }
+
+    #[test]
+    fn escape_label_values() {
+        let mut out = String::new();
+        sample(&mut out, "metric", &[("label", "a\"b\\c\nd")], 1);
+
+        assert_eq!(out, "metric{label=\"a\\\"b\\\\c\\nd\"} 1\n");
+    }
+
+    #[test]
+    fn serve_on_localhost() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("metrics-serve");
+
+        runtime.block_on(async {
+            let listener = bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+            let address = listener.local_addr().unwrap();
+
+            let mut workers = Workers::new();
+            workers.spawn("metrics", |shutdown| {
+                metrics_worker(test.node.clone(), listener, shutdown)
+            });
+
+            let client = Client::new();
+
+            let response = client
+                .get(format!("http://{}/metrics", address).parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["Content-Type"], CONTENT_TYPE);
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert!(std::str::from_utf8(&body)
+                .unwrap()
+                .contains("bee_neighbors 0"));
+
+            let response = client
+                .get(format!("http://{}/", address).parse().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+            workers.shutdown().await.unwrap();
+        });
+    }
+}
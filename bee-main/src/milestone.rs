@@ -0,0 +1,343 @@
+//! The validation of the milestones issued by the coordinator.
+//!
+//! A milestone is a bundle starting with `security_level` transactions holding the signature of the coordinator,
+//! followed by a transaction holding the siblings of the leaf of the coordinator's Merkle tree. The coordinator signs
+//! the normalized hash of the siblings transaction, whose trunk and branch are the transactions the milestone
+//! confirms, and all signature transactions branch off the trunk of the siblings transaction.
+
+use crate::node::{Node, ParsedTransaction};
+
+use common::constants::HASH_TRIT_LEN;
+
+use bundle::{Transaction, OBSOLETE_TAG, PAYLOAD};
+use crypto::hashes_preview::{CurlP27, Sponge, Trits, TritsMut};
+use signing::wots::{normalize_hash, WotsSignature};
+use signing::{PublicKey, RecoverableSignature, Signature};
+use ternary::iota_conversion::{long_value, Trinary};
+
+use bee_tangle::TxHash;
+
+use std::convert::TryFrom;
+
+/// The sponge the coordinator signs milestones with.
+pub type CoordinatorSponge = CurlP27;
+
+/// The number of trits of the obsolete tag encoding the index of a milestone.
+const MILESTONE_INDEX_TRIT_LEN: usize = 15;
+
+/// Returns the index of a milestone, encoded in the first trits of its obsolete tag, unless it is negative.
+pub fn milestone_index(parsed: &ParsedTransaction) -> Option<u32> {
+    let trits = &parsed.trits[OBSOLETE_TAG.trit_offset.start..][..MILESTONE_INDEX_TRIT_LEN];
+
+    u32::try_from(long_value(trits)).ok()
+}
+
+/// Whether `tail` is the tail of the milestone bundle with the given index, signed by the coordinator with `S`.
+///
+/// The whole bundle must already be stored; a milestone with missing transactions is invalid.
+pub fn is_valid_milestone<S: Sponge + Default>(node: &Node, tail: TxHash, index: u32) -> bool {
+    let coordinator = &node.config.coordinator;
+    let security_level = coordinator.security_level as usize;
+
+    let mut transactions = Vec::with_capacity(security_level + 1);
+    let mut hash = tail;
+
+    for current_index in 0..=security_level {
+        let parsed = match node
+            .transaction_trytes(hash)
+            .and_then(|tx_trytes| ParsedTransaction::from_trytes(&tx_trytes).ok())
+        {
+            Some(parsed) => parsed,
+            None => return false,
+        };
+
+        if !is_in_bundle(&parsed.transaction, current_index, security_level)
+            || transactions
+                .first()
+                .is_some_and(|tail: &ParsedTransaction| {
+                    tail.transaction.bundle_hash().0 != parsed.transaction.bundle_hash().0
+                })
+        {
+            return false;
+        }
+
+        hash = match parsed.trunk() {
+            Ok(trunk) => trunk,
+            Err(_) => return false,
+        };
+        transactions.push(parsed);
+    }
+
+    // Can't fail, there are `security_level + 1` transactions
+    let siblings = transactions.pop().unwrap();
+
+    let confirmed = siblings.trunk().ok();
+    if transactions
+        .iter()
+        .any(|parsed| parsed.branch().ok() != confirmed)
+    {
+        return false;
+    }
+
+    let signature = transactions
+        .iter()
+        .flat_map(|parsed| payload(parsed).iter().copied())
+        .collect::<Vec<_>>();
+    let message = normalize_hash(&siblings.hash.as_trytes().trits());
+    let address = WotsSignature::<S>::from_bytes(&signature).recover_public_key(&message);
+
+    match merkle_root::<S>(
+        address.to_bytes(),
+        payload(&siblings),
+        index,
+        coordinator.depth,
+    ) {
+        Some(root) => root.trytes().is_ok_and(|root| root == coordinator.address),
+        None => false,
+    }
+}
+
+/// Whether a transaction is at `current_index` of a bundle long enough to hold a milestone.
+fn is_in_bundle(transaction: &Transaction, current_index: usize, security_level: usize) -> bool {
+    transaction.index().0 == current_index && transaction.last_index().0 >= security_level
+}
+
+fn payload(parsed: &ParsedTransaction) -> &[i8] {
+    &parsed.trits[PAYLOAD.trit_offset.start..][..PAYLOAD.trit_offset.length]
+}
+
+/// Computes the root of a Merkle tree of the given depth from a leaf at `index` and its siblings, bottom up.
+///
+/// Returns `None` if `index` is not a leaf of the tree.
+fn merkle_root<S: Sponge + Default>(
+    leaf: &[i8],
+    siblings: &[i8],
+    index: u32,
+    depth: u8,
+) -> Option<Vec<i8>> {
+    if index.checked_shr(depth as u32).unwrap_or(0) != 0 {
+        return None;
+    }
+
+    let mut sponge = S::default();
+    let mut hash = leaf.to_vec();
+    let mut index = index;
+
+    for sibling in siblings.chunks(HASH_TRIT_LEN).take(depth as usize) {
+        let (left, right) = if index & 1 == 0 {
+            (&hash[..], sibling)
+        } else {
+            (sibling, &hash[..])
+        };
+
+        sponge.absorb(&Trits::from_i8_unchecked(left)).ok()?;
+        sponge.absorb(&Trits::from_i8_unchecked(right)).ok()?;
+
+        let mut parent = vec![0; HASH_TRIT_LEN];
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut parent))
+            .ok()?;
+        sponge.reset();
+
+        hash = parent;
+        index >>= 1;
+    }
+
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node::tests::test_node;
+    use crate::node::transaction_hash;
+
+    use bundle::{
+        Field, ADDRESS, BRANCH_HASH, BUNDLE_HASH, INDEX, LAST_INDEX, PAYLOAD, TRUNK_HASH,
+    };
+    use common::constants::TRANSACTION_TRYT_LEN;
+    use signing::seed::Seed;
+    use signing::wots::WotsPrivateKeyGeneratorBuilder;
+    use signing::{PrivateKey, PrivateKeyGenerator};
+
+    use std::sync::Arc;
+
+    const SEED: &str =
+        "COORDINATORSEED9COORDINATORSEED9COORDINATORSEED9COORDINATORSEED9COORDINATORSEED99";
+    const DEPTH: u8 = 2;
+
+    /// The transactions of a milestone bundle, tail first, and the address of the coordinator that signed it.
+    struct Milestone {
+        coordinator: String,
+        transactions: Vec<String>,
+    }
+
+    fn set(tx_trytes: &mut String, field: &Field, trytes: &str) {
+        let start = field.tryte_offset.start;
+        tx_trytes.replace_range(start..start + trytes.len(), trytes);
+    }
+
+    fn set_number(tx_trytes: &mut String, field: &Field, number: i64) {
+        let trytes = number
+            .trits_with_length(field.trit_offset.length)
+            .trytes()
+            .unwrap();
+        set(tx_trytes, field, &trytes);
+    }
+
+    fn hash_pair(left: &[i8], right: &[i8]) -> Vec<i8> {
+        let mut sponge = CoordinatorSponge::default();
+        let mut hash = vec![0; HASH_TRIT_LEN];
+
+        sponge.absorb(&Trits::from_i8_unchecked(left)).unwrap();
+        sponge.absorb(&Trits::from_i8_unchecked(right)).unwrap();
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+            .unwrap();
+
+        hash
+    }
+
+    /// A milestone with the given index, issued by a coordinator of depth `DEPTH` and security level 1, with
+    /// `obsolete_tag_index` encoded in its tail.
+    fn milestone(index: u32, obsolete_tag_index: i64) -> Milestone {
+        let seed = Seed::from_bytes(&SEED.trits()).unwrap();
+        let generator = WotsPrivateKeyGeneratorBuilder::<CoordinatorSponge>::default()
+            .security_level(1)
+            .build()
+            .unwrap();
+
+        // Only the leaf of the milestone is needed, the other nodes of the tree can be anything
+        let mut root = generator
+            .generate(&seed, index as u64)
+            .generate_public_key()
+            .to_bytes()
+            .to_vec();
+        let mut siblings = Vec::new();
+
+        for depth in 0..DEPTH {
+            let sibling = ((b'S' + depth) as char).to_string().repeat(81).trits();
+            root = if index >> depth & 1 == 0 {
+                hash_pair(&root, &sibling)
+            } else {
+                hash_pair(&sibling, &root)
+            };
+            siblings.extend(sibling);
+        }
+
+        let coordinator = root.trytes().unwrap();
+        let bundle_hash = "B".repeat(81);
+        let confirmed_trunk = "T".repeat(81);
+
+        let mut siblings_tx = "9".repeat(TRANSACTION_TRYT_LEN);
+        set(&mut siblings_tx, &PAYLOAD, &siblings.trytes().unwrap());
+        set_number(&mut siblings_tx, &INDEX, 1);
+        set_number(&mut siblings_tx, &LAST_INDEX, 1);
+        set(&mut siblings_tx, &BUNDLE_HASH, &bundle_hash);
+        set(&mut siblings_tx, &TRUNK_HASH, &confirmed_trunk);
+        set(&mut siblings_tx, &BRANCH_HASH, &"R".repeat(81));
+        let siblings_hash = transaction_hash(&siblings_tx.trits()).unwrap();
+
+        let message = normalize_hash(&siblings_hash.as_trytes().trits());
+        let signature = generator
+            .generate(&seed, index as u64)
+            .sign(&message)
+            .to_bytes()
+            .trytes()
+            .unwrap();
+
+        let mut tail = "9".repeat(TRANSACTION_TRYT_LEN);
+        set(&mut tail, &PAYLOAD, &signature);
+        set(&mut tail, &ADDRESS, &coordinator);
+        set_number(&mut tail, &OBSOLETE_TAG, obsolete_tag_index);
+        set_number(&mut tail, &LAST_INDEX, 1);
+        set(&mut tail, &BUNDLE_HASH, &bundle_hash);
+        set(&mut tail, &TRUNK_HASH, siblings_hash.as_trytes());
+        set(&mut tail, &BRANCH_HASH, &confirmed_trunk);
+
+        Milestone {
+            coordinator,
+            transactions: vec![tail, siblings_tx],
+        }
+    }
+
+    /// Stores the transactions of `milestone` in a node trusting its coordinator, and returns the hash of its tail.
+    fn store(node: &mut Node, milestone: &Milestone) -> TxHash {
+        let mut config = (*node.config).clone();
+        config.coordinator.address = milestone.coordinator.clone();
+        config.coordinator.security_level = 1;
+        config.coordinator.depth = DEPTH;
+        node.config = Arc::new(config);
+
+        let mut storage = node.storage.lock().unwrap();
+        let hashes = milestone
+            .transactions
+            .iter()
+            .map(|tx_trytes| {
+                let hash = transaction_hash(&tx_trytes.trits()).unwrap();
+                storage.insert(hash.as_trytes().as_bytes(), tx_trytes.as_bytes());
+                hash
+            })
+            .collect::<Vec<_>>();
+
+        hashes[0]
+    }
+
+    #[test]
+    fn validate_milestone() {
+        let mut test = test_node("valid-milestone");
+        let milestone = milestone(2, 2);
+        let tail = store(&mut test.node, &milestone);
+
+        let parsed = ParsedTransaction::from_trytes(&milestone.transactions[0]).unwrap();
+        assert_eq!(milestone_index(&parsed), Some(2));
+
+        assert!(is_valid_milestone::<CoordinatorSponge>(&test.node, tail, 2));
+        // The index is part of the Merkle path the signature is checked against
+        assert!(!is_valid_milestone::<CoordinatorSponge>(
+            &test.node, tail, 3
+        ));
+        assert!(!is_valid_milestone::<CoordinatorSponge>(
+            &test.node, tail, 6
+        ));
+    }
+
+    #[test]
+    fn reject_forged_milestones() {
+        let milestone = milestone(1, 1);
+
+        let mut test = test_node("forged-milestone");
+        let mut forged = Milestone {
+            coordinator: milestone.coordinator.clone(),
+            transactions: milestone.transactions.clone(),
+        };
+        let replacement = if forged.transactions[0].starts_with('A') {
+            "B"
+        } else {
+            "A"
+        };
+        forged.transactions[0].replace_range(0..1, replacement);
+        let tail = store(&mut test.node, &forged);
+        assert!(!is_valid_milestone::<CoordinatorSponge>(
+            &test.node, tail, 1
+        ));
+
+        let mut test = test_node("incomplete-milestone");
+        let mut incomplete = milestone;
+        incomplete.transactions.pop();
+        let tail = store(&mut test.node, &incomplete);
+        assert!(!is_valid_milestone::<CoordinatorSponge>(
+            &test.node, tail, 1
+        ));
+    }
+
+    #[test]
+    fn reject_negative_milestone_index() {
+        let mut tail = "9".repeat(TRANSACTION_TRYT_LEN);
+        set_number(&mut tail, &OBSOLETE_TAG, -5);
+        let parsed = ParsedTransaction::from_trytes(&tail).unwrap();
+
+        assert_eq!(milestone_index(&parsed), None);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::config::Config;
+use crate::milestone::milestone_index;
+
+use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+use common::{Error, Result, Trit};
+
+use bundle::Transaction;
+use crypto::hashes_preview::{CurlP81, Sponge, Trits, TritsMut};
+use ternary::iota_conversion::Trinary;
+
+use bee_storage::Storage;
+use bee_tangle::{Tangle, Tx, TxHash, Vertex};
+
+use network::{Peer, PeerId};
+use pow::{Difficulty, InputTrits};
+
+use log::{debug, warn};
+
+use tokio::sync::mpsc;
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// The commands the network worker accepts from the rest of the node.
+#[derive(Debug)]
+pub enum NetworkCommand {
+    AddPeer(SocketAddr),
+    RemovePeer(SocketAddr),
+    Broadcast(Vec<u8>),
+}
+
+/// The transactions indexed by the fields they can be searched by.
+#[derive(Default)]
+pub struct Indices {
+    addresses: HashMap<String, Vec<TxHash>>,
+    bundles: HashMap<String, Vec<TxHash>>,
+    tags: HashMap<String, Vec<TxHash>>,
+}
+
+/// The handles to the state and channels shared between the workers.
+#[derive(Clone)]
+pub struct Node {
+    pub config: Arc<Config>,
+    pub storage: Arc<Mutex<Storage>>,
+    pub tangle: Arc<RwLock<Tangle>>,
+    pub indices: Arc<RwLock<Indices>>,
+    pub peers: Arc<Mutex<HashMap<PeerId, Peer>>>,
+    pub latest_solid_milestone: Arc<RwLock<Option<(TxHash, u32)>>>,
+    /// The trits of the transactions received from neighbors, to be verified in batches.
+    pub transactions: mpsc::Sender<(SocketAddr, Vec<Trit>)>,
+    pub vertices: mpsc::Sender<Vertex>,
+    pub milestones: mpsc::Sender<(TxHash, u32)>,
+    pub network: mpsc::Sender<NetworkCommand>,
+}
+
+/// A transaction that was parsed and hashed, but not stored yet.
+pub struct ParsedTransaction {
+    pub hash: TxHash,
+    pub trits: Vec<Trit>,
+    pub transaction: Transaction,
+}
+
+impl ParsedTransaction {
+    /// Parses and hashes the trytes of a transaction.
+    pub fn from_trytes(tx_trytes: &str) -> Result<Self> {
+        let transaction = Transaction::from_tryte_str(tx_trytes)?;
+        let trits = tx_trytes.to_string().trits();
+
+        Ok(Self {
+            hash: transaction_hash(&trits)?,
+            trits,
+            transaction,
+        })
+    }
+
+    /// Parses the trits of a transaction whose CurlP81 hash is known already, e.g. because it was hashed in a batch.
+    pub fn from_hashed_trits(trits: Vec<Trit>, hash: &[Trit]) -> Result<Self> {
+        let tx_trytes = (&trits[..]).trytes().map_err(|_| Error::TransactionError)?;
+
+        Ok(Self {
+            hash: to_tx_hash_from_trits(hash)?,
+            transaction: Transaction::from_tryte_str(&tx_trytes)?,
+            trits,
+        })
+    }
+
+    pub fn trytes(&self) -> Result<String> {
+        self.trits.trytes().map_err(|_| Error::TransactionError)
+    }
+
+    pub fn trunk(&self) -> Result<TxHash> {
+        to_tx_hash(&self.transaction.trunk_hash().0)
+    }
+
+    pub fn branch(&self) -> Result<TxHash> {
+        to_tx_hash(&self.transaction.branch_hash().0)
+    }
+}
+
+impl Node {
+    /// Loads all transactions found in the storage into the indices and the Tangle, and returns the milestones among
+    /// them so that they can be handed over to the milestone tracker.
+    pub fn load(&self) -> Result<Vec<(TxHash, u32)>> {
+        let storage = self.storage.lock().unwrap();
+        let mut tangle = self.tangle.write().unwrap();
+        let mut indices = self.indices.write().unwrap();
+        let mut milestones = Vec::new();
+
+        for (_, tx_trytes) in storage.iter() {
+            let tx_trytes = std::str::from_utf8(tx_trytes).map_err(|_| {
+                Error::StorageError(String::from("stored transaction is not valid trytes"))
+            })?;
+            let parsed = ParsedTransaction::from_trytes(tx_trytes)?;
+
+            if let Some(milestone) = self.milestone(&parsed) {
+                milestones.push(milestone);
+            }
+
+            indices.insert(&parsed);
+            tangle.insert(Vertex::new(
+                parsed.hash,
+                Tx::new(parsed.trunk()?, parsed.branch()?),
+            ));
+        }
+
+        Ok(milestones)
+    }
+
+    /// Stores a transaction, indexes it and hands it over to the solidifier (and the milestone tracker, if it was
+    /// issued by the coordinator).
+    pub async fn store_transaction(&self, parsed: &ParsedTransaction) -> Result<()> {
+        let is_new = {
+            let mut storage = self.storage.lock().unwrap();
+
+            if storage.contains(parsed.hash.as_trytes().as_bytes()) {
+                false
+            } else {
+                storage.insert(
+                    parsed.hash.as_trytes().as_bytes(),
+                    parsed.trytes()?.as_bytes(),
+                );
+                true
+            }
+        };
+
+        if !is_new {
+            return Ok(());
+        }
+
+        self.indices.write().unwrap().insert(parsed);
+        debug!(hash = parsed.hash.as_trytes(); "transaction stored");
+
+        let vertex = Vertex::new(parsed.hash, Tx::new(parsed.trunk()?, parsed.branch()?));
+        self.vertices
+            .clone()
+            .send(vertex)
+            .await
+            .map_err(|_| Error::RuntimeError(String::from("the solidifier is not running")))?;
+
+        if let Some(milestone) = self.milestone(parsed) {
+            self.milestones.clone().send(milestone).await.map_err(|_| {
+                Error::RuntimeError(String::from("the milestone tracker is not running"))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a transaction to all connected neighbors.
+    pub async fn broadcast_transaction(&self, parsed: &ParsedTransaction) -> Result<()> {
+        let packet = network::encode_transaction(&parsed.trits).ok_or(Error::TransactionError)?;
+
+        self.network_command(NetworkCommand::Broadcast(packet))
+            .await
+    }
+
+    pub async fn network_command(&self, command: NetworkCommand) -> Result<()> {
+        self.network
+            .clone()
+            .send(command)
+            .await
+            .map_err(|_| Error::NetworkError)
+    }
+
+    /// Checks the proof of work of the transactions against the minimum weight magnitude of the node.
+    pub fn verify_pow(&self, transactions: &[ParsedTransaction]) -> Vec<bool> {
+        let inputs = transactions
+            .iter()
+            // Can't fail, parsed transactions are exactly one transaction long
+            .map(|parsed| InputTrits::try_from(&parsed.trits[..]).unwrap())
+            .collect::<Vec<_>>();
+
+        pow::verify_batch(&inputs, &Difficulty::from(self.config.pow.mwm))
+    }
+
+    /// Whether the CurlP81 `hash` of a transaction ends with the zero trits asked for by the minimum weight magnitude of
+    /// the node.
+    pub fn meets_mwm(&self, hash: &[Trit]) -> bool {
+        hash.iter()
+            .rev()
+            .take(self.config.pow.mwm)
+            .all(|&trit| trit == 0)
+    }
+
+    /// Returns the trytes of the stored transaction with the given hash.
+    pub fn transaction_trytes(&self, hash: TxHash) -> Option<String> {
+        self.storage
+            .lock()
+            .unwrap()
+            .get(hash.as_trytes().as_bytes())
+            .and_then(|tx_trytes| String::from_utf8(tx_trytes.to_vec()).ok())
+    }
+
+    /// Returns the hash and index of a transaction that claims to be the tail of a milestone, issued by the
+    /// coordinator with a valid index; the milestone tracker validates its signature once its bundle is solid.
+    fn milestone(&self, parsed: &ParsedTransaction) -> Option<(TxHash, u32)> {
+        let transaction = &parsed.transaction;
+
+        if transaction.index().0 != 0
+            || transaction.address().0.iter().collect::<String>() != self.config.coordinator.address
+        {
+            return None;
+        }
+
+        match milestone_index(parsed) {
+            Some(index) => Some((parsed.hash, index)),
+            None => {
+                warn!(hash = parsed.hash.as_trytes(); "dropping milestone with a negative index");
+                None
+            }
+        }
+    }
+}
+
+impl Indices {
+    fn insert(&mut self, parsed: &ParsedTransaction) {
+        let transaction = &parsed.transaction;
+
+        self.addresses
+            .entry(transaction.address().0.iter().collect())
+            .or_default()
+            .push(parsed.hash);
+        self.bundles
+            .entry(transaction.bundle_hash().0.iter().collect())
+            .or_default()
+            .push(parsed.hash);
+        self.tags
+            .entry(transaction.tag().0.iter().collect())
+            .or_default()
+            .push(parsed.hash);
+    }
+
+    pub fn by_address(&self, address: &str) -> HashSet<TxHash> {
+        lookup(&self.addresses, address)
+    }
+
+    pub fn by_bundle(&self, bundle: &str) -> HashSet<TxHash> {
+        lookup(&self.bundles, bundle)
+    }
+
+    pub fn by_tag(&self, tag: &str) -> HashSet<TxHash> {
+        lookup(&self.tags, tag)
+    }
+}
+
+fn lookup(index: &HashMap<String, Vec<TxHash>>, key: &str) -> HashSet<TxHash> {
+    index.get(key).into_iter().flatten().copied().collect()
+}
+
+/// Computes the CurlP81 hash of the trits of a transaction.
+pub fn transaction_hash(trits: &[Trit]) -> Result<TxHash> {
+    if trits.len() != TRANSACTION_TRIT_LEN {
+        return Err(Error::TransactionError);
+    }
+
+    let mut curl = CurlP81::new();
+    let mut hash = [0; HASH_TRIT_LEN];
+
+    // Can't fail, CurlP absorbs and squeezes any number of trits
+    curl.absorb(&Trits::from_i8_unchecked(trits)).unwrap();
+    curl.squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+        .unwrap();
+
+    to_tx_hash_from_trits(&hash)
+}
+
+fn to_tx_hash_from_trits(hash: &[Trit]) -> Result<TxHash> {
+    let hash = hash.trytes().map_err(|_| Error::TransactionError)?;
+
+    TxHash::from_trytes(&hash).ok_or(Error::TransactionError)
+}
+
+fn to_tx_hash(trytes: &[char]) -> Result<TxHash> {
+    TxHash::from_trytes(&trytes.iter().collect::<String>()).ok_or(Error::TransactionError)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    use bundle::ADDRESS;
+
+    use common::constants::TRANSACTION_TRYT_LEN;
+
+    use pow::{Cores, PearlDiver};
+
+    /// The minimum weight magnitude of test nodes, low enough to do the proof of work of test transactions quickly.
+    pub const TEST_MWM: usize = 3;
+
+    pub struct TestNode {
+        pub node: Node,
+        _vertices: mpsc::Receiver<Vertex>,
+        _milestones: mpsc::Receiver<(TxHash, u32)>,
+        pub transactions: mpsc::Receiver<(SocketAddr, Vec<Trit>)>,
+        pub network: mpsc::Receiver<NetworkCommand>,
+    }
+
+    /// A node over an empty storage in a temporary directory, without any running workers.
+    pub fn test_node(name: &str) -> TestNode {
+        let dir = std::env::temp_dir().join(format!("bee-node-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (vertices, vertices_rx) = mpsc::channel(16);
+        let (milestones, milestones_rx) = mpsc::channel(16);
+        let (transactions, transactions_rx) = mpsc::channel(16);
+        let (network, network_rx) = mpsc::channel(16);
+
+        let mut config = Config::default();
+        config.pow.mwm = TEST_MWM;
+
+        TestNode {
+            node: Node {
+                config: Arc::new(config),
+                storage: Arc::new(Mutex::new(Storage::open(&dir).unwrap())),
+                tangle: Arc::new(RwLock::new(Tangle::new())),
+                indices: Arc::new(RwLock::new(Indices::default())),
+                peers: Arc::new(Mutex::new(HashMap::new())),
+                latest_solid_milestone: Arc::new(RwLock::new(None)),
+                transactions,
+                vertices,
+                milestones,
+                network,
+            },
+            _vertices: vertices_rx,
+            _milestones: milestones_rx,
+            transactions: transactions_rx,
+            network: network_rx,
+        }
+    }
+
+    /// A transaction with all fields zeroed, except for its address and a nonce satisfying `TEST_MWM`.
+    pub fn tx_trytes(address: &str) -> String {
+        let mut tx_trytes = "9".repeat(TRANSACTION_TRYT_LEN);
+        let start = ADDRESS.tryte_offset.start;
+        tx_trytes.replace_range(start..start + address.len(), address);
+
+        let input = InputTrits::try_from(&tx_trytes.trits()[..]).unwrap();
+        let outcome = PearlDiver::new(Cores::from(1), Difficulty::from(TEST_MWM))
+            .search_transaction(input)
+            .unwrap();
+
+        outcome.transaction.to_vec().trytes().unwrap()
+    }
+}
@@ -1,23 +1,716 @@
-use common::Result;
+use crate::api::{self, api_worker};
+use crate::config::Config;
+use crate::logger;
+use crate::metrics::{self, metrics_worker};
+use crate::milestone::{is_valid_milestone, CoordinatorSponge};
+use crate::node::{Indices, NetworkCommand, Node, ParsedTransaction};
+use crate::workers::{shutdown_signal, Shutdown, Workers};
 
-use network::{Peer, PeerId};
+use common::constants::TRANSACTION_TRIT_LEN;
+use common::{Error, Result, Trit};
+
+use crypto::curl::constants::CURL_P_81;
+use crypto::hashes_preview::{BatchQueue, Trits};
+
+use bee_storage::Storage;
+use bee_tangle::{Tangle, TxHash, Vertex};
+
+use network::{decode_transaction, Peer, PeerId, PeerMetrics, PACKET_SIZE};
+
+use log::{debug, error, info, warn};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{delay_for, delay_until, interval, timeout, Duration};
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// The interval in which unflushed storage changes are written to disk.
+const STORAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+/// The longest a received transaction waits for others to be verified together with.
+const VERIFY_BATCH_WAIT: Duration = Duration::from_millis(100);
+/// The interval in which pending milestones are checked for solidity.
+const MILESTONE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// The capacity of the channels connecting the workers.
+const CHANNEL_CAPACITY: usize = 1000;
+/// The number of packets queued for a neighbor, beyond which packets for it are dropped.
+const PEER_QUEUE_CAPACITY: usize = 256;
+/// The time after which connecting to a neighbor is given up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// The pause after a failed accept, so that persistent failures don't keep the worker busy.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
 
 /// The Bee prototype.
 pub struct Prototype {
-    peers: HashMap<PeerId, Peer>,
+    config: Config,
+    peers: Arc<Mutex<HashMap<PeerId, Peer>>>,
 }
 
 impl Prototype {
-    pub fn from_config(filepath: &str) -> Self {
-        Prototype {
-            peers: HashMap::new(),
-        }
+    /// Creates the prototype from the configuration file at `filepath`, overridden by the environment and `args`.
+    pub fn from_config(filepath: &str, args: &[String]) -> Result<Self> {
+        let config = Config::load(filepath, args)?;
+
+        logger::init(&config.logging)?;
+
+        let peers = config
+            .neighbors()?
+            .into_iter()
+            .map(|address| (PeerId(address), Peer::new(address)))
+            .collect();
+
+        Ok(Prototype {
+            config,
+            peers: Arc::new(Mutex::new(peers)),
+        })
     }
 
+    /// Runs the node until SIGINT or SIGTERM is received, then shuts it down gracefully.
     pub fn run(&mut self) -> Result<()> {
+        let mut runtime = Runtime::new()
+            .map_err(|e| Error::RuntimeError(format!("cannot start the runtime: {}", e)))?;
+
+        runtime.block_on(async {
+            let (mut workers, node) = self.start().await?;
+
+            // A failed worker is reported by the shutdown, which fails in turn
+            let signal = tokio::select! {
+                signal = shutdown_signal() => signal,
+                worker = workers.failure() => {
+                    warn!(worker = worker; "shutting down after a worker stopped unexpectedly");
+                    Ok(())
+                }
+            };
+
+            // Even if listening for signals failed, the workers that were already started are stopped properly.
+            let shutdown = workers.shutdown().await;
+
+            drop(node);
+
+            signal.and(shutdown)
+        })
+    }
+
+    /// Opens the storage and spawns all workers in dependency order.
+    async fn start(&self) -> Result<(Workers, Node)> {
+        let storage = Storage::open(&self.config.storage.path).map_err(|e| {
+            Error::StorageError(format!("cannot open '{}': {}", self.config.storage.path, e))
+        })?;
+
+        let listener =
+            TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], self.config.network.port)))
+                .await
+                .map_err(|_| Error::NetworkError)?;
+        let api_listener = api::bind(self.config.api_address()?)?;
+        let metrics_listener = metrics::bind(self.config.metrics_address()?)?;
+
+        info!(
+            port = self.config.network.port,
+            api:% = self.config.api_address()?,
+            metrics:% = self.config.metrics_address()?;
+            "listening for neighbors, API requests and metrics scrapes"
+        );
+
+        let (vertices, vertices_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (milestones, milestones_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (transactions, transactions_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let (network, network_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+        let node = Node {
+            config: Arc::new(self.config.clone()),
+            storage: Arc::new(Mutex::new(storage)),
+            tangle: Arc::new(RwLock::new(Tangle::new())),
+            indices: Arc::new(RwLock::new(Indices::default())),
+            peers: self.peers.clone(),
+            latest_solid_milestone: Arc::new(RwLock::new(None)),
+            transactions,
+            vertices,
+            milestones,
+            network,
+        };
+
+        let milestones = node.load()?;
+        info!(transactions = node.tangle.read().unwrap().len(); "storage loaded");
+
+        let mut workers = Workers::new();
+
+        workers.spawn("storage", |shutdown| {
+            storage_worker(node.storage.clone(), shutdown)
+        });
+        workers.spawn("solidifier", |shutdown| {
+            solidifier_worker(node.tangle.clone(), vertices_rx, shutdown)
+        });
+        workers.spawn("milestone tracker", |shutdown| {
+            milestone_tracker_worker(node.clone(), milestones_rx, shutdown)
+        });
+
+        // The stored milestones are tracked again, so that the latest solid milestone survives restarts
+        for milestone in milestones {
+            node.milestones.clone().send(milestone).await.map_err(|_| {
+                Error::RuntimeError(String::from("the milestone tracker is not running"))
+            })?;
+        }
+        workers.spawn("transaction verifier", |shutdown| {
+            verifier_worker(node.clone(), transactions_rx, shutdown)
+        });
+        workers.spawn("network", |shutdown| {
+            network_worker(listener, node.clone(), network_rx, shutdown)
+        });
+        workers.spawn("api", |shutdown| {
+            api_worker(node.clone(), api_listener, shutdown)
+        });
+        workers.spawn("metrics", |shutdown| {
+            metrics_worker(node.clone(), metrics_listener, shutdown)
+        });
+
+        Ok((workers, node))
+    }
+}
+
+/// Periodically flushes the storage, and flushes it a last time on shutdown.
+///
+/// The changes of a failed periodic flush are kept for the next one, only a failed last flush fails the worker.
+async fn storage_worker(storage: Arc<Mutex<Storage>>, shutdown: Shutdown) -> Result<()> {
+    let mut ticker = interval(STORAGE_FLUSH_INTERVAL);
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = ticker.tick() => if let Err(e) = flush(storage.clone()).await {
+                error!(error:% = e; "storage flush failed, retrying with the next one");
+            },
+        }
+    }
+
+    flush(storage).await
+}
+
+async fn flush(storage: Arc<Mutex<Storage>>) -> Result<()> {
+    tokio::task::spawn_blocking(move || {
+        // Only take the changes while holding the lock, appending them can take a while
+        let (changes, path, records) = {
+            let mut storage = storage.lock().unwrap();
+
+            if !storage.is_dirty() {
+                return Ok(());
+            }
+
+            (
+                storage.take_changes(),
+                storage.path().to_path_buf(),
+                storage.len(),
+            )
+        };
+
+        if let Err(e) = changes.append() {
+            storage.lock().unwrap().restore_changes(changes);
+            return Err(Error::StorageError(format!(
+                "cannot flush '{}': {}",
+                path.display(),
+                e
+            )));
+        }
+        debug!(records = records; "storage flushed");
+
         Ok(())
+    })
+    .await
+    .map_err(|e| Error::RuntimeError(format!("storage flush panicked: {}", e)))?
+}
+
+/// A task exchanging transactions with a neighbor, run by the network worker.
+struct PeerTask {
+    /// Distinguishes the tasks of successive connections to the same address.
+    id: u64,
+    inbound: bool,
+    packets: mpsc::Sender<Vec<u8>>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+/// Accepts incoming neighbor connections, connects to the static neighbors and executes the commands of the other
+/// workers until shutdown.
+///
+/// Every neighbor is served by its own task, so that a slow or unreachable neighbor never holds up the others. The
+/// tasks are stopped before the worker itself, so that no received transaction is stored after shutdown.
+async fn network_worker(
+    mut listener: TcpListener,
+    node: Node,
+    mut commands: mpsc::Receiver<NetworkCommand>,
+    shutdown: Shutdown,
+) -> Result<()> {
+    let mut tasks = HashMap::new();
+    let mut next_id = 0;
+    // Unbounded, so that a stopping task never waits on the worker; there is at most one message per task
+    let (stopped, mut stopped_rx) = mpsc::unbounded_channel();
+    tokio::pin!(shutdown);
+
+    let neighbors = node
+        .peers
+        .lock()
+        .unwrap()
+        .keys()
+        .map(|PeerId(address)| *address)
+        .collect::<Vec<_>>();
+    for address in neighbors {
+        next_id += 1;
+        spawn_peer_task(&mut tasks, next_id, &node, address, None, &stopped).await;
+    }
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, address)) => {
+                    info!(peer:% = address; "neighbor connected");
+                    next_id += 1;
+                    spawn_peer_task(&mut tasks, next_id, &node, address, Some(stream), &stopped).await;
+                }
+                // E.g. running out of file descriptors, which may be over once some connections are closed
+                Err(e) => {
+                    warn!(error:% = e; "cannot accept neighbor connection");
+                    delay_for(ACCEPT_RETRY_DELAY).await;
+                }
+            },
+            task = stopped_rx.recv() => {
+                // Can't be `None`, the worker holds a sender
+                if let Some((address, id)) = task {
+                    if tasks.get(&address).is_some_and(|task: &PeerTask| task.id == id) {
+                        let task = tasks.remove(&address).unwrap();
+                        // Inbound neighbors come from ephemeral ports, they won't be seen again
+                        if task.inbound {
+                            node.peers.lock().unwrap().remove(&PeerId(address));
+                        }
+                    }
+                }
+            }
+            command = commands.recv() => match command {
+                Some(NetworkCommand::AddPeer(address)) => {
+                    node.peers.lock().unwrap().insert(PeerId(address), Peer::new(address));
+                    if !tasks.contains_key(&address) {
+                        next_id += 1;
+                        spawn_peer_task(&mut tasks, next_id, &node, address, None, &stopped).await;
+                    }
+                }
+                Some(NetworkCommand::RemovePeer(address)) => {
+                    node.peers.lock().unwrap().remove(&PeerId(address));
+                    if let Some(task) = tasks.remove(&address) {
+                        stop_peer_task(task).await;
+                    }
+                    info!(peer:% = address; "neighbor removed");
+                }
+                Some(NetworkCommand::Broadcast(packet)) => {
+                    for (address, task) in tasks.iter_mut() {
+                        if task.packets.try_send(packet.clone()).is_err() {
+                            debug!(peer:% = address; "dropping packet for neighbor that can't keep up");
+                        }
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+
+    for (_, task) in tasks {
+        stop_peer_task(task).await;
+    }
+
+    Ok(())
+}
+
+/// Spawns the task of a neighbor, connecting to it first unless it connected to the node.
+async fn spawn_peer_task(
+    tasks: &mut HashMap<SocketAddr, PeerTask>,
+    id: u64,
+    node: &Node,
+    address: SocketAddr,
+    stream: Option<TcpStream>,
+    stopped: &mpsc::UnboundedSender<(SocketAddr, u64)>,
+) {
+    let metrics = node
+        .peers
+        .lock()
+        .unwrap()
+        .entry(PeerId(address))
+        .or_insert_with(|| Peer::new(address))
+        .metrics()
+        .clone();
+    let (packets, packets_rx) = mpsc::channel(PEER_QUEUE_CAPACITY);
+    let (shutdown, shutdown_rx) = Shutdown::channel();
+    let inbound = stream.is_some();
+    let node = node.clone();
+    let stopped = stopped.clone();
+
+    let handle = tokio::spawn(async move {
+        peer_task(node, address, stream, metrics, packets_rx, shutdown_rx).await;
+        // The worker is gone if it is shutting down, and then doesn't need to know
+        let _ = stopped.send((address, id));
+    });
+
+    if let Some(previous) = tasks.insert(
+        address,
+        PeerTask {
+            id,
+            inbound,
+            packets,
+            shutdown,
+            handle,
+        },
+    ) {
+        stop_peer_task(previous).await;
+    }
+}
+
+async fn stop_peer_task(task: PeerTask) {
+    // The task may have already stopped on its own
+    let _ = task.shutdown.send(());
+
+    if let Err(e) = task.handle.await {
+        error!(error:% = e; "neighbor task panicked");
+    }
+}
+
+/// Connects to a neighbor if needed, then receives transactions from it and sends it packets until the connection
+/// is lost or the task is shut down; an unreachable neighbor stays known but unconnected.
+async fn peer_task(
+    node: Node,
+    address: SocketAddr,
+    stream: Option<TcpStream>,
+    metrics: Arc<PeerMetrics>,
+    packets: mpsc::Receiver<Vec<u8>>,
+    shutdown: Shutdown,
+) {
+    tokio::pin!(shutdown);
+
+    let stream = match stream {
+        Some(stream) => stream,
+        None => {
+            let connect = timeout(CONNECT_TIMEOUT, TcpStream::connect(address));
+
+            tokio::select! {
+                _ = &mut shutdown => return,
+                connected = connect => match connected {
+                    Ok(Ok(stream)) => {
+                        info!(peer:% = address; "connected to neighbor");
+                        stream
+                    }
+                    Ok(Err(e)) => {
+                        warn!(peer:% = address, error:% = e; "cannot connect to neighbor");
+                        return;
+                    }
+                    Err(_) => {
+                        warn!(peer:% = address; "timed out connecting to neighbor");
+                        return;
+                    }
+                },
+            }
+        }
+    };
+
+    let (reader, writer) = stream.into_split();
+
+    tokio::select! {
+        _ = &mut shutdown => (),
+        _ = receive_transactions(reader, node, address, metrics.clone()) => (),
+        _ = send_packets(writer, packets, address, metrics) => (),
+    }
+}
+
+/// Sends the packets queued for a neighbor until the connection is lost.
+async fn send_packets(
+    mut writer: OwnedWriteHalf,
+    mut packets: mpsc::Receiver<Vec<u8>>,
+    address: SocketAddr,
+    metrics: Arc<PeerMetrics>,
+) {
+    while let Some(packet) = packets.recv().await {
+        if let Err(e) = writer.write_all(&packet).await {
+            warn!(peer:% = address, error:% = e; "connection to neighbor lost");
+            break;
+        }
+        metrics.transaction_sent();
+    }
+}
+
+/// Hands the transactions received from a neighbor over to the transaction verifier until the connection is closed.
+async fn receive_transactions(
+    mut reader: OwnedReadHalf,
+    node: Node,
+    address: SocketAddr,
+    metrics: Arc<PeerMetrics>,
+) {
+    let mut packet = [0u8; PACKET_SIZE];
+
+    while reader.read_exact(&mut packet).await.is_ok() {
+        metrics.transaction_received();
+
+        match decode_transaction(&packet) {
+            Some(trits) => {
+                if node
+                    .transactions
+                    .clone()
+                    .send((address, trits))
+                    .await
+                    .is_err()
+                {
+                    warn!(peer:% = address; "cannot verify received transaction, the verifier is not running");
+                    break;
+                }
+            }
+            None => debug!(peer:% = address; "dropping invalid transaction"),
+        }
+    }
+
+    debug!(peer:% = address; "stopped receiving from neighbor");
+}
+
+type VerifyQueue = BatchQueue<(SocketAddr, Vec<Trit>)>;
+
+/// Hashes the transactions received from neighbors in batches, and stores those with a valid proof of work.
+///
+/// A batch is hashed once it is full, or once its oldest transaction has waited `VERIFY_BATCH_WAIT`. The hashes are
+/// both the hashes of the transactions and their proof of work.
+async fn verifier_worker(
+    node: Node,
+    mut transactions: mpsc::Receiver<(SocketAddr, Vec<Trit>)>,
+    shutdown: Shutdown,
+) -> Result<()> {
+    let mut queue = VerifyQueue::new(TRANSACTION_TRIT_LEN, CURL_P_81, VERIFY_BATCH_WAIT);
+    tokio::pin!(shutdown);
+
+    loop {
+        let deadline = queue.deadline();
+        let expired = async {
+            match deadline {
+                Some(deadline) => delay_until(deadline.into()).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = expired => queue = verify(&node, queue).await?,
+            received = transactions.recv() => match received {
+                Some((address, trits)) => {
+                    // Can't fail, decoded transactions have the length of the queue, which is never left full
+                    let input = trits.clone();
+                    if queue.push((address, trits), &Trits::from_i8_unchecked(&input)).unwrap() {
+                        queue = verify(&node, queue).await?;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+
+    // The transactions received before shutdown are verified still
+    verify(&node, queue).await.map(drop)
+}
+
+/// Hashes the queued transactions on a blocking thread, so that the other tasks keep running, and stores those with a
+/// valid proof of work. Returns the emptied queue.
+async fn verify(node: &Node, mut queue: VerifyQueue) -> Result<VerifyQueue> {
+    if queue.is_empty() {
+        return Ok(queue);
+    }
+
+    let (queue, hashes) = tokio::task::spawn_blocking(move || {
+        let hashes = queue.flush();
+        (queue, hashes)
+    })
+    .await
+    .map_err(|e| Error::RuntimeError(format!("transaction verification panicked: {}", e)))?;
+
+    for ((address, trits), hash) in hashes {
+        if !node.meets_mwm(hash.as_i8_slice()) {
+            debug!(peer:% = address; "dropping transaction with invalid proof of work");
+            continue;
+        }
+
+        match ParsedTransaction::from_hashed_trits(trits, hash.as_i8_slice()) {
+            Ok(parsed) => node.store_transaction(&parsed).await?,
+            Err(_) => debug!(peer:% = address; "dropping invalid transaction"),
+        }
+    }
+
+    Ok(queue)
+}
+
+/// Inserts incoming vertices into the Tangle, which triggers their solidification.
+async fn solidifier_worker(
+    tangle: Arc<RwLock<Tangle>>,
+    mut vertices: mpsc::Receiver<Vertex>,
+    shutdown: Shutdown,
+) -> Result<()> {
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            vertex = vertices.recv() => match vertex {
+                Some(vertex) => {
+                    tangle.write().unwrap().insert(vertex);
+                }
+                None => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps track of the milestones that are not solid yet, validates them once they are, and updates the latest solid
+/// milestone.
+async fn milestone_tracker_worker(
+    node: Node,
+    mut milestones: mpsc::Receiver<(TxHash, u32)>,
+    shutdown: Shutdown,
+) -> Result<()> {
+    let mut pending = Vec::new();
+    let mut ticker = interval(MILESTONE_CHECK_INTERVAL);
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            milestone = milestones.recv() => match milestone {
+                Some(milestone) => pending.push(milestone),
+                None => break,
+            },
+            _ = ticker.tick() => {
+                let solid = {
+                    let tangle = node.tangle.read().unwrap();
+                    let (solid, unsolid): (Vec<_>, Vec<_>) = pending.drain(..).partition(|(hash, _)| {
+                        tangle.get(*hash).map(|vertex| vertex.is_solid()).unwrap_or(false)
+                    });
+                    pending = unsolid;
+                    solid
+                };
+
+                for (hash, index) in solid {
+                    if !is_valid_milestone::<CoordinatorSponge>(&node, hash, index) {
+                        warn!(index = index, hash = hash.as_trytes(); "dropping invalid milestone");
+                        continue;
+                    }
+
+                    let mut latest = node.latest_solid_milestone.write().unwrap();
+
+                    if latest.map(|(_, latest_index)| index > latest_index).unwrap_or(true) {
+                        *latest = Some((hash, index));
+                        info!(index = index, hash = hash.as_trytes(); "new latest solid milestone");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node::tests::{test_node, tx_trytes};
+    use crate::workers::Workers;
+
+    use common::constants::TRANSACTION_TRYT_LEN;
+    use ternary::iota_conversion::Trinary;
+
+    use std::time::Instant;
+
+    /// Waits up to a few seconds for `condition` to hold.
+    async fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while !condition() {
+            if Instant::now() > deadline {
+                return false;
+            }
+            delay_for(Duration::from_millis(10)).await;
+        }
+
+        true
+    }
+
+    #[test]
+    fn serve_neighbors_independently() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("network");
+
+        runtime.block_on(async {
+            let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+                .await
+                .unwrap();
+            let address = listener.local_addr().unwrap();
+            let (mut commands, commands_rx) = mpsc::channel(16);
+
+            let mut workers = Workers::new();
+            let node = test.node.clone();
+            workers.spawn("network", |shutdown| {
+                network_worker(listener, node, commands_rx, shutdown)
+            });
+
+            // Connecting to a neighbor that doesn't answer doesn't hold up the others
+            let unresponsive = SocketAddr::from(([10, 255, 255, 1], 15600));
+            commands
+                .send(NetworkCommand::AddPeer(unresponsive))
+                .await
+                .unwrap();
+
+            let neighbor = TcpStream::connect(address).await.unwrap();
+            let neighbor_address = PeerId(neighbor.local_addr().unwrap());
+            let peers = test.node.peers.clone();
+            assert!(wait_for(|| peers.lock().unwrap().contains_key(&neighbor_address)).await);
+
+            // Inbound neighbors are forgotten once they disconnect, unlike the added ones
+            drop(neighbor);
+            assert!(wait_for(|| !peers.lock().unwrap().contains_key(&neighbor_address)).await);
+            assert!(peers.lock().unwrap().contains_key(&PeerId(unresponsive)));
+
+            workers.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn verify_received_transactions() {
+        let mut runtime = Runtime::new().unwrap();
+        let test = test_node("verifier");
+        let valid = tx_trytes("VALID");
+        // No nonce was searched for this one
+        let invalid = format!("INVALID{}", "9".repeat(TRANSACTION_TRYT_LEN - 7));
+
+        let transactions = test.transactions;
+        let node = test.node;
+
+        runtime.block_on(async {
+            let mut workers = Workers::new();
+            let verifier_node = node.clone();
+            workers.spawn("transaction verifier", |shutdown| {
+                verifier_worker(verifier_node, transactions, shutdown)
+            });
+
+            let peer = SocketAddr::from(([127, 0, 0, 1], 15600));
+            for tx_trytes in &[&valid, &invalid] {
+                let trits = tx_trytes.to_string().trits();
+                node.transactions.clone().send((peer, trits)).await.unwrap();
+            }
+
+            // Far from a full batch, the transactions are verified once they have waited long enough
+            assert!(wait_for(|| !node.storage.lock().unwrap().is_empty()).await);
+
+            workers.shutdown().await.unwrap();
+        });
+
+        let valid_hash = ParsedTransaction::from_trytes(&valid).unwrap().hash;
+        assert_eq!(node.storage.lock().unwrap().len(), 1);
+        assert_eq!(node.transaction_trytes(valid_hash), Some(valid));
     }
 }
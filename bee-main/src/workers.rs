@@ -0,0 +1,267 @@
+use common::{Error, Result};
+
+use log::{debug, error};
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future that resolves once the worker holding it is asked to shut down.
+pub struct Shutdown(oneshot::Receiver<()>);
+
+impl Shutdown {
+    /// Creates a `Shutdown` together with the sender resolving it, for tasks that are stopped by a worker.
+    pub fn channel() -> (oneshot::Sender<()>, Self) {
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        (shutdown, Shutdown(shutdown_rx))
+    }
+}
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // A dropped sender means that nobody is able to stop the worker anymore, which is treated as a shutdown too.
+        Pin::new(&mut self.0).poll(cx).map(|_| ())
+    }
+}
+
+struct Worker {
+    name: &'static str,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<Result<()>>,
+}
+
+/// Reports the worker it belongs to when dropped, i.e. when the worker fails or panics, unless it was disarmed.
+struct FailureGuard {
+    name: &'static str,
+    failures: Option<mpsc::UnboundedSender<&'static str>>,
+}
+
+impl FailureGuard {
+    fn disarm(mut self) {
+        self.failures = None;
+    }
+}
+
+impl Drop for FailureGuard {
+    fn drop(&mut self) {
+        if let Some(failures) = self.failures.take() {
+            // Only fails if the `Workers` were dropped, and with them whoever would notice the failure
+            let _ = failures.send(self.name);
+        }
+    }
+}
+
+/// The set of running workers of the node.
+///
+/// Workers have to be spawned in dependency order, i.e. a worker may only depend on workers that were spawned before
+/// it. They are shut down in the reverse order, so that no worker outlives one of its dependencies.
+pub struct Workers {
+    workers: Vec<Worker>,
+    failures: mpsc::UnboundedSender<&'static str>,
+    failures_rx: mpsc::UnboundedReceiver<&'static str>,
+}
+
+impl Default for Workers {
+    fn default() -> Self {
+        let (failures, failures_rx) = mpsc::unbounded_channel();
+
+        Self {
+            workers: Vec::new(),
+            failures,
+            failures_rx,
+        }
+    }
+}
+
+impl Workers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn a new worker on the current runtime. `worker` is given a `Shutdown` future which it has to watch in
+    /// order to stop gracefully.
+    pub fn spawn<W, F>(&mut self, name: &'static str, worker: W)
+    where
+        W: FnOnce(Shutdown) -> F,
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        let (shutdown, shutdown_rx) = Shutdown::channel();
+        let worker = worker(shutdown_rx);
+        let guard = FailureGuard {
+            name,
+            failures: Some(self.failures.clone()),
+        };
+
+        let handle = tokio::spawn(async move {
+            let result = worker.await;
+            if result.is_ok() {
+                guard.disarm();
+            }
+            result
+        });
+
+        self.workers.push(Worker {
+            name,
+            shutdown,
+            handle,
+        });
+    }
+
+    /// Resolves with the name of the first worker that fails or panics, which none of the others notice on their own.
+    ///
+    /// Never resolves if all workers keep running.
+    pub async fn failure(&mut self) -> &'static str {
+        // Can't be `None`, `Workers` holds a sender
+        self.failures_rx.recv().await.unwrap()
+    }
+
+    /// Shut down all workers in reverse dependency order, waiting for each one to finish before stopping the next.
+    ///
+    /// All workers are stopped even if some of them fail; the first error encountered is returned.
+    pub async fn shutdown(self) -> Result<()> {
+        let mut result = Ok(());
+
+        for worker in self.workers.into_iter().rev() {
+            // The worker may have already stopped on its own, in which case there is nobody left to notify.
+            let _ = worker.shutdown.send(());
+
+            let worker_result = match worker.handle.await {
+                Ok(worker_result) => worker_result,
+                Err(e) => Err(Error::RuntimeError(format!(
+                    "worker '{}' panicked: {}",
+                    worker.name, e
+                ))),
+            };
+
+            match &worker_result {
+                Ok(()) => debug!(worker = worker.name; "worker stopped"),
+                Err(e) => error!(worker = worker.name, error:% = e; "worker failed"),
+            }
+
+            if let Err(e) = worker_result {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Resolves once the process receives SIGINT or, on Unix, SIGTERM.
+pub async fn shutdown_signal() -> Result<()> {
+    let ctrl_c = tokio::signal::ctrl_c();
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate())
+            .map_err(|e| Error::RuntimeError(format!("cannot listen for SIGTERM: {}", e)))?;
+
+        tokio::select! {
+            r = ctrl_c => r.map_err(|e| Error::RuntimeError(format!("cannot listen for SIGINT: {}", e))),
+            _ = sigterm.recv() => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    ctrl_c
+        .await
+        .map_err(|e| Error::RuntimeError(format!("cannot listen for SIGINT: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::time::{delay_for, Duration};
+
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn shutdown_in_reverse_order() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+
+        runtime.block_on(async {
+            let mut workers = Workers::new();
+
+            for name in &["storage", "network", "api"] {
+                let stopped = stopped.clone();
+                workers.spawn(name, move |shutdown| async move {
+                    shutdown.await;
+                    stopped.lock().unwrap().push(*name);
+                    Ok(())
+                });
+            }
+
+            assert!(workers.shutdown().await.is_ok());
+        });
+
+        assert_eq!(*stopped.lock().unwrap(), vec!["api", "network", "storage"]);
+    }
+
+    #[test]
+    fn notice_failed_workers() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let mut workers = Workers::new();
+
+            workers.spawn("running", |shutdown| async {
+                shutdown.await;
+                Ok(())
+            });
+            workers.spawn("done", |_| async { Ok(()) });
+            workers.spawn("failed", |shutdown| async {
+                delay_for(Duration::from_millis(10)).await;
+                drop(shutdown);
+                Err(Error::StorageError(String::from("failed")))
+            });
+            workers.spawn("panicked", |shutdown| async {
+                delay_for(Duration::from_millis(50)).await;
+                drop(shutdown);
+                panic!("panicked")
+            });
+
+            assert_eq!(workers.failure().await, "failed");
+            assert_eq!(workers.failure().await, "panicked");
+            assert!(workers.shutdown().await.is_err());
+        });
+    }
+
+    #[test]
+    fn report_first_error() {
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let result = runtime.block_on(async {
+            let mut workers = Workers::new();
+
+            workers.spawn("first", |_| async {
+                Err(Error::StorageError(String::from("first")))
+            });
+            workers.spawn("second", |shutdown| async {
+                shutdown.await;
+                Err(Error::StorageError(String::from("second")))
+            });
+            workers.spawn("third", |shutdown| async {
+                shutdown.await;
+                Ok(())
+            });
+
+            workers.shutdown().await
+        });
+
+        match result {
+            Err(Error::StorageError(details)) => assert_eq!(details, "second"),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -1,23 +1,36 @@
 mod constants;
+mod metrics;
+mod packet;
+
+pub use constants::PACKET_SIZE;
+pub use metrics::PeerMetrics;
+pub use packet::{decode_transaction, encode_transaction};
 
 use std::net::{SocketAddr, ToSocketAddrs};
-use tokio::net::TcpListener;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct PeerId(pub SocketAddr);
 
 pub struct Peer {
     address: SocketAddr,
+    metrics: Arc<PeerMetrics>,
 }
 
 impl Peer {
     pub fn new(address: impl ToSocketAddrs) -> Self {
         Peer {
             address: address.to_socket_addrs().unwrap().next().unwrap(),
+            metrics: Arc::default(),
         }
     }
 
     pub fn address(&self) -> SocketAddr {
         self.address
     }
+
+    /// The metrics of the peer, shared with the tasks exchanging transactions with it.
+    pub fn metrics(&self) -> &Arc<PeerMetrics> {
+        &self.metrics
+    }
 }
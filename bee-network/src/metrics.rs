@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The traffic exchanged with a single peer.
+#[derive(Debug, Default)]
+pub struct PeerMetrics {
+    transactions_received: AtomicU64,
+    transactions_sent: AtomicU64,
+}
+
+impl PeerMetrics {
+    pub fn transactions_received(&self) -> u64 {
+        self.transactions_received.load(Ordering::Relaxed)
+    }
+
+    pub fn transactions_sent(&self) -> u64 {
+        self.transactions_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn transaction_received(&self) {
+        self.transactions_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn transaction_sent(&self) {
+        self.transactions_sent.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::constants::PACKET_SIZE;
+
+use common::constants::TRANSACTION_TRIT_LEN;
+use common::Trit;
+
+use log::debug;
+
+const TRITS_PER_BYTE: usize = 5;
+
+/// Encodes the trits of a transaction into a packet of `PACKET_SIZE` bytes, packing five trits into each byte.
+///
+/// Returns `None` if `trits` is not exactly one transaction long.
+pub fn encode_transaction(trits: &[Trit]) -> Option<Vec<u8>> {
+    if trits.len() != TRANSACTION_TRIT_LEN {
+        return None;
+    }
+
+    let packet = trits
+        .chunks(TRITS_PER_BYTE)
+        .map(|chunk| chunk.iter().rev().fold(0i8, |acc, trit| acc * 3 + trit) as u8)
+        .collect::<Vec<u8>>();
+
+    debug_assert_eq!(packet.len(), PACKET_SIZE);
+
+    Some(packet)
+}
+
+/// Decodes a packet of `PACKET_SIZE` bytes back into the trits of a transaction.
+///
+/// Returns `None` if the packet has the wrong size or contains a byte that doesn't encode five trits.
+pub fn decode_transaction(packet: &[u8]) -> Option<Vec<Trit>> {
+    if packet.len() != PACKET_SIZE {
+        debug!(size = packet.len(); "dropping packet of invalid size");
+        return None;
+    }
+
+    let mut trits = Vec::with_capacity(PACKET_SIZE * TRITS_PER_BYTE);
+
+    for byte in packet {
+        let mut value = *byte as i8;
+
+        if !(-121..=121).contains(&value) {
+            return None;
+        }
+
+        for _ in 0..TRITS_PER_BYTE {
+            let trit = match value.rem_euclid(3) {
+                2 => -1,
+                trit => trit,
+            };
+            trits.push(trit);
+            value = (value - trit) / 3;
+        }
+    }
+
+    trits.truncate(TRANSACTION_TRIT_LEN);
+
+    Some(trits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_transaction() {
+        let trits = (0..TRANSACTION_TRIT_LEN)
+            .map(|i| (i % 3) as i8 - 1)
+            .collect::<Vec<Trit>>();
+
+        let packet = encode_transaction(&trits).unwrap();
+        assert_eq!(packet.len(), PACKET_SIZE);
+        assert_eq!(decode_transaction(&packet).unwrap(), trits);
+    }
+
+    #[test]
+    fn reject_invalid_sizes() {
+        assert!(encode_transaction(&[0; 42]).is_none());
+        assert!(decode_transaction(&[0; 42]).is_none());
+        assert!(decode_transaction(&[122; PACKET_SIZE]).is_none());
+    }
+}
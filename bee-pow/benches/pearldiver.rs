@@ -0,0 +1,130 @@
+#[macro_use]
+extern crate criterion;
+
+use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+use criterion::{black_box, Benchmark, Criterion, ParameterizedBenchmark, Throughput};
+use pow::{Cores, Difficulty, InputTrits, NonceRange, PearlDiver, PearlDiverState, Simd};
+
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+
+/// The inner steps every lane searches per iteration.
+const STEPS: u64 = 64;
+/// The nonces one lane tries per step.
+const BATCH_SIZE: u64 = 64;
+
+/// The flag of a search that is going on, as the dives see it.
+const SEARCHING: u8 = 1;
+
+/// Threads checking the state of a search over and over, like the other dives of a search do, until dropped.
+struct Contenders {
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Contenders {
+    fn spawn<F: Fn() -> bool + Clone + Send + 'static>(threads: usize, is_searching: F) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let threads = (0..threads)
+            .map(|_| {
+                let stop = stop.clone();
+                let is_searching = is_searching.clone();
+                thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        black_box(is_searching());
+                    }
+                })
+            })
+            .collect();
+
+        Self { stop, threads }
+    }
+}
+
+impl Drop for Contenders {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            // Can't fail, the threads don't panic
+            thread.join().unwrap();
+        }
+    }
+}
+
+/// Searches a fixed part of the nonce space for a nonce that can't be found, so that every iteration hashes the
+/// same number of nonces.
+fn search(cores: usize, input: &InputTrits) {
+    let range = NonceRange {
+        steps: Some(STEPS),
+        ..NonceRange::default()
+    };
+    PearlDiver::new(Cores::from(cores), Difficulty::from(HASH_TRIT_LEN))
+        .with_range(range)
+        .search_sync(input);
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = InputTrits::try_from(&[1; TRANSACTION_TRIT_LEN][..]).unwrap();
+    let concurrent_input = input.clone();
+    let lanes = Simd::detect().lanes() as u64;
+
+    let mut cores = vec![1, 2, 4, num_cpus::get()];
+    cores.sort();
+    cores.dedup();
+
+    c.bench(
+        "PearlDiver",
+        ParameterizedBenchmark::new(
+            "search with cores",
+            move |b, &cores| b.iter(|| search(cores, &input)),
+            cores.clone(),
+        )
+        .throughput(move |&cores| {
+            Throughput::Elements((cores as u64 * lanes * STEPS * BATCH_SIZE) as u32)
+        })
+        .sample_size(20),
+    );
+
+    // Every dive checks the state of its search before each step. It used to be behind an `RwLock`, which the
+    // dives of a search contended on; now they load an atomic flag, like `is_searching` does. The measured dive
+    // checks the state while the other dives of its search keep checking it too.
+    let rwlock = Arc::new(RwLock::new(PearlDiverState::Searching));
+    let flag = Arc::new(AtomicU8::new(SEARCHING));
+    c.bench(
+        "PearlDiver state check",
+        ParameterizedBenchmark::new(
+            "RwLock (before)",
+            move |b, &cores| {
+                let rwlock = rwlock.clone();
+                let is_searching = move || *rwlock.read().unwrap() == PearlDiverState::Searching;
+                let _contenders = Contenders::spawn(cores - 1, is_searching.clone());
+                b.iter(|| (0..STEPS).filter(|_| black_box(&is_searching)()).count())
+            },
+            cores.clone(),
+        )
+        .with_function("atomic (after)", move |b, &cores| {
+            let flag = flag.clone();
+            let is_searching = move || flag.load(Ordering::Relaxed) == SEARCHING;
+            let _contenders = Contenders::spawn(cores - 1, is_searching.clone());
+            b.iter(|| (0..STEPS).filter(|_| black_box(&is_searching)()).count())
+        }),
+    );
+
+    // A search used to wait for the end of every search that started before it, now their dives take turns
+    let endless = PearlDiver::new(Cores::default(), Difficulty::from(HASH_TRIT_LEN))
+        .search_async(concurrent_input.clone(), None);
+    c.bench(
+        "PearlDiver",
+        Benchmark::new("search next to an endless search", move |b| {
+            b.iter(|| search(1, &concurrent_input))
+        })
+        .throughput(Throughput::Elements((lanes * STEPS * BATCH_SIZE) as u32))
+        .sample_size(10),
+    );
+    drop(endless);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
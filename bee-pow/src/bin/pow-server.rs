@@ -0,0 +1,55 @@
+//! Serves the proof of work to remote clients.
+//!
+//! Usage: `pow-server [--bind <address>] [--cores <n>] [--jobs <n>] [--queue <n>] [--max-mwm <n>] [--clients <n>]`
+
+use pow::remote::{PowServer, ServerConfig};
+use pow::Cores;
+
+use std::env;
+use std::process;
+use std::str::FromStr;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:14266";
+
+fn main() {
+    if let Err(e) = run(env::args().skip(1).collect()) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut address = String::from(DEFAULT_ADDRESS);
+    let mut config = ServerConfig::default();
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+
+        match flag.as_str() {
+            "--bind" => address = value,
+            "--cores" => config.cores = Cores::from(parse::<usize>(&flag, &value)?),
+            "--jobs" => config.max_jobs = parse(&flag, &value)?,
+            "--queue" => config.queue_len = parse(&flag, &value)?,
+            "--max-mwm" => config.max_mwm = parse(&flag, &value)?,
+            "--clients" => config.max_clients = parse(&flag, &value)?,
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    let server = PowServer::bind(&address, config).map_err(|e| e.to_string())?;
+    println!(
+        "serving proof of work on {}",
+        server.local_addr().map_err(|e| e.to_string())?
+    );
+
+    server.run().map_err(|e| e.to_string())
+}
+
+fn parse<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {} for {}", value, flag))
+}
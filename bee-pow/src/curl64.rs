@@ -1,56 +1,45 @@
-use crypto::curl::constants::CURL_STAT_TRIT_LEN as STATE_LEN;
+use crypto::hashes_preview::BctState;
 
 type WithCarry = bool;
 
-pub(crate) struct Curl64State {
-    hi: [u64; STATE_LEN],
-    lo: [u64; STATE_LEN],
-}
+/// The bit-sliced Curl state of the search, with the operations on the nonce trits on top of the shared `BctState`.
+#[derive(Clone)]
+pub(crate) struct Curl64State(BctState);
 
 impl Curl64State {
     pub fn new(init_value: u64) -> Self {
-        Self {
-            hi: [init_value; STATE_LEN],
-            lo: [init_value; STATE_LEN],
-        }
+        Self(BctState::new(init_value))
     }
 
     pub fn set(&mut self, index: usize, hi: u64, lo: u64) {
-        self.hi[index] = hi;
-        self.lo[index] = lo;
+        self.0.set(index, hi, lo);
     }
 
     pub fn get(&self, index: usize) -> (u64, u64) {
-        (self.hi[index], self.lo[index])
+        self.0.get(index)
     }
 
     pub fn bit_add(&mut self, index: usize) -> WithCarry {
-        let hi = self.hi[index];
-        let lo = self.lo[index];
+        let (hi, lo) = self.0.get(index);
 
-        self.hi[index] = lo;
-        self.lo[index] = hi ^ lo;
+        self.0.set(index, lo, hi ^ lo);
 
         (hi & !lo) != 0
     }
 
     pub fn bit_equal(&self, index: usize) -> u64 {
-        !(self.hi[index] ^ self.lo[index])
-    }
+        let (hi, lo) = self.0.get(index);
 
-    pub unsafe fn as_mut_ptr(&mut self) -> (*mut u64, *mut u64) {
-        ((&mut self.hi).as_mut_ptr(), (&mut self.lo).as_mut_ptr())
+        !(hi ^ lo)
     }
-}
 
-impl Clone for Curl64State {
-    fn clone(&self) -> Self {
-        let mut hi = [0; STATE_LEN];
-        let mut lo = [0; STATE_LEN];
-
-        hi.copy_from_slice(&self.hi);
-        lo.copy_from_slice(&self.lo);
+    /// Transforms the whole state, so that more trits can be absorbed afterwards.
+    pub fn transform(&mut self, tmp: &mut Self, rounds: usize) {
+        self.0.transform(&mut tmp.0, rounds);
+    }
 
-        Self { hi, lo }
+    /// Only computes the trits of the hash in the last round, so nothing can be absorbed afterwards.
+    pub fn transform_hash(&mut self, tmp: &mut Self, rounds: usize) {
+        self.0.transform_hash(&mut tmp.0, rounds);
     }
 }
@@ -2,6 +2,9 @@ use common::constants::TRANSACTION_TRIT_LEN as INPUT_LEN;
 use common::constants::TRANSACTION_TRIT_LEN as TRANS_LEN;
 use common::Trit;
 
+use std::convert::TryFrom;
+
+#[derive(Clone)]
 pub struct InputTrits(pub(crate) [Trit; INPUT_LEN]);
 
 impl std::ops::Deref for InputTrits {
@@ -11,3 +14,19 @@ impl std::ops::Deref for InputTrits {
         &self.0
     }
 }
+
+impl TryFrom<&[Trit]> for InputTrits {
+    type Error = common::Error;
+
+    /// Fails if `trits` is not exactly one transaction long.
+    fn try_from(trits: &[Trit]) -> Result<Self, Self::Error> {
+        if trits.len() != INPUT_LEN {
+            return Err(common::Error::TransactionError);
+        }
+
+        let mut input = [0; INPUT_LEN];
+        input.copy_from_slice(trits);
+
+        Ok(Self(input))
+    }
+}
@@ -3,7 +3,30 @@ mod cores;
 mod curl64;
 mod difficulty;
 mod input;
+mod metrics;
 mod nonce;
+mod outcome;
 mod pearldiver;
+mod pool;
+mod progress;
+mod range;
+pub mod remote;
+mod rounds;
+mod search;
+mod simd;
+mod state;
+mod verify;
 
+pub use cores::Cores;
+pub use difficulty::Difficulty;
+pub use input::InputTrits;
+pub use metrics::{metrics, PowMetrics};
+pub use nonce::NonceTrits;
+pub use outcome::PowOutcome;
 pub use pearldiver::*;
+pub use progress::PowStats;
+pub use range::NonceRange;
+pub use rounds::Rounds;
+pub use search::{CancelToken, SearchHandle};
+pub use simd::Simd;
+pub use verify::{verify, verify_batch};
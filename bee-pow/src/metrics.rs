@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static METRICS: PowMetrics = PowMetrics {
+    hashes: AtomicU64::new(0),
+    searches: AtomicU64::new(0),
+    last_search_hashes_per_second: AtomicU64::new(0),
+};
+
+/// The proof of work done by all `PearlDiver`s of the process.
+pub struct PowMetrics {
+    hashes: AtomicU64,
+    searches: AtomicU64,
+    last_search_hashes_per_second: AtomicU64,
+}
+
+impl PowMetrics {
+    /// The number of hashes computed by the finished searches.
+    pub fn hashes(&self) -> u64 {
+        self.hashes.load(Ordering::Relaxed)
+    }
+
+    /// The number of finished searches.
+    pub fn searches(&self) -> u64 {
+        self.searches.load(Ordering::Relaxed)
+    }
+
+    /// The hash rate of the last finished search, which isn't updated while searching.
+    pub fn last_search_hashes_per_second(&self) -> u64 {
+        self.last_search_hashes_per_second.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn search_finished(&self, hashes: u64, duration: Duration) {
+        self.hashes.fetch_add(hashes, Ordering::Relaxed);
+        self.searches.fetch_add(1, Ordering::Relaxed);
+
+        let micros = duration.as_micros().max(1) as u64;
+        self.last_search_hashes_per_second
+            .store(hashes.saturating_mul(1_000_000) / micros, Ordering::Relaxed);
+    }
+}
+
+/// The metrics shared by all `PearlDiver`s.
+pub fn metrics() -> &'static PowMetrics {
+    &METRICS
+}
@@ -0,0 +1,106 @@
+use crate::constants::TRANS_NONCE_START;
+use crate::input::InputTrits;
+use crate::nonce::NonceTrits;
+use crate::rounds::Rounds;
+
+use common::constants::HASH_TRIT_LEN as HASH_LEN;
+use common::Trit;
+
+use crypto::hashes_preview::{CurlP, Sponge, Trits, TritsMut};
+
+/// A transaction stamped with the nonce found for it, together with its hash, CurlP81 unless searched otherwise.
+pub struct PowOutcome<T = InputTrits> {
+    pub transaction: T,
+    pub hash: [Trit; HASH_LEN],
+    /// The number of trailing zero trits of the hash, which is at least the difficulty searched for.
+    pub trailing_zeros: usize,
+}
+
+impl PowOutcome {
+    /// Writes `nonce` into the NONCE field of `input` and hashes the result.
+    pub fn new(input: InputTrits, nonce: &NonceTrits) -> Self {
+        Self::with_rounds(input, nonce, Rounds::default())
+    }
+
+    /// Like `new`, for a nonce that was searched with `rounds` rounds of Curl.
+    pub fn with_rounds(mut input: InputTrits, nonce: &NonceTrits, rounds: Rounds) -> Self {
+        input.0[TRANS_NONCE_START..].copy_from_slice(nonce.as_slice());
+
+        let hash = hash(&input, rounds);
+
+        Self {
+            transaction: input,
+            hash,
+            trailing_zeros: trailing_zeros(&hash),
+        }
+    }
+}
+
+impl<T> PowOutcome<T> {
+    /// Converts the stamped transaction, e.g. into a bundle transaction.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> PowOutcome<U> {
+        PowOutcome {
+            transaction: f(self.transaction),
+            hash: self.hash,
+            trailing_zeros: self.trailing_zeros,
+        }
+    }
+}
+
+/// Computes the Curl hash of a transaction.
+pub(crate) fn hash(input: &InputTrits, rounds: Rounds) -> [Trit; HASH_LEN] {
+    let mut curl = CurlP::new(*rounds);
+    let mut hash = [0; HASH_LEN];
+
+    // Can't fail, CurlP absorbs and squeezes any number of trits
+    curl.absorb(&Trits::from_i8_unchecked(&input.0)).unwrap();
+    curl.squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+        .unwrap();
+
+    hash
+}
+
+pub(crate) fn trailing_zeros(hash: &[Trit]) -> usize {
+    hash.iter().rev().take_while(|&&trit| trit == 0).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Cores, Difficulty, PearlDiver};
+
+    use common::constants::TRANSACTION_TRIT_LEN as TRANS_LEN;
+
+    #[test]
+    fn stamp_nonce_and_hash() {
+        let mut trits = [0; TRANS_LEN];
+        trits[..TRANS_NONCE_START]
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, trit)| *trit = (i % 3) as i8 - 1);
+        let input = InputTrits(trits);
+
+        let outcome = PearlDiver::new(Cores::from(2), Difficulty::from(9))
+            .search_transaction(input.clone())
+            .unwrap();
+
+        assert_eq!(
+            outcome.transaction[..TRANS_NONCE_START],
+            input[..TRANS_NONCE_START]
+        );
+        assert_eq!(
+            &outcome.hash[..],
+            &hash(&outcome.transaction, Rounds::default())[..]
+        );
+        assert!(outcome.trailing_zeros >= 9);
+        assert_eq!(outcome.trailing_zeros, trailing_zeros(&outcome.hash));
+    }
+
+    #[test]
+    fn count_trailing_zeros() {
+        assert_eq!(trailing_zeros(&[1, 0, -1, 0, 0]), 2);
+        assert_eq!(trailing_zeros(&[0, 0, 0]), 3);
+        assert_eq!(trailing_zeros(&[0, 0, 1]), 0);
+    }
+}
@@ -1,6 +1,4 @@
 use crypto::curl::constants::CURL_HASH_TRIT_LEN as HASH_LEN;
-use crypto::curl::constants::CURL_P_81 as NUM_ROUNDS;
-use crypto::curl::constants::CURL_STAT_TRIT_LEN as STATE_LEN;
 
 use common::constants::NONCE_TRIT_LEN as NONCE_LEN;
 
@@ -9,9 +7,28 @@ use crate::cores::Cores;
 use crate::curl64::Curl64State;
 use crate::difficulty::Difficulty;
 use crate::input::InputTrits;
+use crate::metrics::metrics;
 use crate::nonce::NonceTrits;
-
-use std::sync::{Arc, RwLock};
+use crate::outcome::PowOutcome;
+use crate::pool::workers;
+use crate::progress::{PowStats, Progress};
+use crate::range::NonceRange;
+use crate::rounds::Rounds;
+use crate::search::SearchHandle;
+#[cfg(target_arch = "x86_64")]
+use crate::simd::{Avx2, Sse2};
+use crate::simd::{Simd, WideState, Word};
+use crate::state::SharedState;
+
+use crossbeam::sync::WaitGroup;
+
+use log::debug;
+
+use std::ops::Range;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 type Exhausted = bool;
 
@@ -20,6 +37,7 @@ pub enum PearlDiverState {
     Created,
     Searching,
     Cancelled,
+    TimedOut,
     Completed(Option<NonceTrits>),
 }
 
@@ -27,7 +45,12 @@ pub enum PearlDiverState {
 pub struct PearlDiver {
     cores: Cores,
     difficulty: Difficulty,
-    state: Arc<RwLock<PearlDiverState>>,
+    simd: Simd,
+    range: NonceRange,
+    rounds: Rounds,
+    deadline: Option<Instant>,
+    pub(crate) state: Arc<SharedState>,
+    pub(crate) progress: Arc<Progress>,
 }
 
 impl PearlDiver {
@@ -39,81 +62,333 @@ impl PearlDiver {
         }
     }
 
+    /// Uses `simd` to transform the Curl states, or the scalar transform if the CPU doesn't support it.
+    pub fn with_simd(mut self, simd: Simd) -> Self {
+        self.simd = if simd.is_supported() {
+            simd
+        } else {
+            Simd::Scalar
+        };
+        self
+    }
+
+    /// Searches only the nonces of `range`; the search completes without a nonce once the range is exhausted.
+    pub fn with_range(mut self, range: NonceRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Searches nonces for the Curl hash with `rounds` rounds instead of Curl-P-81, e.g. for test networks.
+    pub fn with_rounds(mut self, rounds: Rounds) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Gives the search up with `PearlDiverState::TimedOut` once `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// The range that continues the search where it stopped, if it is searched with the same cores and `Simd`.
+    ///
+    /// Lanes that got further than others search some nonces again, so that nothing is skipped.
+    pub fn resume_range(&self) -> NonceRange {
+        self.range.skip(self.progress.steps().unwrap_or(0))
+    }
+
     pub fn search_sync(&mut self, input: &InputTrits) {
         assert!(self.state() == PearlDiverState::Created);
 
-        let mut prestate = make_prestate(input);
+        self.set_state(PearlDiverState::Searching);
+        self.search(input, self.deadline);
+    }
+
+    /// Searches for a nonce and returns the transaction stamped with it, if one was found.
+    pub fn search_transaction(&mut self, input: InputTrits) -> Option<PowOutcome> {
+        self.search_sync(&input);
+
+        match self.state() {
+            PearlDiverState::Completed(Some(nonce)) => {
+                Some(PowOutcome::with_rounds(input, &nonce, self.rounds))
+            }
+            _ => None,
+        }
+    }
+
+    /// Searches for a nonce on a separate thread, without blocking the caller.
+    ///
+    /// The search is given up with `PearlDiverState::TimedOut` once `timeout` or the deadline has passed. The returned
+    /// handle resolves to the final state of the search and cancels it when dropped before that.
+    pub fn search_async(&mut self, input: InputTrits, timeout: Option<Duration>) -> SearchHandle {
+        assert!(self.state() == PearlDiverState::Created);
 
+        // Switch the state before spawning, so that a search cancelled right away never starts
         self.set_state(PearlDiverState::Searching);
+        let deadline = timeout
+            .map(|timeout| Instant::now() + timeout)
+            .into_iter()
+            .chain(self.deadline)
+            .min();
+
+        SearchHandle::spawn(self.clone(), move |pearldiver| {
+            pearldiver.search(&input, deadline)
+        })
+    }
+
+    /// Searches for a nonce on the calling thread, once the state has been switched to `Searching`.
+    pub(crate) fn search(&mut self, input: &InputTrits, deadline: Option<Instant>) {
+        let prestate = make_prestate(input, self.rounds);
+        let lanes = self.simd.lanes();
+        let range = self.range;
+        let rounds = self.rounds;
 
         let num_cores = self.cores.clone();
+        let progress = self.progress.clone();
+
+        progress.start();
+
+        // The dives run on the shared workers, and the search is done once all of them dropped their `finished`.
+        // More dives than workers would only take turns, so there is at most one per worker.
+        let finished = WaitGroup::new();
+        for thread in 0..(*num_cores).min(workers().size()) {
+            // Every lane of every thread starts from a different outer nonce
+            let mut states = Vec::with_capacity(lanes);
+            for lane in 0..lanes {
+                let mut state = prestate.clone();
+                let outer = range.outer.saturating_add((thread * lanes + lane) as u64);
+                // A lane that overflows a region starts over, which doesn't matter for a search
+                add(&mut state, OUTER_INCR_START..INNER_INCR_START, outer);
+                add(&mut state, INNER_INCR_START..HASH_LEN, range.inner);
+                states.push(state);
+            }
 
-        crossbeam::scope(|scope| {
-            for _ in 0..*num_cores {
-                let mut state_thr = prestate.clone();
-
-                let pdstate = self.state.clone();
-                let difficulty = self.difficulty.clone();
-
-                scope.spawn(move |_| {
-                    let mut state_tmp = Curl64State::new(BITS_1);
-
-                    while *pdstate.read().unwrap() == PearlDiverState::Searching {
-                        unsafe {
-                            transform(&mut state_thr, &mut state_tmp);
-                        }
-
-                        if let Some(nonce) = find_nonce(&state_thr, &difficulty) {
-                            *pdstate.write().unwrap() = PearlDiverState::Completed(Some(nonce));
-                            break;
-                        } else {
-                            if {
-                                let exhausted = inner_increment(&mut state_thr);
-                                exhausted
-                            } {
-                                break;
-                            }
-                        }
-                    }
-                });
-
-                outer_increment(&mut prestate);
+            Dive {
+                thread,
+                simd: self.simd,
+                pdstate: self.state.clone(),
+                difficulty: self.difficulty.clone(),
+                progress: progress.clone(),
+                deadline,
+                limit: range.steps,
+                rounds,
+                states,
+                steps: 0,
+                finished: finished.clone(),
             }
-        })
-        .unwrap();
+            .spawn();
+        }
+
+        // Each reporter stops as soon as its sender of `done` is dropped, i.e. once all dives are done
+        let mut done = Vec::new();
+        let mut reporters = Vec::new();
+        for (interval, subscriber) in progress.subscribers() {
+            let (done_tx, done_rx) = mpsc::channel::<()>();
+            done.push(done_tx);
+
+            let progress = progress.clone();
+            reporters.push(thread::spawn(move || {
+                report(&progress, interval, subscriber, done_rx)
+            }));
+        }
+
+        finished.wait();
+        drop(done);
+        for reporter in reporters {
+            // Can't fail, reporting doesn't panic
+            reporter.join().unwrap();
+        }
 
         // If we reach this point, but the PearlDiver state hasn't been changed to `Completed` by some thread,
         // then we must have searched the whole space without finding a valid nonce, and we have to switch the
         // state to `Completed(None)` manually.
-        if self.state() == PearlDiverState::Searching {
-            self.set_state(PearlDiverState::Completed(None));
-        }
+        self.state
+            .switch(PearlDiverState::Searching, PearlDiverState::Completed(None));
+
+        progress.finish();
+
+        let stats = progress.stats();
+        metrics().search_finished(stats.hashes, stats.elapsed);
+
+        let outcome = match self.state() {
+            PearlDiverState::Completed(Some(_)) => "found",
+            PearlDiverState::Completed(None) => "exhausted",
+            PearlDiverState::TimedOut => "timed out",
+            _ => "cancelled",
+        };
+        debug!(
+            cores = *num_cores,
+            difficulty = *self.difficulty,
+            outcome = outcome,
+            hashes = stats.hashes,
+            hashes_per_second = stats.hashes_per_second();
+            "search finished"
+        );
+    }
+
+    /// The progress of the search so far.
+    pub fn stats(&self) -> PowStats {
+        self.progress.stats()
+    }
+
+    /// Subscribes to the progress of the search, which is sent every `interval` while searching, and once more when
+    /// the search finished; the subscription ends after that.
+    ///
+    /// Subscriptions have to be made before the search is started.
+    pub fn subscribe(&self, interval: Duration) -> Receiver<PowStats> {
+        self.progress.subscribe(interval)
     }
 
     pub fn cancel(&mut self) {
-        if self.state() == PearlDiverState::Searching {
-            self.set_state(PearlDiverState::Cancelled);
-        }
+        self.state
+            .switch(PearlDiverState::Searching, PearlDiverState::Cancelled);
     }
 
     pub fn state(&self) -> PearlDiverState {
-        *self.state.read().unwrap()
+        self.state.get()
     }
 
     pub fn set_state(&mut self, state: PearlDiverState) {
-        *self.state.write().unwrap() = state;
+        self.state.set(state);
     }
 }
 
-fn outer_increment(prestate: &mut Curl64State) {
-    for i in OUTER_INCR_START..INNER_INCR_START {
-        let with_carry = prestate.bit_add(i);
-        if !with_carry {
+/// The part of a search that runs on one worker.
+///
+/// A dive gives its worker up after `SLICE_MILLIS`, and continues once the dives that were queued in the meantime had
+/// their turn, so that concurrent searches share the workers instead of waiting for each other to finish.
+struct Dive {
+    thread: usize,
+    simd: Simd,
+    pdstate: Arc<SharedState>,
+    difficulty: Difficulty,
+    progress: Arc<Progress>,
+    deadline: Option<Instant>,
+    limit: Option<u64>,
+    rounds: Rounds,
+    states: Vec<Curl64State>,
+    steps: u64,
+    finished: WaitGroup,
+}
+
+impl Dive {
+    /// Queues the next slice of the dive on the workers.
+    fn spawn(mut self) {
+        workers().execute(move || {
+            let slice_end = Instant::now() + Duration::from_millis(SLICE_MILLIS);
+            let done = match self.simd {
+                #[cfg(target_arch = "x86_64")]
+                Simd::Avx2 => self.run::<Avx2>(slice_end),
+                #[cfg(target_arch = "x86_64")]
+                Simd::Sse2 => self.run::<Sse2>(slice_end),
+                _ => self.run::<u64>(slice_end),
+            };
+
+            if done {
+                self.progress.searched(self.steps);
+                drop(self.finished);
+            } else {
+                self.spawn();
+            }
+        });
+    }
+
+    /// Searches the nonces of the states until `slice_end`, and returns whether the dive is done.
+    ///
+    /// The states are transformed at once, `W::LANES` at a time. The words have to be supported by the CPU, which
+    /// `PearlDiver::with_simd` makes sure of.
+    fn run<W: Word>(&mut self, slice_end: Instant) -> bool {
+        let mut state_hash = WideState::<W>::new();
+        let mut state_tmp = WideState::<W>::new();
+
+        // A slice that has been queued for a while may start after the search was cancelled or timed out
+        loop {
+            if !self.pdstate.is_searching() {
+                return true;
+            }
+            if matches!(self.limit, Some(limit) if self.steps >= limit) {
+                return true;
+            }
+            if matches!(self.deadline, Some(deadline) if Instant::now() >= deadline) {
+                self.pdstate
+                    .switch(PearlDiverState::Searching, PearlDiverState::TimedOut);
+                return true;
+            }
+
+            // The nonce candidates are taken from the untransformed states, so only a copy is hashed
+            state_hash.load(&self.states);
+            unsafe {
+                W::transform(&mut state_hash, &mut state_tmp, *self.rounds);
+            }
+            // Each transform hashes a whole batch of nonces per lane at once
+            self.progress.add_hashes((BATCH_SIZE * W::LANES) as u64);
+
+            let zeros = state_hash.zero_trits((HASH_LEN - self.difficulty.0)..HASH_LEN);
+            if let Some(lane) = (0..W::LANES).find(|&lane| zeros[lane] != 0) {
+                let slot = zeros[lane].trailing_zeros() as usize;
+                let nonce = extract_nonce(&self.states[lane], slot);
+                if self.pdstate.switch(
+                    PearlDiverState::Searching,
+                    PearlDiverState::Completed(Some(nonce)),
+                ) {
+                    self.progress.found_by(self.thread);
+                }
+                return true;
+            }
+
+            let mut exhausted = false;
+            for state in self.states.iter_mut() {
+                exhausted |= inner_increment(state);
+            }
+            self.steps += 1;
+            if exhausted {
+                return true;
+            }
+
+            if Instant::now() >= slice_end {
+                return false;
+            }
+        }
+    }
+}
+
+/// Sends the progress to `subscriber` every `interval`, until `done` is disconnected.
+fn report(
+    progress: &Progress,
+    interval: Duration,
+    subscriber: mpsc::Sender<PowStats>,
+    done: Receiver<()>,
+) {
+    while let Err(RecvTimeoutError::Timeout) = done.recv_timeout(interval) {
+        if subscriber.send(progress.stats()).is_err() {
             break;
         }
     }
 }
 
+/// Adds `value` to the counter in `region` of the state, and returns whether it overflowed.
+fn add(state: &mut Curl64State, region: Range<usize>, mut value: u64) -> Exhausted {
+    let mut carry = 0;
+
+    for i in region {
+        if value == 0 && carry == 0 {
+            return false;
+        }
+
+        // At most three increments of a trit wrap it around at most once
+        let increments = value % 3 + carry;
+        value /= 3;
+        carry = 0;
+        for _ in 0..increments {
+            if state.bit_add(i) {
+                carry = 1;
+            }
+        }
+    }
+
+    value != 0 || carry != 0
+}
+
 fn inner_increment(prestate: &mut Curl64State) -> Exhausted {
     // we have not exhausted the search space until each add
     // operation produces a carry
@@ -128,7 +403,7 @@ fn inner_increment(prestate: &mut Curl64State) -> Exhausted {
     true
 }
 
-fn make_prestate(input: &InputTrits) -> Curl64State {
+fn make_prestate(input: &InputTrits, rounds: Rounds) -> Curl64State {
     let mut prestate = Curl64State::new(BITS_1);
     let mut tmpstate = Curl64State::new(BITS_1);
 
@@ -139,21 +414,19 @@ fn make_prestate(input: &InputTrits) -> Curl64State {
             match (*input)[offset] {
                 1 => prestate.set(i, BITS_1, BITS_0),
                 -1 => prestate.set(i, BITS_0, BITS_1),
-                _ => (),
+                _ => prestate.set(i, BITS_1, BITS_1),
             }
             offset += 1;
         }
 
-        unsafe {
-            transform(&mut prestate, &mut tmpstate);
-        }
+        prestate.transform(&mut tmpstate, *rounds);
     }
 
     for i in 0..CHUNK_NONCE_START {
         match (*input)[offset] {
             1 => prestate.set(i, BITS_1, BITS_0),
             -1 => prestate.set(i, BITS_0, BITS_1),
-            _ => (),
+            _ => prestate.set(i, BITS_1, BITS_1),
         }
         offset += 1;
     }
@@ -166,88 +439,15 @@ fn make_prestate(input: &InputTrits) -> Curl64State {
     prestate
 }
 
-/// NOTE: To prevent unnecessary allocations we instantiate the scratchpad (tmp) only once per core outside of
-/// this function.
-unsafe fn transform(pre: &mut Curl64State, tmp: &mut Curl64State) {
-    let (mut hpre, mut lpre) = pre.as_mut_ptr();
-    let (mut htmp, mut ltmp) = tmp.as_mut_ptr();
-
-    let mut lswp: *mut u64;
-    let mut hswp: *mut u64;
-
-    for _ in 0..(NUM_ROUNDS - 1) {
-        for j in 0..STATE_LEN {
-            let index1 = INDICES[j + 0];
-            let index2 = INDICES[j + 1];
-
-            let alpha = *lpre.offset(index1);
-            let kappa = *hpre.offset(index1);
-            let sigma = *lpre.offset(index2);
-            let gamma = *hpre.offset(index2);
-
-            let delta = (alpha | !gamma) & (sigma ^ kappa);
-
-            *ltmp.offset(j as isize) = !delta;
-            *htmp.offset(j as isize) = (alpha ^ gamma) | delta;
-        }
-
-        lswp = lpre;
-        hswp = hpre;
-        lpre = ltmp;
-        hpre = htmp;
-        ltmp = lswp;
-        htmp = hswp;
-    }
-
-    // NOTE: Since we don't compute a new state after that, we stop after 'HASH_LEN'.
-    for j in 0..HASH_LEN {
-        let index1 = INDICES[j + 0];
-        let index2 = INDICES[j + 1];
-
-        let alpha = *lpre.offset(index1);
-        let kappa = *hpre.offset(index1);
-        let sigma = *lpre.offset(index2);
-        let gamma = *hpre.offset(index2);
-
-        let delta = (alpha | !gamma) & (sigma ^ kappa);
-
-        *lpre.offset(j as isize) = !delta;
-        *hpre.offset(j as isize) = (alpha ^ gamma) | delta;
-    }
-}
-
-fn find_nonce(state: &Curl64State, difficulty: &Difficulty) -> Option<NonceTrits> {
-    let mut nonce_test = BITS_1;
-
-    for i in (HASH_LEN - difficulty.0)..HASH_LEN {
-        nonce_test &= state.bit_equal(i);
-
-        // If 'nonce_test' ever becomes 0, then this means that none of the current nonce candidates satisfied
-        // the difficulty setting
-        if nonce_test == 0 {
-            return None;
-        }
-    }
-
-    for slot in 0..BATCH_SIZE {
-        if (nonce_test >> slot) & 1 != 0 {
-            return Some(extract_nonce(&state, slot));
-        }
-    }
-
-    unreachable!()
-}
-
-/// Extracts the nonce from the final Curl state and the given slot index.
+/// Extracts the nonce from the untransformed Curl state and the given slot index.
 fn extract_nonce(state: &Curl64State, slot: usize) -> NonceTrits {
     let mut nonce = [0; NONCE_LEN];
     let mut offset = 0;
-    let slotmask = 1 << slot;
 
     for i in CHUNK_NONCE_START..HASH_LEN {
         let (hi, lo) = state.get(i);
 
-        match (hi & slotmask, lo & slotmask) {
+        match ((hi >> slot) & 1, (lo >> slot) & 1) {
             (1, 0) => nonce[offset] = 1,
             (0, 1) => nonce[offset] = -1,
             (_, _) => (),
@@ -263,7 +463,12 @@ impl Default for PearlDiver {
         Self {
             cores: Cores::default(),
             difficulty: Difficulty::default(),
-            state: Arc::new(RwLock::new(PearlDiverState::Created)),
+            simd: Simd::detect(),
+            range: NonceRange::default(),
+            rounds: Rounds::default(),
+            deadline: None,
+            state: Arc::new(SharedState::default()),
+            progress: Arc::new(Progress::default()),
         }
     }
 }
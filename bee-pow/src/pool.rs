@@ -0,0 +1,94 @@
+use crossbeam::channel::{self, Sender};
+use lazy_static::lazy_static;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+lazy_static! {
+    /// The workers shared by all searches, one per core.
+    static ref WORKERS: WorkerPool = WorkerPool::new(num_cpus::get());
+}
+
+/// A fixed number of threads that run tasks in the order they were submitted.
+///
+/// Searches hand their cores to the workers instead of spawning threads of their own, so that a search doesn't
+/// pay for starting threads and concurrent searches can't oversubscribe the CPU.
+pub(crate) struct WorkerPool {
+    tasks: Sender<Task>,
+    size: usize,
+}
+
+impl WorkerPool {
+    pub fn new(num_threads: usize) -> Self {
+        let (tasks, receiver) = channel::unbounded::<Task>();
+        let size = num_threads.max(1);
+
+        for _ in 0..size {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                for task in receiver {
+                    // A panicking task must not take the worker down with it
+                    let _ = panic::catch_unwind(AssertUnwindSafe(task));
+                }
+            });
+        }
+
+        Self { tasks, size }
+    }
+
+    /// The number of threads, i.e. of tasks that run at the same time.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn execute(&self, task: impl FnOnce() + Send + 'static) {
+        // Can't fail, the workers never stop receiving
+        self.tasks.send(Box::new(task)).unwrap();
+    }
+}
+
+pub(crate) fn workers() -> &'static WorkerPool {
+    &WORKERS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossbeam::sync::WaitGroup;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn run_all_tasks() {
+        let pool = WorkerPool::new(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        let done = WaitGroup::new();
+
+        for _ in 0..10 {
+            let counter = counter.clone();
+            let done = done.clone();
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                drop(done);
+            });
+        }
+        done.wait();
+
+        assert_eq!(counter.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn survive_panicking_tasks() {
+        let pool = WorkerPool::new(1);
+        let done = WaitGroup::new();
+
+        pool.execute(|| panic!("task failed"));
+        let finished = done.clone();
+        pool.execute(move || drop(finished));
+        done.wait();
+    }
+}
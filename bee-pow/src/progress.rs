@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A snapshot of the progress of a search.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PowStats {
+    /// The number of nonces tried so far, by all threads.
+    pub hashes: u64,
+    /// The time spent searching so far, or in total once the search finished.
+    pub elapsed: Duration,
+    /// The index of the thread that found the nonce, if one was found.
+    pub found_by: Option<usize>,
+}
+
+impl PowStats {
+    pub fn hashes_per_second(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1) as u64;
+        self.hashes.saturating_mul(1_000_000) / micros
+    }
+}
+
+#[derive(Default)]
+struct Times {
+    start: Option<Instant>,
+    end: Option<Instant>,
+    found_by: Option<usize>,
+}
+
+/// The progress of a search, shared between its threads and everyone polling it.
+#[derive(Default)]
+pub(crate) struct Progress {
+    hashes: AtomicU64,
+    times: Mutex<Times>,
+    /// The fewest inner steps any lane searched.
+    steps: Mutex<Option<u64>>,
+    subscribers: Mutex<Vec<(Duration, Sender<PowStats>)>>,
+}
+
+impl Progress {
+    pub fn start(&self) {
+        self.times.lock().unwrap().start = Some(Instant::now());
+    }
+
+    pub fn add_hashes(&self, hashes: u64) {
+        self.hashes.fetch_add(hashes, Ordering::Relaxed);
+    }
+
+    /// Records the inner steps a thread searched, once it stopped.
+    pub fn searched(&self, steps: u64) {
+        let mut fewest = self.steps.lock().unwrap();
+        *fewest = Some(fewest.map_or(steps, |fewest| fewest.min(steps)));
+    }
+
+    pub fn steps(&self) -> Option<u64> {
+        *self.steps.lock().unwrap()
+    }
+
+    pub fn found_by(&self, thread: usize) {
+        self.times.lock().unwrap().found_by = Some(thread);
+    }
+
+    /// Stops the clock and sends the final stats to all subscribers, which ends their subscriptions.
+    pub fn finish(&self) {
+        self.times.lock().unwrap().end = Some(Instant::now());
+
+        let stats = self.stats();
+        for (_, subscriber) in self.subscribers.lock().unwrap().drain(..) {
+            // The subscriber may have lost interest already
+            let _ = subscriber.send(stats);
+        }
+    }
+
+    pub fn stats(&self) -> PowStats {
+        let times = self.times.lock().unwrap();
+
+        PowStats {
+            hashes: self.hashes.load(Ordering::Relaxed),
+            elapsed: match (times.start, times.end) {
+                (Some(start), Some(end)) => end - start,
+                (Some(start), None) => start.elapsed(),
+                _ => Duration::default(),
+            },
+            found_by: times.found_by,
+        }
+    }
+
+    pub fn subscribe(&self, interval: Duration) -> Receiver<PowStats> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push((interval, sender));
+        receiver
+    }
+
+    /// The reporting intervals of the subscribers, with the channels to send their stats on.
+    pub fn subscribers(&self) -> Vec<(Duration, Sender<PowStats>)> {
+        self.subscribers.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Cores, Difficulty, InputTrits, PearlDiver, PearlDiverState};
+
+    use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+
+    #[test]
+    fn report_found_nonce() {
+        let mut pearldiver = PearlDiver::new(Cores::from(2), Difficulty::from(6));
+        assert_eq!(pearldiver.stats(), PowStats::default());
+
+        pearldiver.search_sync(&InputTrits([1; TRANSACTION_TRIT_LEN]));
+        let stats = pearldiver.stats();
+
+        assert!(stats.hashes > 0);
+        assert_eq!(stats.hashes % 64, 0);
+        assert!(stats.found_by.unwrap() < 2);
+        assert!(stats.elapsed > Duration::default());
+        // The clock stops with the search
+        assert_eq!(pearldiver.stats(), stats);
+    }
+
+    #[test]
+    fn send_progress_to_subscribers() {
+        let pearldiver = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN));
+        let subscription = pearldiver.subscribe(Duration::from_millis(5));
+
+        // An all-zero transaction hashes to zero, so only a non-zero one keeps the search going
+        let handle = pearldiver
+            .clone()
+            .search_async(InputTrits([1; TRANSACTION_TRIT_LEN]), None);
+
+        let mut reports = Vec::new();
+        for stats in subscription.iter() {
+            reports.push(stats);
+            if reports.len() == 3 {
+                handle.cancel();
+            }
+        }
+
+        assert!(handle.state() == PearlDiverState::Cancelled);
+        assert!(reports.len() > 3);
+        assert!(reports.windows(2).all(|w| w[0].hashes <= w[1].hashes));
+        assert_eq!(reports.last(), Some(&handle.stats()));
+        assert_eq!(reports.last().unwrap().found_by, None);
+    }
+}
@@ -0,0 +1,157 @@
+/// A deterministic part of the nonce space, so that a search can be split between processes or resumed.
+///
+/// Every lane of a search (one per thread, times the lanes of its `Simd`) starts from its own value of the outer
+/// increment region, lane `i` from `outer + i`, and then walks the inner increment region, starting `inner` steps
+/// in. Each step tries one batch of 64 nonces per lane.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NonceRange {
+    /// The value of the outer increment region of the first lane.
+    pub outer: u64,
+    /// The number of inner steps that are skipped.
+    pub inner: u64,
+    /// The number of inner steps each lane searches at most, or `None` for the whole inner region.
+    pub steps: Option<u64>,
+}
+
+impl NonceRange {
+    /// Splits the inner steps into `parts` consecutive ranges of (almost) equal size.
+    ///
+    /// The parts only complement each other if they are searched with the same number of cores and `Simd`.
+    pub fn split(&self, parts: u64) -> Vec<NonceRange> {
+        let parts = parts.max(1);
+        let steps = self.steps.unwrap_or(u64::MAX - self.inner);
+        let (len, rest) = (steps / parts, steps % parts);
+
+        let mut inner = self.inner;
+        (0..parts)
+            .map(|part| {
+                let steps = len + if part < rest { 1 } else { 0 };
+                let range = NonceRange {
+                    outer: self.outer,
+                    inner,
+                    steps: Some(steps),
+                };
+                inner += steps;
+                range
+            })
+            .collect()
+    }
+
+    /// The range that is left after every lane searched `steps` inner steps of this one.
+    pub fn skip(&self, steps: u64) -> NonceRange {
+        NonceRange {
+            outer: self.outer,
+            inner: self.inner.saturating_add(steps),
+            steps: self.steps.map(|total| total.saturating_sub(steps)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Cores, Difficulty, InputTrits, PearlDiver, PearlDiverState, Simd};
+
+    use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+
+    use std::time::Duration;
+
+    fn pearldiver(range: NonceRange) -> PearlDiver {
+        PearlDiver::new(Cores::from(1), Difficulty::from(8))
+            .with_simd(Simd::Scalar)
+            .with_range(range)
+    }
+
+    #[test]
+    fn split_into_consecutive_parts() {
+        let range = NonceRange {
+            outer: 3,
+            inner: 10,
+            steps: Some(11),
+        };
+
+        let parts = range.split(3);
+
+        assert_eq!(
+            parts
+                .iter()
+                .map(|part| (part.inner, part.steps))
+                .collect::<Vec<_>>(),
+            vec![(10, Some(4)), (14, Some(4)), (18, Some(3))]
+        );
+        assert!(parts.iter().all(|part| part.outer == 3));
+    }
+
+    #[test]
+    fn split_search_between_pearldivers() {
+        let input = InputTrits([1; TRANSACTION_TRIT_LEN]);
+
+        let mut whole = pearldiver(NonceRange::default());
+        whole.search_sync(&input);
+        let nonce = match whole.state() {
+            PearlDiverState::Completed(Some(nonce)) => nonce,
+            _ => panic!("no nonce found"),
+        };
+        // The steps before the one that found the nonce
+        let steps = whole.resume_range().inner;
+        assert!(steps > 1);
+
+        let parts = NonceRange {
+            steps: Some(steps + 1),
+            ..NonceRange::default()
+        }
+        .split(2);
+
+        let mut first = pearldiver(parts[0]);
+        first.search_sync(&input);
+        assert!(first.state() == PearlDiverState::Completed(None));
+        assert_eq!(first.resume_range().inner, parts[1].inner);
+
+        let mut second = pearldiver(parts[1]);
+        second.search_sync(&input);
+        assert!(second.state() == PearlDiverState::Completed(Some(nonce)));
+    }
+
+    #[tokio::test]
+    async fn resume_cancelled_search() {
+        let input = InputTrits([1; TRANSACTION_TRIT_LEN]);
+        let range = NonceRange {
+            outer: 5,
+            inner: 7,
+            steps: None,
+        };
+        let pearldiver =
+            PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN)).with_range(range);
+        let subscription = pearldiver.subscribe(Duration::from_millis(5));
+
+        let handle = pearldiver.clone().search_async(input, None);
+        // Wait for a few steps to be searched
+        while subscription.recv().unwrap().hashes < 3 * 64 {}
+        handle.cancel();
+        assert!(handle.await == PearlDiverState::Cancelled);
+
+        let resumed = pearldiver.resume_range();
+        assert_eq!(resumed.outer, range.outer);
+        assert!(resumed.inner > range.inner);
+        assert_eq!(resumed.steps, None);
+    }
+
+    #[test]
+    fn search_disjoint_outer_values() {
+        let input = InputTrits([1; TRANSACTION_TRIT_LEN]);
+        let nonce = |outer| {
+            let mut pearldiver = pearldiver(NonceRange {
+                outer,
+                ..NonceRange::default()
+            });
+            pearldiver.search_sync(&input);
+            match pearldiver.state() {
+                PearlDiverState::Completed(Some(nonce)) => nonce,
+                _ => panic!("no nonce found"),
+            }
+        };
+
+        assert!(nonce(0) != nonce(1));
+    }
+}
@@ -0,0 +1,73 @@
+use super::protocol::{Request, Response};
+
+use common::{Error, Result};
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+/// A connection to a `PowServer`, for clients that don't search nonces themselves.
+pub struct PowClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    next_id: u64,
+}
+
+impl PowClient {
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self> {
+        let writer = TcpStream::connect(address)
+            .map_err(|e| Error::RuntimeError(format!("cannot connect to the PoW server: {}", e)))?;
+        let reader = writer
+            .try_clone()
+            .map(BufReader::new)
+            .map_err(network_error)?;
+
+        Ok(Self {
+            reader,
+            writer,
+            next_id: 0,
+        })
+    }
+
+    /// Does the proof of work of a transaction remotely, and returns the transaction stamped with the nonce.
+    ///
+    /// Responses to other jobs that arrive in the meantime are dropped.
+    pub fn pow(&mut self, trytes: &str, mwm: usize) -> Result<String> {
+        let id = self.next_id.to_string();
+        self.next_id += 1;
+
+        self.send(&Request::Pow {
+            id: id.clone(),
+            mwm,
+            trytes: trytes.to_string(),
+        })?;
+
+        loop {
+            match self.receive()? {
+                response if response.id() != id => continue,
+                Response::Done { trytes, .. } => return Ok(trytes),
+                Response::Cancelled { .. } => {
+                    return Err(Error::RuntimeError(String::from("proof of work cancelled")))
+                }
+                Response::Error { message, .. } => return Err(Error::RuntimeError(message)),
+            }
+        }
+    }
+
+    /// Sends a request without waiting for its response.
+    pub fn send(&mut self, request: &Request) -> Result<()> {
+        writeln!(self.writer, "{}", request).map_err(network_error)
+    }
+
+    /// Waits for the next response, whichever job it belongs to.
+    pub fn receive(&mut self) -> Result<Response> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line).map_err(network_error)? {
+            0 => Err(Error::NetworkError),
+            _ => line.parse().map_err(Error::RuntimeError),
+        }
+    }
+}
+
+fn network_error(_: std::io::Error) -> Error {
+    Error::NetworkError
+}
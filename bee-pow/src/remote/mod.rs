@@ -0,0 +1,180 @@
+//! Proof of work as a service, so that light clients don't have to link `PearlDiver`.
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::PowClient;
+pub use protocol::{Request, Response, MAX_LINE_LEN, UNKNOWN_ID};
+pub use server::{PowServer, ServerConfig};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::outcome::{hash, trailing_zeros};
+    use crate::{Cores, InputTrits, Rounds};
+
+    use common::constants::{HASH_TRIT_LEN, NONCE_TRIT_LEN, TRANSACTION_TRYT_LEN};
+    use ternary::iota_conversion::Trinary;
+
+    use std::convert::TryFrom;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::thread;
+
+    fn start(config: ServerConfig) -> SocketAddr {
+        let server = PowServer::bind("127.0.0.1:0", config).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    fn config() -> ServerConfig {
+        ServerConfig {
+            cores: Cores::from(1),
+            max_jobs: 1,
+            queue_len: 1,
+            max_mwm: HASH_TRIT_LEN,
+            max_clients: 2,
+        }
+    }
+
+    fn trytes() -> String {
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ9"
+            .chars()
+            .cycle()
+            .take(TRANSACTION_TRYT_LEN)
+            .collect()
+    }
+
+    fn pow(id: &str, mwm: usize) -> Request {
+        Request::Pow {
+            id: id.to_string(),
+            mwm,
+            trytes: trytes(),
+        }
+    }
+
+    #[test]
+    fn stamp_transaction_with_nonce() {
+        let mut client = PowClient::connect(start(config())).unwrap();
+
+        let stamped = client.pow(&trytes(), 3).unwrap();
+
+        let input = InputTrits::try_from(&stamped.trits()[..]).unwrap();
+
+        let nonce_start = TRANSACTION_TRYT_LEN - NONCE_TRIT_LEN / 3;
+        assert_eq!(&stamped[..nonce_start], &trytes()[..nonce_start]);
+        assert!(trailing_zeros(&hash(&input, Rounds::default())) >= 3);
+    }
+
+    #[test]
+    fn cancel_running_and_queued_jobs() {
+        let mut client = PowClient::connect(start(config())).unwrap();
+
+        client.send(&pow("running", HASH_TRIT_LEN)).unwrap();
+        client.send(&pow("queued", HASH_TRIT_LEN)).unwrap();
+        client.send(&pow("rejected", HASH_TRIT_LEN)).unwrap();
+
+        match client.receive().unwrap() {
+            Response::Error { id, message } => {
+                assert_eq!(id, "rejected");
+                assert_eq!(message, "the queue is full");
+            }
+            response => panic!("unexpected response {}", response),
+        }
+
+        client
+            .send(&Request::Cancel {
+                id: String::from("queued"),
+            })
+            .unwrap();
+        client
+            .send(&Request::Cancel {
+                id: String::from("running"),
+            })
+            .unwrap();
+
+        let mut cancelled = vec![client.receive().unwrap(), client.receive().unwrap()];
+        cancelled.sort_by(|a, b| a.id().cmp(b.id()));
+        assert_eq!(
+            cancelled,
+            vec![
+                Response::Cancelled {
+                    id: String::from("queued")
+                },
+                Response::Cancelled {
+                    id: String::from("running")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_requests() {
+        let mut client = PowClient::connect(start(config())).unwrap();
+
+        assert!(client.pow("ABC", 3).is_err());
+        assert!(client.pow(&trytes(), 0).is_err());
+
+        client
+            .send(&Request::Cancel {
+                id: String::from("1"),
+            })
+            .unwrap();
+        assert!(matches!(client.receive().unwrap(), Response::Error { .. }));
+    }
+
+    #[test]
+    fn disconnect_clients_sending_long_lines() {
+        let address = start(config());
+        let mut stream = TcpStream::connect(address).unwrap();
+
+        // A line exactly as long as allowed is read as a request
+        let line = format!("CANCEL {}", "9".repeat(MAX_LINE_LEN - 7));
+        writeln!(stream, "{}", line).unwrap();
+        // A longer one isn't even read to its end
+        stream.write_all(&vec![b'9'; 2 * MAX_LINE_LEN]).unwrap();
+
+        let mut reader = BufReader::new(stream);
+        let mut responses = Vec::new();
+        let mut response = String::new();
+        while reader.read_line(&mut response).unwrap() > 0 {
+            responses.push(response.parse::<Response>().unwrap());
+            response.clear();
+        }
+
+        assert_eq!(responses.len(), 2);
+        assert!(
+            matches!(&responses[0], Response::Error { message, .. } if message.starts_with("unknown job"))
+        );
+        assert!(
+            matches!(&responses[1], Response::Error { message, .. } if message.starts_with("request longer"))
+        );
+    }
+
+    #[test]
+    fn refuse_clients_beyond_the_limit() {
+        let address = start(config());
+        let mut first = PowClient::connect(address).unwrap();
+        let _second = PowClient::connect(address).unwrap();
+        // Served once they are both accepted
+        assert!(first.pow(&trytes(), 0).is_err());
+
+        let mut third = PowClient::connect(address).unwrap();
+        match third.receive().unwrap() {
+            Response::Error { id, message } => {
+                assert_eq!(id, UNKNOWN_ID);
+                assert_eq!(message, "too many clients");
+            }
+            response => panic!("unexpected response {}", response),
+        }
+
+        // A place is freed once a client disconnects
+        drop(first);
+        thread::sleep(std::time::Duration::from_millis(100));
+        let mut fourth = PowClient::connect(address).unwrap();
+        assert!(fourth.pow(&trytes(), 3).is_ok());
+    }
+}
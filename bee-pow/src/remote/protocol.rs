@@ -0,0 +1,159 @@
+//! The line based protocol between `PowClient` and `PowServer`.
+//!
+//! Every request and every response is a single line of space separated fields:
+//!
+//! ```text
+//! POW <id> <mwm> <trytes>     asks for the proof of work of a transaction
+//! CANCEL <id>                 cancels a queued or running job
+//!
+//! DONE <id> <trytes>          the transaction, stamped with a nonce
+//! CANCELLED <id>              the job was cancelled
+//! ERROR <id> <message>        the job failed, `-` is used as id if the request couldn't be parsed
+//! ```
+//!
+//! Ids are chosen by the client and only have to be unique among its pending jobs. Lines longer than `MAX_LINE_LEN`
+//! bytes are rejected, and the connection closed.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The id used in responses to requests without a readable id.
+pub const UNKNOWN_ID: &str = "-";
+
+/// The longest line a server reads, without its line break; a `POW` request of a whole transaction is far shorter.
+pub const MAX_LINE_LEN: usize = 4096;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Request {
+    Pow {
+        id: String,
+        mwm: usize,
+        trytes: String,
+    },
+    Cancel {
+        id: String,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Response {
+    Done { id: String, trytes: String },
+    Cancelled { id: String },
+    Error { id: String, message: String },
+}
+
+impl Response {
+    pub fn id(&self) -> &str {
+        match self {
+            Response::Done { id, .. } => id,
+            Response::Cancelled { id } => id,
+            Response::Error { id, .. } => id,
+        }
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Request::Pow { id, mwm, trytes } => write!(f, "POW {} {} {}", id, mwm, trytes),
+            Request::Cancel { id } => write!(f, "CANCEL {}", id),
+        }
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Done { id, trytes } => write!(f, "DONE {} {}", id, trytes),
+            Response::Cancelled { id } => write!(f, "CANCELLED {}", id),
+            Response::Error { id, message } => write!(f, "ERROR {} {}", id, message),
+        }
+    }
+}
+
+impl FromStr for Request {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        match fields.as_slice() {
+            ["POW", id, mwm, trytes] => Ok(Request::Pow {
+                id: id.to_string(),
+                mwm: mwm
+                    .parse()
+                    .map_err(|_| format!("invalid minimum weight magnitude {}", mwm))?,
+                trytes: trytes.to_string(),
+            }),
+            ["CANCEL", id] => Ok(Request::Cancel { id: id.to_string() }),
+            _ => Err(String::from("malformed request")),
+        }
+    }
+}
+
+impl FromStr for Response {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut fields = line.trim_end().splitn(3, ' ');
+
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some("DONE"), Some(id), Some(trytes)) => Ok(Response::Done {
+                id: id.to_string(),
+                trytes: trytes.to_string(),
+            }),
+            (Some("CANCELLED"), Some(id), None) => Ok(Response::Cancelled { id: id.to_string() }),
+            (Some("ERROR"), Some(id), Some(message)) => Ok(Response::Error {
+                id: id.to_string(),
+                message: message.to_string(),
+            }),
+            _ => Err(String::from("malformed response")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_what_is_written() {
+        let requests = vec![
+            Request::Pow {
+                id: String::from("1"),
+                mwm: 14,
+                trytes: String::from("ABC9"),
+            },
+            Request::Cancel {
+                id: String::from("1"),
+            },
+        ];
+        for request in requests {
+            assert_eq!(request.to_string().parse::<Request>(), Ok(request));
+        }
+
+        let responses = vec![
+            Response::Done {
+                id: String::from("1"),
+                trytes: String::from("ABC9"),
+            },
+            Response::Cancelled {
+                id: String::from("1"),
+            },
+            Response::Error {
+                id: String::from(UNKNOWN_ID),
+                message: String::from("malformed request"),
+            },
+        ];
+        for response in responses {
+            assert_eq!(response.to_string().parse::<Response>(), Ok(response));
+        }
+    }
+
+    #[test]
+    fn reject_malformed_requests() {
+        assert!("POW 1 fourteen ABC9".parse::<Request>().is_err());
+        assert!("POW 1 14".parse::<Request>().is_err());
+        assert!("STOP 1".parse::<Request>().is_err());
+    }
+}
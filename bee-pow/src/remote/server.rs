@@ -0,0 +1,377 @@
+use super::protocol::{Request, Response, MAX_LINE_LEN, UNKNOWN_ID};
+
+use crate::cores::Cores;
+use crate::difficulty::Difficulty;
+use crate::input::InputTrits;
+use crate::outcome::PowOutcome;
+use crate::pearldiver::{PearlDiver, PearlDiverState};
+
+use common::constants::{NETWORK_DIFFICULTY, TRANSACTION_TRYT_LEN};
+use common::{Error, Result};
+use ternary::iota_conversion::Trinary;
+
+use log::{debug, warn};
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// The pending jobs of a connection, by id.
+type Jobs = Arc<Mutex<HashMap<String, PearlDiver>>>;
+
+#[derive(Clone)]
+pub struct ServerConfig {
+    /// The cores used by every job.
+    pub cores: Cores,
+    /// The number of jobs that are searched at the same time.
+    pub max_jobs: usize,
+    /// The number of jobs that may wait for a search, on top of the running ones.
+    pub queue_len: usize,
+    /// The highest minimum weight magnitude that is accepted.
+    pub max_mwm: usize,
+    /// The number of clients served at the same time, further connections are refused.
+    pub max_clients: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            cores: Cores::default(),
+            max_jobs: 1,
+            queue_len: 16,
+            max_mwm: NETWORK_DIFFICULTY,
+            max_clients: 64,
+        }
+    }
+}
+
+/// Does the proof of work for remote clients, see `protocol` for the requests it understands.
+///
+/// Jobs are queued and searched by `max_jobs` workers in the order they were received. All pending jobs of a
+/// client are cancelled once it disconnects. Every client is served by its own threads, so at most `max_clients` are
+/// served at once.
+pub struct PowServer {
+    listener: TcpListener,
+    config: ServerConfig,
+}
+
+impl PowServer {
+    pub fn bind(address: impl ToSocketAddrs, config: ServerConfig) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .map_err(|e| Error::RuntimeError(format!("cannot bind the PoW server: {}", e)))?;
+
+        Ok(Self { listener, config })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .map_err(|e| Error::RuntimeError(format!("cannot get the PoW server address: {}", e)))
+    }
+
+    /// Serves clients until accepting a connection fails.
+    pub fn run(self) -> Result<()> {
+        let max_jobs = self.config.max_jobs.max(1);
+        let queue = Arc::new(Queue::new(max_jobs + self.config.queue_len));
+        let clients = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..max_jobs {
+            let queue = queue.clone();
+            thread::spawn(move || work(&queue));
+        }
+
+        loop {
+            let (stream, peer) = self
+                .listener
+                .accept()
+                .map_err(|e| Error::RuntimeError(format!("cannot accept PoW clients: {}", e)))?;
+
+            if clients.fetch_add(1, Ordering::SeqCst) >= self.config.max_clients {
+                clients.fetch_sub(1, Ordering::SeqCst);
+                warn!(peer:% = peer; "refusing PoW client, too many are connected");
+                refuse(stream, "too many clients");
+                continue;
+            }
+            debug!(peer:% = peer; "PoW client connected");
+
+            let config = self.config.clone();
+            let queue = queue.clone();
+            let clients = clients.clone();
+            thread::spawn(move || {
+                if let Err(e) = serve(stream, &config, &queue) {
+                    warn!(peer:% = peer, error:% = e; "PoW client failed");
+                }
+                clients.fetch_sub(1, Ordering::SeqCst);
+                debug!(peer:% = peer; "PoW client disconnected");
+            });
+        }
+    }
+}
+
+struct Job {
+    id: String,
+    input: InputTrits,
+    pearldiver: PearlDiver,
+    jobs: Jobs,
+    responses: Sender<Response>,
+}
+
+impl Job {
+    fn run(mut self) {
+        if self
+            .pearldiver
+            .state
+            .switch(PearlDiverState::Created, PearlDiverState::Searching)
+        {
+            self.pearldiver.search(&self.input, None);
+        }
+
+        let id = self.id.clone();
+        let response = match self.pearldiver.state() {
+            PearlDiverState::Completed(Some(nonce)) => {
+                match (&PowOutcome::new(self.input, &nonce).transaction[..]).trytes() {
+                    Ok(trytes) => Response::Done { id, trytes },
+                    Err(e) => Response::Error {
+                        id,
+                        message: e.to_string(),
+                    },
+                }
+            }
+            PearlDiverState::Completed(None) => Response::Error {
+                id,
+                message: String::from("no nonce found"),
+            },
+            _ => Response::Cancelled { id },
+        };
+
+        self.jobs.lock().unwrap().remove(&self.id);
+        // The client may be gone already, which is fine
+        let _ = self.responses.send(response);
+    }
+}
+
+/// The jobs waiting for a worker, and the ones being searched.
+struct Queue {
+    jobs: Mutex<Pending>,
+    available: Condvar,
+    capacity: usize,
+}
+
+struct Pending {
+    waiting: VecDeque<Job>,
+    /// Both the waiting and the running jobs.
+    count: usize,
+}
+
+impl Queue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            jobs: Mutex::new(Pending {
+                waiting: VecDeque::with_capacity(capacity),
+                count: 0,
+            }),
+            available: Condvar::new(),
+            capacity,
+        }
+    }
+
+    /// Returns whether the job was queued, which it isn't if the queue is full.
+    fn push(&self, job: Job) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.count >= self.capacity {
+            return false;
+        }
+
+        jobs.waiting.push_back(job);
+        jobs.count += 1;
+        self.available.notify_one();
+        true
+    }
+
+    fn pop(&self) -> Job {
+        let mut jobs = self.jobs.lock().unwrap();
+        loop {
+            match jobs.waiting.pop_front() {
+                Some(job) => return job,
+                None => jobs = self.available.wait(jobs).unwrap(),
+            }
+        }
+    }
+
+    /// Frees the place of a job that was popped.
+    fn finished(&self) {
+        self.jobs.lock().unwrap().count -= 1;
+    }
+}
+
+fn work(queue: &Queue) {
+    loop {
+        queue.pop().run();
+        queue.finished();
+    }
+}
+
+/// Reads the requests of a client until it disconnects, and cancels all its pending jobs then.
+fn serve(stream: TcpStream, config: &ServerConfig, queue: &Queue) -> io::Result<()> {
+    let (responses, receiver) = mpsc::channel();
+    let writer = stream.try_clone()?;
+    thread::spawn(move || write_responses(writer, receiver));
+
+    let jobs = Jobs::default();
+    let result = read_requests(stream, config, queue, &jobs, &responses);
+
+    for pearldiver in jobs.lock().unwrap().values() {
+        cancel(pearldiver);
+    }
+
+    result
+}
+
+fn read_requests(
+    stream: TcpStream,
+    config: &ServerConfig,
+    queue: &Queue,
+    jobs: &Jobs,
+    responses: &Sender<Response>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        // One byte more than a line break after the longest line, so that longer lines can be told apart
+        let len = (&mut reader)
+            .take(MAX_LINE_LEN as u64 + 2)
+            .read_line(&mut line)?;
+
+        let request = line.trim_end_matches('\n').trim_end_matches('\r');
+
+        if len == 0 {
+            break;
+        }
+        if request.len() > MAX_LINE_LEN {
+            // The rest of the line can't be told from the next request, so the client is disconnected
+            let _ = responses.send(Response::Error {
+                id: String::from(UNKNOWN_ID),
+                message: format!("request longer than {} bytes", MAX_LINE_LEN),
+            });
+            break;
+        }
+
+        let response = match request.parse::<Request>() {
+            Ok(Request::Pow { id, mwm, trytes }) => {
+                submit(id, mwm, &trytes, config, queue, jobs, responses).err()
+            }
+            Ok(Request::Cancel { id }) => match jobs.lock().unwrap().get(&id) {
+                // The worker responds once the job stopped
+                Some(pearldiver) => {
+                    cancel(pearldiver);
+                    None
+                }
+                None => Some(Response::Error {
+                    message: format!("unknown job {}", id),
+                    id,
+                }),
+            },
+            Err(message) => Some(Response::Error {
+                id: String::from(UNKNOWN_ID),
+                message,
+            }),
+        };
+
+        if let Some(response) = response {
+            // The writer only stops early if the client can't be written to anymore
+            if responses.send(response).is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Queues a job, or returns the error to respond with.
+fn submit(
+    id: String,
+    mwm: usize,
+    trytes: &str,
+    config: &ServerConfig,
+    queue: &Queue,
+    jobs: &Jobs,
+    responses: &Sender<Response>,
+) -> std::result::Result<(), Response> {
+    let error = |id: &str, message: String| Response::Error {
+        id: id.to_string(),
+        message,
+    };
+
+    if mwm == 0 || mwm > config.max_mwm {
+        return Err(error(
+            &id,
+            format!("minimum weight magnitude must be in 1..={}", config.max_mwm),
+        ));
+    }
+    if trytes.len() != TRANSACTION_TRYT_LEN
+        || !trytes.chars().all(|c| c == '9' || c.is_ascii_uppercase())
+    {
+        return Err(error(
+            &id,
+            format!("a transaction must be {} trytes long", TRANSACTION_TRYT_LEN),
+        ));
+    }
+    // Can't fail, the length has been checked
+    let input = InputTrits::try_from(&trytes.trits()[..]).unwrap();
+
+    let mut pending = jobs.lock().unwrap();
+    if pending.contains_key(&id) {
+        return Err(error(&id, format!("job {} is already pending", id)));
+    }
+
+    let pearldiver = PearlDiver::new(config.cores.clone(), Difficulty::from(mwm));
+    pending.insert(id.clone(), pearldiver.clone());
+
+    let job = Job {
+        id: id.clone(),
+        input,
+        pearldiver,
+        jobs: jobs.clone(),
+        responses: responses.clone(),
+    };
+    if !queue.push(job) {
+        pending.remove(&id);
+        return Err(error(&id, String::from("the queue is full")));
+    }
+
+    Ok(())
+}
+
+/// Responds with an error to a client that isn't served, and disconnects it.
+fn refuse(mut stream: TcpStream, message: &str) {
+    let response = Response::Error {
+        id: String::from(UNKNOWN_ID),
+        message: message.to_string(),
+    };
+    // The client may be gone already, which is fine
+    let _ = writeln!(stream, "{}", response);
+}
+
+fn write_responses(mut stream: TcpStream, responses: Receiver<Response>) {
+    for response in responses {
+        if writeln!(stream, "{}", response).is_err() {
+            break;
+        }
+    }
+}
+
+/// Cancels a job, whether it is still queued or already searched.
+fn cancel(pearldiver: &PearlDiver) {
+    let state = &pearldiver.state;
+    if !state.switch(PearlDiverState::Created, PearlDiverState::Cancelled) {
+        state.switch(PearlDiverState::Searching, PearlDiverState::Cancelled);
+    }
+}
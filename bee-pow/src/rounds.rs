@@ -0,0 +1,90 @@
+use crypto::curl::constants::{CURL_P_27, CURL_P_81};
+
+/// The number of rounds of the Curl transform that is searched with, Curl-P-81 by default.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rounds(pub(self) usize);
+
+impl Rounds {
+    pub const CURL_P_27: Rounds = Rounds(CURL_P_27);
+    pub const CURL_P_81: Rounds = Rounds(CURL_P_81);
+}
+
+impl Default for Rounds {
+    fn default() -> Self {
+        Self::CURL_P_81
+    }
+}
+
+impl From<usize> for Rounds {
+    fn from(num_rounds: usize) -> Self {
+        Self(num_rounds.max(1))
+    }
+}
+
+impl std::ops::Deref for Rounds {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::outcome::hash;
+    use crate::{Cores, Difficulty, InputTrits, PearlDiver};
+
+    use common::constants::TRANSACTION_TRYT_LEN;
+    use ternary::iota_conversion::Trinary;
+
+    use std::convert::TryFrom;
+
+    const KNOWN_ANSWERS: [(Rounds, &str); 2] = [
+        (
+            Rounds::CURL_P_27,
+            "MDVUSGZBNPVMPUNNNQ99VHWIFOTWVKNOIGK9WTX9C9ZYVXHPYDWJBCCGZICENOJOEDIQONKOBGMWCBRPW",
+        ),
+        (
+            Rounds::CURL_P_81,
+            "CCKMVNGLUAFT9XX9TPXPPJQODVYZKVCNBFKUPWBNWA99FPQFQXGGFWXQPAYVPVSDVYLPMCTFOZCYYAW9M",
+        ),
+    ];
+
+    fn input() -> InputTrits {
+        let trytes = "ABCDEFGHIJKLMNOPQRSTUVWXYZ9"
+            .chars()
+            .cycle()
+            .take(TRANSACTION_TRYT_LEN)
+            .collect::<String>();
+
+        InputTrits::try_from(&trytes.trits()[..]).unwrap()
+    }
+
+    #[test]
+    fn hash_known_answers() {
+        for (rounds, expected) in KNOWN_ANSWERS.iter() {
+            assert_eq!(&(&hash(&input(), *rounds)[..]).trytes().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn search_nonce_for_every_variant() {
+        for (rounds, _) in KNOWN_ANSWERS.iter() {
+            let outcome = PearlDiver::new(Cores::from(2), Difficulty::from(6))
+                .with_rounds(*rounds)
+                .search_transaction(input())
+                .unwrap();
+
+            assert!(outcome.trailing_zeros >= 6, "{:?}", rounds);
+            assert_eq!(&outcome.hash[..], &hash(&outcome.transaction, *rounds)[..]);
+        }
+    }
+
+    #[test]
+    fn have_at_least_one_round() {
+        assert_eq!(*Rounds::from(0), 1);
+        assert_eq!(Rounds::default(), Rounds::CURL_P_81);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::pearldiver::{PearlDiver, PearlDiverState};
+use crate::progress::{PowStats, Progress};
+use crate::state::SharedState;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// The outcome of a search and the task waiting for it.
+#[derive(Default)]
+struct Outcome {
+    state: Option<PearlDiverState>,
+    waker: Option<Waker>,
+}
+
+/// Publishes the final state of a search when dropped, even if the search panicked, so that no task waits forever.
+struct Publisher {
+    pdstate: Arc<SharedState>,
+    outcome: Arc<Mutex<Outcome>>,
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        let state = match self.pdstate.get() {
+            // Only a panicking search can end without leaving the `Searching` state
+            PearlDiverState::Searching => PearlDiverState::Cancelled,
+            state => state,
+        };
+
+        let mut outcome = self.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        outcome.state = Some(state);
+        if let Some(waker) = outcome.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Cancels the search it was taken from; it can be cloned and moved freely.
+#[derive(Clone)]
+pub struct CancelToken {
+    pdstate: Arc<SharedState>,
+}
+
+impl CancelToken {
+    /// Switches the search to `PearlDiverState::Cancelled`, unless it has already finished.
+    pub fn cancel(&self) {
+        self.pdstate
+            .switch(PearlDiverState::Searching, PearlDiverState::Cancelled);
+    }
+}
+
+/// A search running on a separate thread, that resolves to the final `PearlDiverState` of the search.
+///
+/// Dropping the handle before it resolved cancels the search.
+pub struct SearchHandle {
+    pdstate: Arc<SharedState>,
+    progress: Arc<Progress>,
+    outcome: Arc<Mutex<Outcome>>,
+}
+
+impl SearchHandle {
+    pub(crate) fn spawn<F>(mut pearldiver: PearlDiver, search: F) -> Self
+    where
+        F: FnOnce(&mut PearlDiver) + Send + 'static,
+    {
+        let outcome = Arc::new(Mutex::new(Outcome::default()));
+        let publisher = Publisher {
+            pdstate: pearldiver.state.clone(),
+            outcome: outcome.clone(),
+        };
+        let pdstate = pearldiver.state.clone();
+        let progress = pearldiver.progress.clone();
+
+        thread::spawn(move || {
+            let _publisher = publisher;
+            search(&mut pearldiver);
+        });
+
+        Self {
+            pdstate,
+            progress,
+            outcome,
+        }
+    }
+
+    /// A token to cancel the search from somewhere else than the task awaiting it.
+    pub fn cancel_token(&self) -> CancelToken {
+        CancelToken {
+            pdstate: self.pdstate.clone(),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancel_token().cancel();
+    }
+
+    /// The current state of the search.
+    pub fn state(&self) -> PearlDiverState {
+        self.pdstate.get()
+    }
+
+    /// The progress of the search so far.
+    pub fn stats(&self) -> PowStats {
+        self.progress.stats()
+    }
+}
+
+impl Future for SearchHandle {
+    type Output = PearlDiverState;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut outcome = self.outcome.lock().unwrap();
+
+        match outcome.state {
+            Some(state) => Poll::Ready(state),
+            None => {
+                outcome.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Cores, Difficulty, InputTrits, PowOutcome};
+
+    use common::constants::{HASH_TRIT_LEN, TRANSACTION_TRIT_LEN};
+
+    use std::time::{Duration, Instant};
+
+    fn input() -> InputTrits {
+        let mut trits = [0; TRANSACTION_TRIT_LEN];
+        for (i, trit) in trits.iter_mut().enumerate() {
+            *trit = (i % 3) as i8 - 1;
+        }
+        InputTrits(trits)
+    }
+
+    #[tokio::test]
+    async fn find_nonce() {
+        let handle =
+            PearlDiver::new(Cores::from(2), Difficulty::from(9)).search_async(input(), None);
+
+        match handle.await {
+            PearlDiverState::Completed(Some(nonce)) => {
+                assert!(PowOutcome::new(input(), &nonce).trailing_zeros >= 9)
+            }
+            _ => panic!("no nonce found"),
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_from_token() {
+        let handle = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN))
+            .search_async(input(), None);
+        let token = handle.cancel_token();
+
+        token.cancel();
+
+        assert!(handle.await == PearlDiverState::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn time_out() {
+        let handle = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN))
+            .search_async(input(), Some(Duration::from_millis(20)));
+
+        assert!(handle.await == PearlDiverState::TimedOut);
+    }
+
+    #[test]
+    fn time_out_at_deadline() {
+        let mut pearldiver = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN))
+            .with_deadline(Instant::now() + Duration::from_millis(20));
+
+        pearldiver.search_sync(&input());
+
+        assert!(pearldiver.state() == PearlDiverState::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn share_workers_between_searches() {
+        // A search that doesn't end on its own gets all workers
+        let endless = PearlDiver::new(Cores::default(), Difficulty::from(HASH_TRIT_LEN))
+            .search_async(input(), None);
+
+        let handle =
+            PearlDiver::new(Cores::from(1), Difficulty::from(3)).search_async(input(), None);
+        assert!(matches!(handle.await, PearlDiverState::Completed(Some(_))));
+
+        let handle = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN))
+            .search_async(input(), Some(Duration::from_millis(20)));
+        assert!(handle.await == PearlDiverState::TimedOut);
+
+        drop(endless);
+    }
+
+    #[test]
+    fn cancel_on_drop() {
+        let pearldiver = PearlDiver::new(Cores::from(1), Difficulty::from(HASH_TRIT_LEN));
+
+        drop(pearldiver.clone().search_async(input(), None));
+
+        assert!(pearldiver.state() == PearlDiverState::Cancelled);
+    }
+}
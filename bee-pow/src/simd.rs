@@ -0,0 +1,484 @@
+//! Curl transforms over several `Curl64State`s at once, in the 128-bit lanes of SSE2 or the 256-bit lanes of AVX2.
+//!
+//! A `WideState` interleaves the words of its states, so that one SIMD register holds the same trit of all of them.
+
+use crypto::curl::constants::CURL_HASH_TRIT_LEN as HASH_LEN;
+use crypto::curl::constants::CURL_STAT_TRIT_LEN as STATE_LEN;
+use crypto::hashes_preview::INDICES;
+
+use crate::constants::BITS_1;
+use crate::curl64::Curl64State;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// The instruction set used to transform the Curl states of a search.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Simd {
+    /// One `u64` per trit, 64 nonce candidates per transform.
+    Scalar,
+    /// One 128-bit register per trit, 128 nonce candidates per transform.
+    Sse2,
+    /// One 256-bit register per trit, 256 nonce candidates per transform.
+    Avx2,
+}
+
+impl Simd {
+    /// The best instruction set supported by the CPU.
+    pub fn detect() -> Self {
+        [Simd::Avx2, Simd::Sse2]
+            .iter()
+            .copied()
+            .find(|simd| simd.is_supported())
+            .unwrap_or(Simd::Scalar)
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self {
+            Simd::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Simd::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(target_arch = "x86_64")]
+            Simd::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => false,
+        }
+    }
+
+    /// The number of `Curl64State`s transformed at once.
+    pub fn lanes(self) -> usize {
+        match self {
+            Simd::Scalar => 1,
+            Simd::Sse2 => 2,
+            Simd::Avx2 => 4,
+        }
+    }
+}
+
+impl Default for Simd {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+/// The trits of `LANES` Curl states at the same index.
+pub(crate) trait Word: Copy + Send {
+    const LANES: usize;
+
+    fn load(words: &[u64]) -> Self;
+    fn store(self, words: &mut [u64]);
+
+    fn and(self, other: Self) -> Self;
+    fn or(self, other: Self) -> Self;
+    fn xor(self, other: Self) -> Self;
+    fn not(self) -> Self;
+
+    /// Transforms `pre` into the hash of its states, with the instruction set of the word.
+    ///
+    /// The caller has to make sure that the instruction set is supported by the CPU.
+    unsafe fn transform(pre: &mut WideState<Self>, tmp: &mut WideState<Self>, rounds: usize);
+}
+
+impl Word for u64 {
+    const LANES: usize = 1;
+
+    #[inline(always)]
+    fn load(words: &[u64]) -> Self {
+        words[0]
+    }
+
+    #[inline(always)]
+    fn store(self, words: &mut [u64]) {
+        words[0] = self;
+    }
+
+    #[inline(always)]
+    fn and(self, other: Self) -> Self {
+        self & other
+    }
+
+    #[inline(always)]
+    fn or(self, other: Self) -> Self {
+        self | other
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        self ^ other
+    }
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        !self
+    }
+
+    unsafe fn transform(pre: &mut WideState<Self>, tmp: &mut WideState<Self>, rounds: usize) {
+        transform(pre, tmp, rounds)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[derive(Clone, Copy)]
+pub(crate) struct Sse2(__m128i);
+
+// The registers are plain data
+#[cfg(target_arch = "x86_64")]
+unsafe impl Send for Sse2 {}
+
+#[cfg(target_arch = "x86_64")]
+impl Word for Sse2 {
+    const LANES: usize = 2;
+
+    #[inline(always)]
+    fn load(words: &[u64]) -> Self {
+        unsafe { Sse2(_mm_set_epi64x(words[1] as i64, words[0] as i64)) }
+    }
+
+    #[inline(always)]
+    fn store(self, words: &mut [u64]) {
+        unsafe { _mm_storeu_si128(words[..2].as_mut_ptr() as *mut __m128i, self.0) }
+    }
+
+    #[inline(always)]
+    fn and(self, other: Self) -> Self {
+        unsafe { Sse2(_mm_and_si128(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn or(self, other: Self) -> Self {
+        unsafe { Sse2(_mm_or_si128(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        unsafe { Sse2(_mm_xor_si128(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        unsafe { Sse2(_mm_xor_si128(self.0, _mm_set1_epi64x(-1))) }
+    }
+
+    #[target_feature(enable = "sse2")]
+    unsafe fn transform(pre: &mut WideState<Self>, tmp: &mut WideState<Self>, rounds: usize) {
+        transform(pre, tmp, rounds)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[derive(Clone, Copy)]
+pub(crate) struct Avx2(__m256i);
+
+// The registers are plain data
+#[cfg(target_arch = "x86_64")]
+unsafe impl Send for Avx2 {}
+
+#[cfg(target_arch = "x86_64")]
+impl Word for Avx2 {
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    fn load(words: &[u64]) -> Self {
+        unsafe {
+            Avx2(_mm256_set_epi64x(
+                words[3] as i64,
+                words[2] as i64,
+                words[1] as i64,
+                words[0] as i64,
+            ))
+        }
+    }
+
+    #[inline(always)]
+    fn store(self, words: &mut [u64]) {
+        unsafe { _mm256_storeu_si256(words[..4].as_mut_ptr() as *mut __m256i, self.0) }
+    }
+
+    #[inline(always)]
+    fn and(self, other: Self) -> Self {
+        unsafe { Avx2(_mm256_and_si256(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn or(self, other: Self) -> Self {
+        unsafe { Avx2(_mm256_or_si256(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        unsafe { Avx2(_mm256_xor_si256(self.0, other.0)) }
+    }
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        unsafe { Avx2(_mm256_xor_si256(self.0, _mm256_set1_epi64x(-1))) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn transform(pre: &mut WideState<Self>, tmp: &mut WideState<Self>, rounds: usize) {
+        transform(pre, tmp, rounds)
+    }
+}
+
+/// `W::LANES` Curl states, interleaved.
+pub(crate) struct WideState<W> {
+    hi: Vec<W>,
+    lo: Vec<W>,
+}
+
+impl<W: Word> WideState<W> {
+    pub fn new() -> Self {
+        let ones = W::load(&[BITS_1; 4]);
+
+        Self {
+            hi: vec![ones; STATE_LEN],
+            lo: vec![ones; STATE_LEN],
+        }
+    }
+
+    /// Interleaves `states`, one per lane.
+    pub fn load(&mut self, states: &[Curl64State]) {
+        debug_assert_eq!(states.len(), W::LANES);
+
+        let mut hi = [0; 4];
+        let mut lo = [0; 4];
+
+        for i in 0..STATE_LEN {
+            for (lane, state) in states.iter().enumerate() {
+                let (h, l) = state.get(i);
+                hi[lane] = h;
+                lo[lane] = l;
+            }
+            self.hi[i] = W::load(&hi);
+            self.lo[i] = W::load(&lo);
+        }
+    }
+
+    /// The bits of every lane that are set if the trits at `range` of the hash are all zero.
+    pub fn zero_trits(&self, range: std::ops::Range<usize>) -> [u64; 4] {
+        let mut zeros = W::load(&[BITS_1; 4]);
+        for i in range {
+            zeros = zeros.and(self.hi[i].xor(self.lo[i]).not());
+        }
+
+        let mut words = [0; 4];
+        zeros.store(&mut words);
+        words
+    }
+}
+
+/// The Curl transform of the search, in terms of words, that only computes the trits of the hash in its last round.
+#[inline(always)]
+fn transform<W: Word>(pre: &mut WideState<W>, tmp: &mut WideState<W>, rounds: usize) {
+    let (mut src, mut dst) = (pre, tmp);
+
+    for _ in 0..(rounds - 1) {
+        round(&src.hi, &src.lo, &mut dst.hi, &mut dst.lo, STATE_LEN);
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    // NOTE: Since we don't compute a new state after that, we stop after 'HASH_LEN'.
+    round(&src.hi, &src.lo, &mut dst.hi, &mut dst.lo, HASH_LEN);
+
+    // After an even number of swaps `src` is `pre`, which is where the hash belongs
+    if !rounds.is_multiple_of(2) {
+        src.hi[..HASH_LEN].copy_from_slice(&dst.hi[..HASH_LEN]);
+        src.lo[..HASH_LEN].copy_from_slice(&dst.lo[..HASH_LEN]);
+    }
+}
+
+#[inline(always)]
+fn round<W: Word>(hsrc: &[W], lsrc: &[W], hdst: &mut [W], ldst: &mut [W], len: usize) {
+    for (j, index) in INDICES[..=len].windows(2).enumerate() {
+        let alpha = lsrc[index[0]];
+        let kappa = hsrc[index[0]];
+        let sigma = lsrc[index[1]];
+        let gamma = hsrc[index[1]];
+
+        let delta = alpha.or(gamma.not()).and(sigma.xor(kappa));
+
+        ldst[j] = delta.not();
+        hdst[j] = alpha.xor(gamma).or(delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constants::BITS_0;
+    use crate::{Cores, Difficulty, InputTrits, PearlDiver};
+
+    use common::constants::TRANSACTION_TRIT_LEN;
+    use crypto::curl::constants::{CURL_P_27, CURL_P_81};
+    use crypto::hashes_preview::{CurlP, Sponge, Trits, TritsMut};
+
+    fn random_states(lanes: usize, seed: u64) -> Vec<Curl64State> {
+        // xorshift, good enough to fill the states with arbitrary bits
+        let mut x = seed;
+        let mut next = move || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+
+        (0..lanes)
+            .map(|_| {
+                let mut state = Curl64State::new(BITS_1);
+                for i in 0..STATE_LEN {
+                    state.set(i, next(), next());
+                }
+                state
+            })
+            .collect()
+    }
+
+    fn assert_equivalent<W: Word>() {
+        // An even number of rounds leaves the hash in the scratchpad first
+        for &rounds in [26, CURL_P_27, CURL_P_81].iter() {
+            for seed in 1..=4u64 {
+                let states = random_states(W::LANES, seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+                let mut wide = WideState::<W>::new();
+                let mut tmp = WideState::<W>::new();
+                wide.load(&states);
+                unsafe { W::transform(&mut wide, &mut tmp, rounds) };
+
+                for (lane, state) in states.iter().enumerate() {
+                    let mut expected = state.clone();
+                    let mut tmp = Curl64State::new(BITS_1);
+                    expected.transform_hash(&mut tmp, rounds);
+
+                    for i in 0..HASH_LEN {
+                        let (mut hi, mut lo) = ([0; 4], [0; 4]);
+                        wide.hi[i].store(&mut hi);
+                        wide.lo[i].store(&mut lo);
+
+                        assert_eq!(
+                            (hi[lane], lo[lane]),
+                            expected.get(i),
+                            "trit {} of lane {} after {} rounds",
+                            i,
+                            lane,
+                            rounds
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u64_transform_equals_curlp() {
+        // Every lane hashes different trits
+        let inputs = (0..64)
+            .map(|lane| {
+                (0..HASH_LEN)
+                    .map(|i| ((i * i + lane * i + lane) % 3) as i8 - 1)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for &rounds in [26, CURL_P_27, CURL_P_81].iter() {
+            let mut full = Curl64State::new(BITS_1);
+            for i in 0..HASH_LEN {
+                let (mut hi, mut lo) = (BITS_0, BITS_0);
+                for (lane, input) in inputs.iter().enumerate() {
+                    match input[i] {
+                        1 => hi |= 1 << lane,
+                        -1 => lo |= 1 << lane,
+                        _ => {
+                            hi |= 1 << lane;
+                            lo |= 1 << lane;
+                        }
+                    }
+                }
+                full.set(i, hi, lo);
+            }
+            let mut hash_only = full.clone();
+
+            let mut tmp = Curl64State::new(BITS_1);
+            full.transform(&mut tmp, rounds);
+            hash_only.transform_hash(&mut tmp, rounds);
+
+            for (lane, input) in inputs.iter().enumerate() {
+                let mut curlp = CurlP::new(rounds);
+                let mut expected = [0; HASH_LEN];
+                curlp.absorb(&Trits::from_i8_unchecked(input)).unwrap();
+                curlp
+                    .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut expected))
+                    .unwrap();
+
+                for state in [&full, &hash_only].iter() {
+                    let hash = (0..HASH_LEN)
+                        .map(|i| {
+                            let (hi, lo) = state.get(i);
+                            match ((hi >> lane) & 1, (lo >> lane) & 1) {
+                                (1, 0) => 1,
+                                (0, 1) => -1,
+                                (_, _) => 0,
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(
+                        &hash[..],
+                        &expected[..],
+                        "lane {} after {} rounds",
+                        lane,
+                        rounds
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detect_supported_simd() {
+        assert!(Simd::detect().is_supported());
+        assert!(Simd::Scalar.is_supported());
+    }
+
+    #[test]
+    fn scalar_transform_equals_u64_transform() {
+        assert_equivalent::<u64>();
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn sse2_transform_equals_u64_transform() {
+        if Simd::Sse2.is_supported() {
+            assert_equivalent::<Sse2>();
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn avx2_transform_equals_u64_transform() {
+        if Simd::Avx2.is_supported() {
+            assert_equivalent::<Avx2>();
+        }
+    }
+
+    #[test]
+    fn search_with_every_supported_simd() {
+        let mut trits = [0; TRANSACTION_TRIT_LEN];
+        for (i, trit) in trits.iter_mut().enumerate() {
+            *trit = (i % 3) as i8 - 1;
+        }
+
+        for &simd in [Simd::Scalar, Simd::Sse2, Simd::Avx2].iter() {
+            if !simd.is_supported() {
+                continue;
+            }
+
+            let outcome = PearlDiver::new(Cores::from(2), Difficulty::from(5))
+                .with_simd(simd)
+                .search_transaction(InputTrits(trits))
+                .unwrap();
+
+            assert!(outcome.trailing_zeros >= 5, "{:?}", simd);
+        }
+    }
+}
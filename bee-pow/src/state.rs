@@ -0,0 +1,148 @@
+use crate::nonce::NonceTrits;
+use crate::pearldiver::PearlDiverState;
+
+use std::hint::spin_loop;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+const CREATED: u8 = 0;
+const SEARCHING: u8 = 1;
+const CANCELLED: u8 = 2;
+const TIMED_OUT: u8 = 3;
+const COMPLETED: u8 = 4;
+/// The result of a completed search is being written, which readers wait for.
+const COMPLETING: u8 = 5;
+
+/// The state of a search, shared between its threads and everyone polling it.
+///
+/// The threads only load the atomic flag, so that polling it on every step doesn't contend. The nonce of a
+/// completed search is written once into its slot, before the flag tells that the search completed.
+pub(crate) struct SharedState {
+    flag: AtomicU8,
+    nonce: Mutex<Option<NonceTrits>>,
+}
+
+impl SharedState {
+    pub fn new(state: PearlDiverState) -> Self {
+        let shared = Self {
+            flag: AtomicU8::new(CREATED),
+            nonce: Mutex::new(None),
+        };
+        shared.set(state);
+        shared
+    }
+
+    pub fn get(&self) -> PearlDiverState {
+        loop {
+            return match self.flag.load(Ordering::Acquire) {
+                CREATED => PearlDiverState::Created,
+                SEARCHING => PearlDiverState::Searching,
+                CANCELLED => PearlDiverState::Cancelled,
+                TIMED_OUT => PearlDiverState::TimedOut,
+                COMPLETED => PearlDiverState::Completed(*self.nonce.lock().unwrap()),
+                _ => {
+                    spin_loop();
+                    continue;
+                }
+            };
+        }
+    }
+
+    /// Whether the search is still going on, cheap enough to be checked on every step.
+    pub fn is_searching(&self) -> bool {
+        self.flag.load(Ordering::Relaxed) == SEARCHING
+    }
+
+    /// Sets the state, whichever it was before.
+    pub fn set(&self, state: PearlDiverState) {
+        if let PearlDiverState::Completed(nonce) = state {
+            *self.nonce.lock().unwrap() = nonce;
+        }
+        self.flag.store(flag(state), Ordering::Release);
+    }
+
+    /// Switches from `from` to `to`, and returns whether the state was `from`.
+    ///
+    /// Of several threads switching from the same state at once, only one succeeds.
+    pub fn switch(&self, from: PearlDiverState, to: PearlDiverState) -> bool {
+        let nonce = match to {
+            PearlDiverState::Completed(nonce) => nonce,
+            _ => return self.compare_exchange(flag(from), flag(to)),
+        };
+
+        if !self.compare_exchange(flag(from), COMPLETING) {
+            return false;
+        }
+        *self.nonce.lock().unwrap() = nonce;
+        self.flag.store(COMPLETED, Ordering::Release);
+        true
+    }
+
+    fn compare_exchange(&self, from: u8, to: u8) -> bool {
+        self.flag
+            .compare_exchange(from, to, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+}
+
+impl Default for SharedState {
+    fn default() -> Self {
+        Self::new(PearlDiverState::Created)
+    }
+}
+
+fn flag(state: PearlDiverState) -> u8 {
+    match state {
+        PearlDiverState::Created => CREATED,
+        PearlDiverState::Searching => SEARCHING,
+        PearlDiverState::Cancelled => CANCELLED,
+        PearlDiverState::TimedOut => TIMED_OUT,
+        PearlDiverState::Completed(_) => COMPLETED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::constants::NONCE_TRIT_LEN;
+
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn switch_only_from_the_given_state() {
+        let state = SharedState::new(PearlDiverState::Searching);
+
+        assert!(!state.switch(PearlDiverState::Created, PearlDiverState::Cancelled));
+        assert!(state.switch(PearlDiverState::Searching, PearlDiverState::TimedOut));
+        assert!(state.get() == PearlDiverState::TimedOut);
+        assert!(!state.is_searching());
+    }
+
+    #[test]
+    fn complete_only_once() {
+        let state = Arc::new(SharedState::new(PearlDiverState::Searching));
+
+        let threads = (0..8)
+            .map(|i| {
+                let state = state.clone();
+                thread::spawn(move || {
+                    let nonce = NonceTrits([(i % 3) as i8 - 1; NONCE_TRIT_LEN]);
+                    state.switch(
+                        PearlDiverState::Searching,
+                        PearlDiverState::Completed(Some(nonce)),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        let winners = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .filter(|&won| won)
+            .count();
+
+        assert_eq!(winners, 1);
+        assert!(matches!(state.get(), PearlDiverState::Completed(Some(_))));
+    }
+}
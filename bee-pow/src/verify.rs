@@ -0,0 +1,112 @@
+use crypto::curl::constants::CURL_HASH_TRIT_LEN as HASH_LEN;
+
+use common::constants::TRANSACTION_TRIT_LEN as TRANS_LEN;
+
+use crate::constants::{BATCH_SIZE, BITS_0, BITS_1};
+use crate::curl64::Curl64State;
+use crate::difficulty::Difficulty;
+use crate::input::InputTrits;
+use crate::rounds::Rounds;
+
+/// Checks that the CurlP81 hash of `input` ends with at least `difficulty` zero trits.
+pub fn verify(input: &InputTrits, difficulty: &Difficulty) -> bool {
+    verify_batch(Some(input), difficulty)[0]
+}
+
+/// Checks the proof of work of many transactions, hashing up to 64 of them at once in the lanes of a
+/// `Curl64State`; the result has one entry per transaction, in order.
+pub fn verify_batch<'a, I>(inputs: I, difficulty: &Difficulty) -> Vec<bool>
+where
+    I: IntoIterator<Item = &'a InputTrits>,
+{
+    let inputs = inputs.into_iter().collect::<Vec<_>>();
+    let mut verified = Vec::with_capacity(inputs.len());
+
+    let mut tmp = Curl64State::new(BITS_1);
+    let rounds = *Rounds::default();
+
+    for batch in inputs.chunks(BATCH_SIZE) {
+        let mut state = Curl64State::new(BITS_1);
+
+        for offset in (0..TRANS_LEN).step_by(HASH_LEN) {
+            absorb(&mut state, batch, offset);
+
+            // The last chunk only needs the hash, the state is not absorbed into afterwards
+            if offset + HASH_LEN < TRANS_LEN {
+                state.transform(&mut tmp, rounds);
+            } else {
+                state.transform_hash(&mut tmp, rounds);
+            }
+        }
+
+        let zeros = ((HASH_LEN - **difficulty)..HASH_LEN)
+            .fold(BITS_1, |zeros, i| zeros & state.bit_equal(i));
+
+        verified.extend((0..batch.len()).map(|lane| (zeros >> lane) & 1 != 0));
+    }
+
+    verified
+}
+
+/// Writes the chunk of trits at `offset` of every transaction into its lane of the state.
+fn absorb(state: &mut Curl64State, batch: &[&InputTrits], offset: usize) {
+    for i in 0..HASH_LEN {
+        let (mut hi, mut lo) = (BITS_0, BITS_0);
+
+        for (lane, input) in batch.iter().enumerate() {
+            let (h, l) = match input[offset + i] {
+                1 => (1, 0),
+                -1 => (0, 1),
+                _ => (1, 1),
+            };
+            hi |= h << lane;
+            lo |= l << lane;
+        }
+
+        state.set(i, hi, lo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constants::TRANS_NONCE_START;
+    use crate::{Cores, PearlDiver};
+
+    fn input(seed: usize) -> InputTrits {
+        let mut trits = [0; TRANS_LEN];
+        for (i, trit) in trits[..TRANS_NONCE_START].iter_mut().enumerate() {
+            *trit = ((i * 7 + seed) % 3) as i8 - 1;
+        }
+        InputTrits(trits)
+    }
+
+    #[test]
+    fn verify_found_nonce() {
+        let difficulty = Difficulty::from(6);
+        let outcome = PearlDiver::new(Cores::from(2), difficulty.clone())
+            .search_transaction(input(0))
+            .unwrap();
+
+        assert!(verify(&outcome.transaction, &difficulty));
+        assert!(!verify(
+            &outcome.transaction,
+            &Difficulty::from(outcome.trailing_zeros + 1)
+        ));
+    }
+
+    #[test]
+    fn verify_batch_like_scalar_hash() {
+        let inputs = (0..BATCH_SIZE + 3).map(input).collect::<Vec<_>>();
+        let difficulty = Difficulty::from(1);
+
+        let expected = inputs
+            .iter()
+            .map(|input| crate::outcome::hash(input, Rounds::default())[HASH_LEN - 1] == 0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(verify_batch(&inputs, &difficulty), expected);
+        assert!(verify_batch(&[], &difficulty).is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+use super::seed::Seed;
+use super::wots::{WotsError, WotsPrivateKeyGenerator, WotsPrivateKeyGeneratorBuilder};
+use super::{PrivateKey, PrivateKeyGenerator, PublicKey};
+use crypto::hashes_preview::{Kerl, Sponge, Trits, TritsMut};
+use iota_constants::{
+    ADDRESS_CHECKSUM_TRYTES_SIZE, ADDRESS_WITH_CHECKSUM_TRYTES_SIZE, HASH_TRINARY_SIZE,
+    HASH_TRYTES_SIZE,
+};
+// TODO Remove when available in bee
+use iota_conversion::Trinary;
+use ternary::TryteStr;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::thread;
+
+/// The errors of the address checksum functions.
+#[derive(Debug, PartialEq)]
+pub enum AddressError {
+    /// An address has `HASH_TRYTES_SIZE` trytes, or `ADDRESS_WITH_CHECKSUM_TRYTES_SIZE` with its checksum.
+    InvalidLength(usize),
+    /// A character of the address isn't a tryte.
+    InvalidTryte(char),
+    /// The checksum of the address doesn't match the address.
+    InvalidChecksum,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::InvalidLength(len) => write!(
+                f,
+                "an address has {} trytes, or {} with its checksum, not {}",
+                HASH_TRYTES_SIZE, ADDRESS_WITH_CHECKSUM_TRYTES_SIZE, len
+            ),
+            AddressError::InvalidTryte(c) => write!(f, "{:?} is not a tryte", c),
+            AddressError::InvalidChecksum => write!(f, "invalid address checksum"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Returns the WOTS address of `seed` at `index` and `security_level`, without checksum.
+///
+/// # Example
+///
+/// ```
+/// use crypto::hashes_preview::Kerl;
+/// use signing::address::{address, add_checksum};
+/// use signing::seed::Seed;
+///
+/// let seed = Seed::new();
+/// let address = address::<Kerl>(&seed, 0, 1).unwrap();
+/// let address_with_checksum = add_checksum(&address).unwrap();
+/// ```
+pub fn address<S: Sponge + Default>(
+    seed: &Seed,
+    index: u64,
+    security_level: u8,
+) -> Result<String, WotsError> {
+    let generator = WotsPrivateKeyGeneratorBuilder::<S>::default()
+        .security_level(security_level)
+        .build()?;
+
+    Ok(generate_address(&generator, seed, index))
+}
+
+fn generate_address<S: Sponge + Default>(
+    generator: &WotsPrivateKeyGenerator<S>,
+    seed: &Seed,
+    index: u64,
+) -> String {
+    let public_key = generator.generate(seed, index).generate_public_key();
+
+    // Can't fail, the public key is the trits of a hash
+    public_key.to_bytes().trytes().unwrap()
+}
+
+/// Returns `address` followed by its checksum, the last `ADDRESS_CHECKSUM_TRYTES_SIZE` trytes of its Kerl hash.
+pub fn add_checksum(address: &str) -> Result<String, AddressError> {
+    let address = check_trytes(address, HASH_TRYTES_SIZE)?.as_str();
+
+    Ok(format!("{}{}", address, checksum(address)))
+}
+
+/// Checks that the last `ADDRESS_CHECKSUM_TRYTES_SIZE` trytes of `address` are the checksum of the others.
+pub fn validate_checksum(address: &str) -> Result<(), AddressError> {
+    let address = check_trytes(address, ADDRESS_WITH_CHECKSUM_TRYTES_SIZE)?.as_str();
+
+    if checksum(&address[..HASH_TRYTES_SIZE]) == address[HASH_TRYTES_SIZE..] {
+        Ok(())
+    } else {
+        Err(AddressError::InvalidChecksum)
+    }
+}
+
+/// Returns `address` without its checksum, after validating it.
+pub fn remove_checksum(address: &str) -> Result<&str, AddressError> {
+    validate_checksum(address)?;
+
+    Ok(&address[..HASH_TRYTES_SIZE])
+}
+
+/// Checks that `address` is made of `len` trytes.
+fn check_trytes(address: &str, len: usize) -> Result<&TryteStr, AddressError> {
+    let trytes = TryteStr::try_from_str(address).map_err(|e| match e {
+        ternary::Error::InvalidTryteAt { character, .. } => AddressError::InvalidTryte(character),
+        _ => unreachable!("`TryteStr::try_from_str` only fails on invalid trytes"),
+    })?;
+
+    // Trytes are ASCII, so there are as many trytes as bytes
+    if trytes.len() != len {
+        return Err(AddressError::InvalidLength(trytes.len()));
+    }
+
+    Ok(trytes)
+}
+
+fn checksum(address: &str) -> String {
+    let mut kerl = Kerl::default();
+    let mut hash = [0; HASH_TRINARY_SIZE];
+
+    // Can't fail, the address is exactly one hash long
+    kerl.digest_into(
+        &Trits::from_i8_unchecked(&address.trits()),
+        &mut TritsMut::from_i8_unchecked(&mut hash),
+    )
+    .unwrap();
+
+    // Can't fail, the hash is made of trits
+    let hash = hash.trytes().unwrap();
+    hash[HASH_TRYTES_SIZE - ADDRESS_CHECKSUM_TRYTES_SIZE..].to_string()
+}
+
+/// An iterator over the addresses of a seed at consecutive indexes, with their index.
+///
+/// The addresses are generated in batches, one address per thread.
+///
+/// # Example
+///
+/// ```
+/// use crypto::hashes_preview::Kerl;
+/// use signing::address::Addresses;
+/// use signing::seed::Seed;
+///
+/// let seed = Seed::new();
+/// let addresses = Addresses::<Kerl>::new(&seed, 0, 1).unwrap().threads(2);
+///
+/// for (index, address) in addresses.take(2) {
+///     println!("{} {}", index, address);
+/// }
+/// ```
+pub struct Addresses<'a, S> {
+    seed: &'a Seed,
+    generator: WotsPrivateKeyGenerator<S>,
+    index: u64,
+    threads: usize,
+    batch: VecDeque<(u64, String)>,
+}
+
+impl<'a, S: Sponge + Default + Send> Addresses<'a, S> {
+    /// Starts at `index`, with one thread per core.
+    pub fn new(seed: &'a Seed, index: u64, security_level: u8) -> Result<Self, WotsError> {
+        let generator = WotsPrivateKeyGeneratorBuilder::<S>::default()
+            .security_level(security_level)
+            .build()?;
+
+        Ok(Self {
+            seed,
+            generator,
+            index,
+            threads: num_cpus::get(),
+            batch: VecDeque::new(),
+        })
+    }
+
+    /// Sets the number of threads, and thus of addresses per batch, to at least one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    fn generate_batch(&mut self) {
+        let end = self.index.saturating_add(self.threads as u64);
+        let generator = self.generator;
+        let seed = self.seed;
+
+        self.batch = thread::scope(|scope| {
+            let handles = (self.index..end)
+                .map(|index| {
+                    scope.spawn(move || (index, generate_address(&generator, seed, index)))
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("address generation panicked"))
+                .collect()
+        });
+        self.index = end;
+    }
+}
+
+impl<S: Sponge + Default + Send> Iterator for Addresses<'_, S> {
+    type Item = (u64, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch.is_empty() {
+            self.generate_batch();
+        }
+
+        self.batch.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str =
+        "NNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNN";
+
+    fn assert_kerl_addresses(addresses: &[(u64, u8, &str)]) {
+        let seed = Seed::from_bytes(&SEED.trits()).unwrap();
+
+        for (index, security_level, expected) in addresses.iter() {
+            let address = add_checksum(&address::<Kerl>(&seed, *index, *security_level).unwrap());
+            assert_eq!(address.unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn address_kerl_test() {
+        assert_kerl_addresses(&[(
+            0,
+            1,
+            "EZCPFKWUFUPNNTLPOSQUF9ARBWOXTACYDEWGOHGNZWPMOBMRHYTXOYBISQASOUCUIBHEJAKVBVEVIPFNYORTBBBZRD",
+        )]);
+    }
+
+    #[test]
+    fn address_kerl_security_levels_test() {
+        assert_kerl_addresses(&[
+            (1, 1, "RGXSOSXNPSECZJZBOWSPQV9FEHAECO9PLVR9DRFCOZOUYT9OZMZOGIPIIIXARBWOHAYUDFFUFTROAUBNBWBPSYHBTZ"),
+            (0, 2, "MDWYEJJHJDIUVPKDY9EACGDJUOP9TLYDWETUBOYCBLYXYYYJYUXYUTCTPTDGJYFKMQMCNZDQPTBE9AFIWBMRBZYYEY"),
+            (1, 2, "BRCRVAASDLAZPTSHELUSJGNEWQSCLY9WHEARHXSJBQFNSMTES9OQULMXNNLWSZDE9K9HOWQHPMTVNHEMDZIGLVOVWB"),
+            (0, 3, "UDVBLCHJDIPXJZKKCNVLRLMCPHXKGZQGURCE9M9RQLWPBTSFSVFBJWSMNEUEMLHGOWS9ZXFAWFDXWEEBWRQFKKMCMB"),
+        ]);
+    }
+
+    #[test]
+    fn address_invalid_security_level_test() {
+        let seed = Seed::from_bytes(&SEED.trits()).unwrap();
+
+        assert_eq!(
+            address::<Kerl>(&seed, 0, 4).unwrap_err(),
+            WotsError::InvalidSecurityLevel(4)
+        );
+    }
+
+    #[test]
+    fn address_checksum_test() {
+        let address = &SEED[..HASH_TRYTES_SIZE];
+        let address_with_checksum = add_checksum(address).unwrap();
+
+        assert_eq!(
+            address_with_checksum.len(),
+            ADDRESS_WITH_CHECKSUM_TRYTES_SIZE
+        );
+        assert_eq!(validate_checksum(&address_with_checksum), Ok(()));
+        assert_eq!(remove_checksum(&address_with_checksum), Ok(address));
+
+        let mut invalid = address_with_checksum.clone();
+        invalid.replace_range(0..1, "M");
+        assert_eq!(
+            validate_checksum(&invalid),
+            Err(AddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            remove_checksum(address),
+            Err(AddressError::InvalidLength(81))
+        );
+        assert_eq!(
+            add_checksum(&address_with_checksum),
+            Err(AddressError::InvalidLength(90))
+        );
+        assert_eq!(add_checksum("N-"), Err(AddressError::InvalidTryte('-')));
+
+        // 90 bytes, but not 90 trytes
+        let non_ascii = format!("{}Ł{}", "A".repeat(80), "A".repeat(8));
+        assert_eq!(
+            validate_checksum(&non_ascii),
+            Err(AddressError::InvalidTryte('Ł'))
+        );
+        assert_eq!(
+            remove_checksum(&non_ascii),
+            Err(AddressError::InvalidTryte('Ł'))
+        );
+    }
+
+    #[test]
+    fn addresses_in_order_test() {
+        let seed = Seed::from_bytes(&SEED.trits()).unwrap();
+        let expected = (3..6)
+            .map(|index| (index, address::<Kerl>(&seed, index, 1).unwrap()))
+            .collect::<Vec<_>>();
+
+        let addresses = Addresses::<Kerl>::new(&seed, 3, 1)
+            .unwrap()
+            .threads(2)
+            .take(3)
+            .collect::<Vec<_>>();
+
+        assert_eq!(addresses, expected);
+    }
+}
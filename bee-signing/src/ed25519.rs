@@ -1,6 +1,7 @@
 use super::seed::Seed;
 use super::{PrivateKey, PrivateKeyGenerator, PublicKey, Signature};
-use rand::rngs::OsRng;
+use crypto::hashes_preview::Kerl;
+use sha2::{Digest, Sha512};
 
 #[derive(Default)]
 pub struct Ed25519PrivateKeyGeneratorBuilder {}
@@ -12,11 +13,11 @@ pub struct Ed25519PrivateKey {
 }
 
 pub struct Ed25519PublicKey {
-    key: ed25519_dalek::PublicKey,
+    state: Vec<i8>,
 }
 
 pub struct Ed25519Signature {
-    signature: ed25519_dalek::Signature,
+    state: Vec<i8>,
 }
 
 impl Ed25519PrivateKeyGeneratorBuilder {
@@ -28,11 +29,19 @@ impl Ed25519PrivateKeyGeneratorBuilder {
 impl PrivateKeyGenerator for Ed25519PrivateKeyGenerator {
     type PrivateKey = Ed25519PrivateKey;
 
+    /// The secret key is the first 32 bytes of the SHA-512 of the trits of the Kerl subseed of `seed` at `index`.
     fn generate(&self, seed: &Seed, index: u64) -> Self::PrivateKey {
-        let mut csprng = OsRng {};
-        let keypair: ed25519_dalek::Keypair = ed25519_dalek::Keypair::generate(&mut csprng);
-
-        Ed25519PrivateKey { keypair: keypair }
+        let subseed = seed.subseed::<Kerl>(index);
+        let hash = Sha512::digest(as_u8_slice(subseed.to_bytes()));
+        // Can't fail, the slice is exactly `SECRET_KEY_LENGTH` bytes long
+        let secret =
+            ed25519_dalek::SecretKey::from_bytes(&hash[..ed25519_dalek::SECRET_KEY_LENGTH])
+                .unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+
+        Ed25519PrivateKey {
+            keypair: ed25519_dalek::Keypair { secret, public },
+        }
     }
 }
 
@@ -42,17 +51,16 @@ impl PrivateKey for Ed25519PrivateKey {
 
     fn generate_public_key(&self) -> Self::PublicKey {
         Ed25519PublicKey {
-            key: self.keypair.public,
+            state: as_i8_vec(self.keypair.public.as_bytes()),
         }
     }
 
     // TODO: hash ? enforce size ?
     fn sign(&mut self, message: &[i8]) -> Self::Signature {
-        let test = unsafe { &*(message as *const _ as *const [u8]) };
-        let signature = self.keypair.sign(test);
+        let signature = self.keypair.sign(as_u8_slice(message));
 
         Ed25519Signature {
-            signature: signature,
+            state: as_i8_vec(&signature.to_bytes()),
         }
     }
 }
@@ -60,61 +68,54 @@ impl PrivateKey for Ed25519PrivateKey {
 impl PublicKey for Ed25519PublicKey {
     type Signature = Ed25519Signature;
 
+    /// Returns false if either the key or the signature isn't valid Ed25519.
     fn verify(&self, message: &[i8], signature: &Self::Signature) -> bool {
-        let test = unsafe { &*(message as *const _ as *const [u8]) };
+        let key = ed25519_dalek::PublicKey::from_bytes(as_u8_slice(&self.state));
+        let signature = ed25519_dalek::Signature::from_bytes(as_u8_slice(&signature.state));
 
-        self.key.verify(test, &signature.signature).is_ok()
+        match (key, signature) {
+            (Ok(key), Ok(signature)) => key.verify(as_u8_slice(message), &signature).is_ok(),
+            _ => false,
+        }
     }
 
+    /// The bytes are only checked when verifying.
     fn from_bytes(bytes: &[i8]) -> Self {
         Self {
-            key: ed25519_dalek::PublicKey::default(),
+            state: bytes.to_vec(),
         }
     }
 
     fn to_bytes(&self) -> &[i8] {
-        // &self.state
-        &[]
+        &self.state
     }
 }
 
-// impl Ed25519Signature {
-//     pub fn new(state: &[i8]) -> Ed25519Signature {
-//         Ed25519Signature{
-//             state: state.to_vec(),
-//         }
-//     }
-// }
-
-// TODO default impl ?
 impl Signature for Ed25519Signature {
     fn size(&self) -> usize {
-        // self.state.len()
-        42
+        self.state.len()
     }
 
+    /// The bytes are only checked when verifying.
     fn from_bytes(bytes: &[i8]) -> Self {
-        let test = unsafe { &*(bytes as *const _ as *const [u8]) };
         Self {
-            signature: ed25519_dalek::Signature::from_bytes(test).unwrap(),
+            state: bytes.to_vec(),
         }
     }
 
     fn to_bytes(&self) -> &[i8] {
-        &[]
+        &self.state
     }
 }
 
-// impl RecoverableSignature for Ed25519Signature {
-//     type PublicKey = Ed25519PublicKey;
-//
-//     fn recover_public_key(&self, message: &[i8]) -> Self::PublicKey {
-//
-//         Ed25519PublicKey{
-//             state: hash.to_vec(),
-//         }
-//     }
-// }
+fn as_u8_slice(bytes: &[i8]) -> &[u8] {
+    // Safe, `i8` and `u8` have the same size and alignment
+    unsafe { &*(bytes as *const [i8] as *const [u8]) }
+}
+
+fn as_i8_vec(bytes: &[u8]) -> Vec<i8> {
+    bytes.iter().map(|&byte| byte as i8).collect()
+}
 
 #[cfg(test)]
 mod tests {
@@ -146,6 +147,71 @@ mod tests {
             assert!(valid);
             valid = public_key.verify(seed_trits_2, &signature_good);
             assert!(!valid);
+            valid = public_key.verify(seed_trits_1, &signature_bad);
+            assert!(!valid);
         }
     }
+
+    fn hex(bytes: &[i8]) -> String {
+        bytes
+            .iter()
+            .map(|&byte| format!("{:02x}", byte as u8))
+            .collect()
+    }
+
+    // Cross-checked against another Ed25519 implementation, given the same Kerl subseeds.
+    #[test]
+    fn ed25519_deterministic_test() {
+        let vectors = [
+            (
+                "86e5090805b5a9302de677b63411cf7a1095791cf4085f95ec629daafb63e65e",
+                "6c4973a46275ce1f90769f6e42f32e901d77c788988dff07e9c93582c404c05f6811eb83dc0aaf4a9164c52daba1112a6ac8b1d6d893b8e002bb8af4bccc9608",
+            ),
+            (
+                "37779255fb1768995391278e51dfa7af61e9fe68efa56bb2ba57c12c736a614c",
+                "0cf1fde0f56fa26f93ac9d4273e22ad5b105a37dbdd3a19f19078e8e4fd47793d25c976d0c577afbd8042f938ed7ead21cb759b543535259e553aa8cee19960e",
+            ),
+            (
+                "4f55636bc36e1fdda0bc38c31f38a61660dd0bc04969f1e55f6984e4216348a5",
+                "9d0b5aa7f55040342e74b103c91ce1e0950b4aa3ce792533ede683a84a5eb7b8214a3f7c46171d700618196ea7806446b881a86c6f689b44f7b02fe9eaa5ad0f",
+            ),
+        ];
+        let seed = Seed::from_bytes(&SEED1.trits()).unwrap();
+        let private_key_generator = Ed25519PrivateKeyGeneratorBuilder::default().build();
+
+        for (index, (public_key, signature)) in vectors.iter().enumerate() {
+            let mut private_key = private_key_generator.generate(&seed, index as u64);
+
+            assert_eq!(
+                hex(private_key.generate_public_key().to_bytes()),
+                *public_key
+            );
+            assert_eq!(
+                hex(private_key.sign(&MESSAGE.trits()).to_bytes()),
+                *signature
+            );
+        }
+    }
+
+    #[test]
+    fn ed25519_from_bytes_to_bytes_test() {
+        let seed = Seed::from_bytes(&SEED1.trits()).unwrap();
+        let message = MESSAGE.trits();
+        let mut private_key = Ed25519PrivateKeyGeneratorBuilder::default()
+            .build()
+            .generate(&seed, 0);
+        let public_key = Ed25519PublicKey::from_bytes(private_key.generate_public_key().to_bytes());
+        let signature = Ed25519Signature::from_bytes(private_key.sign(&message).to_bytes());
+
+        assert_eq!(public_key.to_bytes().len(), 32);
+        assert_eq!(signature.size(), 64);
+        assert!(public_key.verify(&message, &signature));
+
+        // Invalid bytes don't verify rather than panic
+        assert!(!public_key.verify(
+            &message,
+            &Ed25519Signature::from_bytes(&signature.to_bytes()[1..])
+        ));
+        assert!(!Ed25519PublicKey::from_bytes(&[0; 31]).verify(&message, &signature));
+    }
 }
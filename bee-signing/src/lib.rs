@@ -1,3 +1,4 @@
+pub mod address;
 pub mod ed25519;
 pub mod mss;
 pub mod seed;
@@ -20,7 +21,7 @@ pub trait PrivateKeyGenerator {
     /// # Example
     ///
     /// ```
-    /// use iota_crypto::Kerl;
+    /// use crypto::hashes_preview::Kerl;
     /// use signing::PrivateKeyGenerator;
     /// use signing::seed::Seed;
     /// use signing::wots::WotsPrivateKeyGeneratorBuilder;
@@ -44,7 +45,7 @@ pub trait PrivateKey {
     /// # Example
     ///
     /// ```
-    /// # use iota_crypto::Kerl;
+    /// # use crypto::hashes_preview::Kerl;
     /// # use signing::PrivateKeyGenerator;
     /// # use signing::seed::Seed;
     /// # use signing::wots::WotsPrivateKeyGeneratorBuilder;
@@ -66,7 +67,7 @@ pub trait PrivateKey {
     /// # Example
     ///
     /// ```
-    /// # use iota_crypto::Kerl;
+    /// # use crypto::hashes_preview::Kerl;
     /// # use signing::PrivateKeyGenerator;
     /// # use signing::seed::Seed;
     /// # use signing::wots::WotsPrivateKeyGeneratorBuilder;
@@ -2,7 +2,7 @@ use super::seed::Seed;
 use super::{
     slice_eq, PrivateKey, PrivateKeyGenerator, PublicKey, RecoverableSignature, Signature,
 };
-use iota_crypto::Sponge;
+use crypto::hashes_preview::{Sponge, Trits, TritsMut};
 use std::marker::PhantomData;
 
 #[derive(Default)]
@@ -116,13 +116,19 @@ where
                 let left_index = index * 2 + 1;
                 let right_index = left_index + 1;
                 sponge
-                    .absorb(&tree[left_index * 243..(left_index + 1) * 243])
+                    .absorb(&Trits::from_i8_unchecked(
+                        &tree[left_index * 243..(left_index + 1) * 243],
+                    ))
                     .unwrap();
                 sponge
-                    .absorb(&tree[right_index * 243..(right_index + 1) * 243])
+                    .absorb(&Trits::from_i8_unchecked(
+                        &tree[right_index * 243..(right_index + 1) * 243],
+                    ))
                     .unwrap();
                 sponge
-                    .squeeze(&mut tree[index * 243..(index + 1) * 243])
+                    .squeeze_into(&mut TritsMut::from_i8_unchecked(
+                        &mut tree[index * 243..(index + 1) * 243],
+                    ))
                     .unwrap();
                 sponge.reset();
             }
@@ -225,13 +231,15 @@ where
             }
 
             if signature.index & j != 0 {
-                sponge.absorb(sibling).unwrap();
-                sponge.absorb(&hash).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(sibling)).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(&hash)).unwrap();
             } else {
-                sponge.absorb(&hash).unwrap();
-                sponge.absorb(sibling).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(&hash)).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(sibling)).unwrap();
             }
-            sponge.squeeze(&mut hash).unwrap();
+            sponge
+                .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+                .unwrap();
             sponge.reset();
 
             j <<= 1;
@@ -287,8 +295,8 @@ mod tests {
         WotsPrivateKeyGenerator, WotsPrivateKeyGeneratorBuilder, WotsPublicKey,
     };
     use super::*;
+    use crypto::hashes_preview::{CurlP81, Kerl};
     use iota_conversion::Trinary;
-    use iota_crypto::{Curl, Kerl};
 
     #[test]
     fn mss_generator_missing_depth_test() {
@@ -459,11 +467,13 @@ mod tests {
     #[test]
     fn mss_wots_curl_curl_test() {
         for s in 1..4 {
-            let wots_private_key_generator = WotsPrivateKeyGeneratorBuilder::<Curl>::default()
+            let wots_private_key_generator = WotsPrivateKeyGeneratorBuilder::<CurlP81>::default()
                 .security_level(s)
                 .build()
                 .unwrap();
-            mss_generic_gen_test::<Curl, WotsPrivateKeyGenerator<Curl>>(wots_private_key_generator);
+            mss_generic_gen_test::<CurlP81, WotsPrivateKeyGenerator<CurlP81>>(
+                wots_private_key_generator,
+            );
         }
     }
 
@@ -474,17 +484,21 @@ mod tests {
                 .security_level(s)
                 .build()
                 .unwrap();
-            mss_generic_gen_test::<Curl, WotsPrivateKeyGenerator<Kerl>>(wots_private_key_generator);
+            mss_generic_gen_test::<CurlP81, WotsPrivateKeyGenerator<Kerl>>(
+                wots_private_key_generator,
+            );
         }
     }
     #[test]
     fn mss_wots_kerl_curl_test() {
         for s in 1..4 {
-            let wots_private_key_generator = WotsPrivateKeyGeneratorBuilder::<Curl>::default()
+            let wots_private_key_generator = WotsPrivateKeyGeneratorBuilder::<CurlP81>::default()
                 .security_level(s)
                 .build()
                 .unwrap();
-            mss_generic_gen_test::<Kerl, WotsPrivateKeyGenerator<Curl>>(wots_private_key_generator);
+            mss_generic_gen_test::<Kerl, WotsPrivateKeyGenerator<CurlP81>>(
+                wots_private_key_generator,
+            );
         }
     }
 }
@@ -1,5 +1,4 @@
-// TODO Replace with bee impl when available
-use iota_crypto::Sponge;
+use crypto::hashes_preview::{Sponge, Trits, TritsMut};
 // TODO Remove when available in bee
 use iota_conversion::Trinary;
 use rand::Rng;
@@ -53,8 +52,10 @@ impl Seed {
             }
         }
 
-        sponge.absorb(&subseed).unwrap();
-        sponge.squeeze(&mut subseed).unwrap();
+        sponge.absorb(&Trits::from_i8_unchecked(&subseed)).unwrap();
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut subseed))
+            .unwrap();
         sponge.reset();
 
         Self::from_bytes_unchecked(&subseed)
@@ -96,8 +97,7 @@ mod tests {
     use super::*;
     // TODO super::super ?
     use super::super::slice_eq;
-    // TODO Remove when available in bee
-    use iota_crypto::{Curl, Kerl};
+    use crypto::hashes_preview::{CurlP27, CurlP81, Kerl};
 
     const SEED: &str =
         "ABCDEFGHIJKLMNOPQRSTUVWXYZ9ABCDEFGHIJKLMNOPQRSTUVWXYZ9ABCDEFGHIJKLMNOPQRSTUVWXYZ9";
@@ -125,7 +125,7 @@ mod tests {
 
     #[test]
     fn seed_subseed_curl81_test() {
-        seed_subseed_generic_test::<Curl>(
+        seed_subseed_generic_test::<CurlP81>(
             SEED,
             &[
                 "PKKJZREHPYHNIBWAPYEXHXEAFZCI99UWZNKBOCCECFTDUXG9YGYDAGRLUBJVKMYNWPRCPYENACHOYSHJO",
@@ -142,25 +142,24 @@ mod tests {
         );
     }
 
-    // TODO Will be activated when Curl27 is a proper type
-    // #[test]
-    // fn seed_subseed_curl27_test() {
-    //     seed_subseed_generic_test::<Curl>(
-    //         SEED,
-    //         &[
-    //             "ITTFAEIWTRSFQGZGLGUMLUTHFXYSCLXTFYMGVTTDSNNWFUCKBRPSOBERNLXIYCNCEBKUV9QIXI9BDCKSM",
-    //             "W9YWLOQQJMENWCDBLBKYBNJJDGFKFBGYEBSIBPKUAGNIV9TJWRRAQPAEKBLIYVLGHPIIDYQYP9QNSPFTY",
-    //             "X9WMLHFSJYEWNLVSGTVGWMAPNUSFMXQPTMCPUML9RCMAJQVUYMTJJHKT9HO9NSNGAEMKGDBHE9KZNMBPZ",
-    //             "YNTUYQNJWJPK99YE9NOMGNKF9YRBJX9EH9UZWLMISXQRQLLZRKHFOPTW9PIERIPXK9ZDUPLSLZOEFUWXF",
-    //             "URBRFVWBAGHM9WTWSZZLRBMNGMNNRJRBGBLDEBBSZTGMWELW9JHXFSFNLRKPI9MLYELEZEDYIPKGE9CRO",
-    //             "XMGTGBZBINHC9ZPKRBHZFLUP9CEWULNCMVUAVVUXRDHU9OILDOORKPLRIWZQDNRFGSWMJAVYZWGDXMZNW",
-    //             "KFEGWPGWLAHWQXGCHKHDDVAZEISLYMGQLRRZBCJWXWKK9JIJKHXRDV9NMYIFTAGKXU9GLACAQUCXBLMH9",
-    //             "BMUAOOZBHPUOVHRWPX9KWUCZSXWXWPMKOMGNAZOXLDMAHBBVMDLXQ9IVPOPIOFPWHZSMRKBOBLCUEVUXX",
-    //             "GLVXLLOFYERJWBECYRXVPCFXK9GUDCHBEZYMTPMUDOYEQCIAPCAACKSOL9ADEGSTBQRIBJIWTCJYVUIRW",
-    //             "FOPHLVKCYHZLLCCOUWBPMQQAWHVRBGJBKQGPQXOTOEWTOCVZQCJXDCBLG9SEZBUVYPIIRTTP9CJPXWKKW",
-    //         ],
-    //     );
-    // }
+    #[test]
+    fn seed_subseed_curl27_test() {
+        seed_subseed_generic_test::<CurlP27>(
+            SEED,
+            &[
+                "ITTFAEIWTRSFQGZGLGUMLUTHFXYSCLXTFYMGVTTDSNNWFUCKBRPSOBERNLXIYCNCEBKUV9QIXI9BDCKSM",
+                "W9YWLOQQJMENWCDBLBKYBNJJDGFKFBGYEBSIBPKUAGNIV9TJWRRAQPAEKBLIYVLGHPIIDYQYP9QNSPFTY",
+                "X9WMLHFSJYEWNLVSGTVGWMAPNUSFMXQPTMCPUML9RCMAJQVUYMTJJHKT9HO9NSNGAEMKGDBHE9KZNMBPZ",
+                "YNTUYQNJWJPK99YE9NOMGNKF9YRBJX9EH9UZWLMISXQRQLLZRKHFOPTW9PIERIPXK9ZDUPLSLZOEFUWXF",
+                "URBRFVWBAGHM9WTWSZZLRBMNGMNNRJRBGBLDEBBSZTGMWELW9JHXFSFNLRKPI9MLYELEZEDYIPKGE9CRO",
+                "XMGTGBZBINHC9ZPKRBHZFLUP9CEWULNCMVUAVVUXRDHU9OILDOORKPLRIWZQDNRFGSWMJAVYZWGDXMZNW",
+                "KFEGWPGWLAHWQXGCHKHDDVAZEISLYMGQLRRZBCJWXWKK9JIJKHXRDV9NMYIFTAGKXU9GLACAQUCXBLMH9",
+                "BMUAOOZBHPUOVHRWPX9KWUCZSXWXWPMKOMGNAZOXLDMAHBBVMDLXQ9IVPOPIOFPWHZSMRKBOBLCUEVUXX",
+                "GLVXLLOFYERJWBECYRXVPCFXK9GUDCHBEZYMTPMUDOYEQCIAPCAACKSOL9ADEGSTBQRIBJIWTCJYVUIRW",
+                "FOPHLVKCYHZLLCCOUWBPMQQAWHVRBGJBKQGPQXOTOEWTOCVZQCJXDCBLG9SEZBUVYPIIRTTP9CJPXWKKW",
+            ],
+        );
+    }
 
     #[test]
     fn seed_subseed_kerl_test() {
@@ -2,7 +2,7 @@ use super::Seed;
 use super::{
     slice_eq, PrivateKey, PrivateKeyGenerator, PublicKey, RecoverableSignature, Signature,
 };
-use iota_crypto::Sponge;
+use crypto::hashes_preview::{Sponge, Trits, TritsMut};
 use std::marker::PhantomData;
 
 // TODO state as Vec<i8> ?
@@ -14,12 +14,21 @@ pub struct WotsPrivateKeyGeneratorBuilder<S> {
     _sponge: PhantomData<S>,
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default)]
 pub struct WotsPrivateKeyGenerator<S> {
     security_level: u8,
     _sponge: PhantomData<S>,
 }
 
+// Not derived, the generator is `Copy` whether the sponge is or not
+impl<S> Clone for WotsPrivateKeyGenerator<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for WotsPrivateKeyGenerator<S> {}
+
 pub struct WotsPrivateKey<S> {
     state: Vec<i8>,
     _sponge: PhantomData<S>,
@@ -72,9 +81,13 @@ impl<S: Sponge + Default> PrivateKeyGenerator for WotsPrivateKeyGenerator<S> {
         let mut sponge = S::default();
         let mut state = vec![0; self.security_level as usize * 6561];
 
-        sponge.absorb(&subseed.to_bytes()).unwrap();
         sponge
-            .squeeze(&mut state[0..self.security_level as usize * 6561])
+            .absorb(&Trits::from_i8_unchecked(subseed.to_bytes()))
+            .unwrap();
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(
+                &mut state[0..self.security_level as usize * 6561],
+            ))
             .unwrap();
         sponge.reset();
 
@@ -97,22 +110,28 @@ impl<S: Sponge + Default> PrivateKey for WotsPrivateKey<S> {
 
         for chunk in hashed_private_key.chunks_mut(243) {
             for _ in 0..26 {
-                sponge.absorb(chunk).unwrap();
-                sponge.squeeze(chunk).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(chunk)).unwrap();
+                sponge
+                    .squeeze_into(&mut TritsMut::from_i8_unchecked(chunk))
+                    .unwrap();
                 sponge.reset();
             }
         }
 
         for (i, chunk) in hashed_private_key.chunks(6561).enumerate() {
-            sponge.absorb(chunk).unwrap();
+            sponge.absorb(&Trits::from_i8_unchecked(chunk)).unwrap();
             sponge
-                .squeeze(&mut digests[i * 243..(i + 1) * 243])
+                .squeeze_into(&mut TritsMut::from_i8_unchecked(
+                    &mut digests[i * 243..(i + 1) * 243],
+                ))
                 .unwrap();
             sponge.reset();
         }
 
-        sponge.absorb(&digests).unwrap();
-        sponge.squeeze(&mut hash).unwrap();
+        sponge.absorb(&Trits::from_i8_unchecked(&digests)).unwrap();
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+            .unwrap();
         sponge.reset();
 
         Self::PublicKey {
@@ -130,8 +149,10 @@ impl<S: Sponge + Default> PrivateKey for WotsPrivateKey<S> {
             let val = message[i * 3] + message[i * 3 + 1] * 3 + message[i * 3 + 2] * 9;
 
             for _ in 0..(13 - val) {
-                sponge.absorb(chunk).unwrap();
-                sponge.squeeze(chunk).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(chunk)).unwrap();
+                sponge
+                    .squeeze_into(&mut TritsMut::from_i8_unchecked(chunk))
+                    .unwrap();
                 sponge.reset();
             }
         }
@@ -196,22 +217,28 @@ impl<S: Sponge + Default> RecoverableSignature for WotsSignature<S> {
             let val = message[i * 3] + message[i * 3 + 1] * 3 + message[i * 3 + 2] * 9;
 
             for _ in 0..(val - -13) {
-                sponge.absorb(chunk).unwrap();
-                sponge.squeeze(chunk).unwrap();
+                sponge.absorb(&Trits::from_i8_unchecked(chunk)).unwrap();
+                sponge
+                    .squeeze_into(&mut TritsMut::from_i8_unchecked(chunk))
+                    .unwrap();
                 sponge.reset();
             }
         }
 
         for (i, chunk) in state.chunks_mut(6561).enumerate() {
-            sponge.absorb(&chunk).unwrap();
+            sponge.absorb(&Trits::from_i8_unchecked(chunk)).unwrap();
             sponge
-                .squeeze(&mut digests[i * 243..(i + 1) * 243])
+                .squeeze_into(&mut TritsMut::from_i8_unchecked(
+                    &mut digests[i * 243..(i + 1) * 243],
+                ))
                 .unwrap();
             sponge.reset();
         }
 
-        sponge.absorb(&digests).unwrap();
-        sponge.squeeze(&mut hash).unwrap();
+        sponge.absorb(&Trits::from_i8_unchecked(&digests)).unwrap();
+        sponge
+            .squeeze_into(&mut TritsMut::from_i8_unchecked(&mut hash))
+            .unwrap();
         sponge.reset();
 
         Self::PublicKey {
@@ -221,12 +248,57 @@ impl<S: Sponge + Default> RecoverableSignature for WotsSignature<S> {
     }
 }
 
+/// The number of trytes of a hash normalized together, one per chunk of a signature fragment.
+const NORMALIZED_FRAGMENT_LEN: usize = 27;
+
+/// Normalizes the trits of a hash, like a bundle hash, before it is signed or a signature of it is verified.
+///
+/// The tryte values of each third of the hash are shifted until they sum to `0`, so that a signature reveals as many
+/// hashes of the private key as it keeps secret.
+pub fn normalize_hash(hash: &[i8]) -> Vec<i8> {
+    let mut values = hash
+        .chunks(3)
+        .map(|trits| trits[0] + trits[1] * 3 + trits[2] * 9)
+        .collect::<Vec<i8>>();
+
+    for fragment in values.chunks_mut(NORMALIZED_FRAGMENT_LEN) {
+        let mut sum = fragment.iter().map(|&value| value as i32).sum::<i32>();
+
+        while sum > 0 {
+            // Can't fail, a positive sum has a value above the minimum
+            let value = fragment.iter_mut().find(|value| **value > -13).unwrap();
+            *value -= 1;
+            sum -= 1;
+        }
+
+        while sum < 0 {
+            // Can't fail, a negative sum has a value below the maximum
+            let value = fragment.iter_mut().find(|value| **value < 13).unwrap();
+            *value += 1;
+            sum += 1;
+        }
+    }
+
+    values
+        .iter()
+        .flat_map(|&value| {
+            let mut trits = [0; 3];
+            let mut value = value;
+            for trit in trits.iter_mut() {
+                *trit = (value + 1).rem_euclid(3) - 1;
+                value = (value - *trit) / 3;
+            }
+            trits.to_vec()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crypto::hashes_preview::{CurlP81, Kerl, Troika, ROUND_CONSTANT_LEN, TROIKA_ROUNDS};
     use iota_conversion::Trinary;
-    use iota_crypto::{Curl, Kerl};
 
     const SEED: &str =
         "NNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNN";
@@ -273,11 +345,32 @@ mod tests {
         }
     }
 
-    fn wots_generic_complete_test<S: Sponge + Default>() {
+    #[test]
+    fn normalize_hash_test() {
+        let hash = "M".repeat(81).trits();
+        let normalized = normalize_hash(&hash);
+
+        assert_eq!(
+            normalized.trytes().unwrap(),
+            "NNNNNNNNNNNNN9MMMMMMMMMMMMM".repeat(3)
+        );
+        assert_eq!(normalize_hash(&normalized), normalized);
+
+        let normalized = normalize_hash(&MESSAGE.trits());
+        for fragment in normalized.chunks(3 * NORMALIZED_FRAGMENT_LEN) {
+            let sum = fragment
+                .chunks(3)
+                .map(|trits| (trits[0] + trits[1] * 3 + trits[2] * 9) as i32)
+                .sum::<i32>();
+            assert_eq!(sum, 0);
+        }
+    }
+
+    fn wots_generic_complete_test<S: Sponge + Default>(indexes: u64) {
         let seed = Seed::from_bytes(&SEED.trits()).unwrap();
 
         for security in 1..4 {
-            for index in 0..5 {
+            for index in 0..indexes {
                 let private_key_generator = WotsPrivateKeyGeneratorBuilder::<S>::default()
                     .security_level(security)
                     .build()
@@ -300,10 +393,53 @@ mod tests {
 
     #[test]
     fn wots_kerl_complete_test() {
-        wots_generic_complete_test::<Kerl>();
+        wots_generic_complete_test::<Kerl>(5);
     }
     #[test]
     fn wots_curl_complete_test() {
-        wots_generic_complete_test::<Curl>();
+        wots_generic_complete_test::<CurlP81>(5);
+    }
+
+    /// `Troika` with arbitrary round constants, NOT the reference ones, which signs and verifies all the same.
+    struct TestTroika(Troika);
+
+    impl Default for TestTroika {
+        fn default() -> Self {
+            let round_constants = (0..TROIKA_ROUNDS)
+                .map(|round| {
+                    let mut constant = [0; ROUND_CONSTANT_LEN];
+                    for (i, trit) in constant.iter_mut().enumerate() {
+                        *trit = ((i * i + round * i + round) % 3) as u8;
+                    }
+                    constant
+                })
+                .collect::<Vec<_>>();
+
+            TestTroika(Troika::new(TROIKA_ROUNDS, &round_constants).unwrap())
+        }
+    }
+
+    impl Sponge for TestTroika {
+        const HASH_LEN: usize = Troika::HASH_LEN;
+
+        type Error = <Troika as Sponge>::Error;
+
+        fn absorb(&mut self, input: &Trits) -> Result<(), Self::Error> {
+            self.0.absorb(input)
+        }
+
+        fn reset(&mut self) {
+            self.0.reset()
+        }
+
+        fn squeeze_into(&mut self, buf: &mut TritsMut) -> Result<(), Self::Error> {
+            self.0.squeeze_into(buf)
+        }
+    }
+
+    // A single key per security level, `Troika` is slow.
+    #[test]
+    fn wots_troika_complete_test() {
+        wots_generic_complete_test::<TestTroika>(1);
     }
 }
@@ -0,0 +1,532 @@
+use log::warn;
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The name of the file the records are persisted to, relative to the storage path.
+const RECORDS_FILE: &str = "records.dat";
+
+type Records = HashMap<Vec<u8>, Vec<u8>>;
+
+/// A key and its value, `None` if the key was removed.
+type Record = (Vec<u8>, Option<Vec<u8>>);
+
+/// The length prefix of a value that marks its key as removed.
+const TOMBSTONE: u32 = u32::MAX;
+
+/// A simple key-value store that keeps its records in memory and persists them to disk when flushed.
+///
+/// Records are written as a sequence of length-prefixed (little-endian `u32`) keys and values. Flushing appends the
+/// changed records to the file, and a removed key is appended with a `TOMBSTONE` value; superseded records are
+/// compacted away the next time the storage is opened.
+pub struct Storage {
+    path: PathBuf,
+    records: Records,
+    size: usize,
+    /// The keys changed since the last flush, with whether they were on disk before their first change.
+    changes: HashMap<Vec<u8>, bool>,
+}
+
+/// Changes taken from a `Storage`, ready to be appended to its records file.
+///
+/// Appending doesn't need the `Storage` itself, so it can be done without holding a lock on it. Changes must be
+/// appended in the order they were taken, and changes that couldn't be appended are given back with
+/// `Storage::restore_changes`.
+pub struct Changes {
+    path: PathBuf,
+    bytes: Vec<u8>,
+    /// The changed keys, with whether they were on disk before their first change.
+    keys: Vec<(Vec<u8>, bool)>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Append the changes to the records file and sync it.
+    ///
+    /// If appending fails, the file is truncated back to its previous records, so that the changes can be appended
+    /// again later.
+    pub fn append(&self) -> io::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let len = file.metadata()?.len();
+
+        file.write_all(&self.bytes)
+            .and_then(|()| file.sync_data())
+            .inspect_err(|_| {
+                // Should this fail as well, the cut-off record is truncated the next time the storage is opened
+                let _ = file.set_len(len);
+            })
+    }
+}
+
+impl Storage {
+    /// Open the storage located at the directory `path`, creating it if it doesn't exist yet.
+    ///
+    /// A record cut off by a crash in the middle of a flush is dropped, and the file truncated back to the records
+    /// before it.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        fs::create_dir_all(&path)?;
+
+        let (records, entries) = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path.join(RECORDS_FILE))
+        {
+            Ok(file) => {
+                let file_len = file.metadata()?.len();
+                let (records, entries, len) = read_records(BufReader::new(&file))?;
+
+                if len < file_len {
+                    warn!(
+                        path:% = path.display(), dropped_bytes = file_len - len;
+                        "truncating a record cut off by an interrupted flush"
+                    );
+                    file.set_len(len)?;
+                    file.sync_all()?;
+                }
+
+                (records, entries)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (HashMap::new(), 0),
+            Err(e) => return Err(e),
+        };
+
+        if entries != records.len() {
+            compact(&path, &records)?;
+        }
+
+        let size = records.iter().map(|(k, v)| record_size(k, v)).sum();
+
+        Ok(Self {
+            path,
+            records,
+            size,
+            changes: HashMap::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The number of bytes the records take up on disk once flushed and compacted.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.records.contains_key(key)
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.records.get(key).map(Vec::as_slice)
+    }
+
+    /// Insert a record, replacing and returning the previous value stored under `key`.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        self.track_change(key);
+        self.size += record_size(key, value);
+
+        let previous = self.records.insert(key.to_vec(), value.to_vec());
+        if let Some(previous) = &previous {
+            self.size -= record_size(key, previous);
+        }
+
+        previous
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        if self.records.contains_key(key) {
+            self.track_change(key);
+        }
+
+        let removed = self.records.remove(key);
+
+        if let Some(removed) = &removed {
+            self.size -= record_size(key, removed);
+        }
+
+        removed
+    }
+
+    /// Iterate over all records in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.records
+            .iter()
+            .map(|(k, v)| (k.as_slice(), v.as_slice()))
+    }
+
+    /// Whether there are changes that haven't been flushed to disk yet.
+    pub fn is_dirty(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Take the changes that haven't been flushed to disk yet, to append them with `Changes::append`.
+    ///
+    /// Keys inserted and removed again since the last flush are left out.
+    pub fn take_changes(&mut self) -> Changes {
+        let mut bytes = Vec::new();
+        let mut keys = Vec::with_capacity(self.changes.len());
+
+        for (key, on_disk) in self.changes.drain() {
+            match self.records.get(&key) {
+                Some(value) => write_record(&mut bytes, &key, value),
+                None if on_disk => write_tombstone(&mut bytes, &key),
+                None => (),
+            }
+            keys.push((key, on_disk));
+        }
+
+        Changes {
+            path: self.path.join(RECORDS_FILE),
+            bytes,
+            keys,
+        }
+    }
+
+    /// Give back changes that couldn't be appended, so that they are part of the next flush.
+    ///
+    /// Keys changed again in the meantime are flushed with their latest value.
+    pub fn restore_changes(&mut self, changes: Changes) {
+        for (key, on_disk) in changes.keys {
+            // The restored changes are older, so they know whether the key is on disk
+            self.changes.insert(key, on_disk);
+        }
+    }
+
+    /// Append the changes that haven't been flushed yet to disk.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let changes = self.take_changes();
+
+        changes
+            .append()
+            .inspect_err(|_| self.restore_changes(changes))
+    }
+
+    fn track_change(&mut self, key: &[u8]) {
+        if !self.changes.contains_key(key) {
+            let on_disk = self.records.contains_key(key);
+            self.changes.insert(key.to_vec(), on_disk);
+        }
+    }
+}
+
+/// Rewrite the records file with only the live `records`.
+///
+/// The records are first written to a temporary file which then replaces the previous one, so that an interrupted
+/// compaction never leaves a truncated records file behind.
+fn compact(path: &Path, records: &Records) -> io::Result<()> {
+    let tmp_path = path.join(format!("{}.tmp", RECORDS_FILE));
+
+    {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        for (key, value) in records {
+            write_chunk(&mut writer, key)?;
+            write_chunk(&mut writer, value)?;
+        }
+        writer.into_inner()?.sync_all()?;
+    }
+
+    fs::rename(tmp_path, path.join(RECORDS_FILE))
+}
+
+/// The size of a record on disk, including its length prefixes.
+fn record_size(key: &[u8], value: &[u8]) -> usize {
+    2 * std::mem::size_of::<u32>() + key.len() + value.len()
+}
+
+/// Read the records of a file, the number of entries they were read from, including superseded ones, and the number
+/// of bytes they take up.
+///
+/// A truncated last record ends the records, and isn't part of the bytes read.
+fn read_records(mut reader: impl Read) -> io::Result<(Records, usize, u64)> {
+    let mut records = HashMap::new();
+    let mut entries = 0;
+    let mut len = 0;
+
+    loop {
+        match read_record(&mut reader) {
+            Ok(Some((key, value))) => {
+                entries += 1;
+                len += (2 * std::mem::size_of::<u32>() + key.len()) as u64;
+
+                match value {
+                    Some(value) => {
+                        len += value.len() as u64;
+                        records.insert(key, value);
+                    }
+                    None => {
+                        records.remove(&key);
+                    }
+                }
+            }
+            Ok(None) => break,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok((records, entries, len))
+}
+
+/// Read a record, or `None` at the end of the reader.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Record>> {
+    let key = match read_len(reader, true)? {
+        Some(len) => read_bytes(reader, len)?,
+        None => return Ok(None),
+    };
+
+    // Can't be `None`, the end of the file isn't allowed within a record
+    let value = match read_len(reader, false)?.unwrap() {
+        TOMBSTONE => None,
+        len => Some(read_bytes(reader, len)?),
+    };
+
+    Ok(Some((key, value)))
+}
+
+/// Read a length prefix, or `None` if `at_boundary` and the reader is at its end.
+///
+/// Any other end of the reader means the file was truncated within a record.
+fn read_len(reader: &mut impl Read, at_boundary: bool) -> io::Result<Option<u32>> {
+    let mut len = [0u8; 4];
+    let mut read = 0;
+
+    while read < len.len() {
+        match reader.read(&mut len[read..]) {
+            Ok(0) if read == 0 && at_boundary => return Ok(None),
+            Ok(0) => return Err(truncated()),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(Some(u32::from_le_bytes(len)))
+}
+
+fn read_bytes(reader: &mut impl Read, len: u32) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    // Not allocated upfront, the length of a truncated record may be garbage
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() < len as usize {
+        return Err(truncated());
+    }
+
+    Ok(bytes)
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "truncated record")
+}
+
+fn write_record(bytes: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    // Can't fail, writing to a `Vec`
+    write_chunk(bytes, key).unwrap();
+    write_chunk(bytes, value).unwrap();
+}
+
+fn write_tombstone(bytes: &mut Vec<u8>, key: &[u8]) {
+    // Can't fail, writing to a `Vec`
+    write_chunk(bytes, key).unwrap();
+    bytes.extend_from_slice(&TOMBSTONE.to_le_bytes());
+}
+
+fn write_chunk(writer: &mut impl Write, chunk: &[u8]) -> io::Result<()> {
+    writer.write_all(&(chunk.len() as u32).to_le_bytes())?;
+    writer.write_all(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bee-storage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn flush_and_reopen() {
+        let dir = tmp_dir("reopen");
+
+        {
+            let mut storage = Storage::open(&dir).unwrap();
+            assert!(storage.is_empty());
+
+            storage.insert(b"hash1", b"trytes1");
+            storage.insert(b"hash2", b"");
+            storage.insert(b"hash3", b"trytes3");
+            storage.remove(b"hash3");
+            assert!(storage.is_dirty());
+
+            storage.flush().unwrap();
+            assert!(!storage.is_dirty());
+
+            let file_size = fs::metadata(dir.join(RECORDS_FILE)).unwrap().len();
+            assert_eq!(storage.size() as u64, file_size);
+        }
+
+        let storage = Storage::open(&dir).unwrap();
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage.get(b"hash1"), Some(&b"trytes1"[..]));
+        assert_eq!(storage.get(b"hash2"), Some(&b""[..]));
+        assert!(!storage.contains(b"hash3"));
+        assert_eq!(storage.size(), 2 * 8 + 5 + 7 + 5);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stop_at_truncated_records() {
+        let mut records = Vec::new();
+        write_chunk(&mut records, b"key").unwrap();
+
+        let (read, entries, len) = read_records(&records[..]).unwrap();
+        assert!(read.is_empty());
+        assert_eq!((entries, len), (0, 0));
+
+        // A partial length prefix isn't the end of a record either
+        write_chunk(&mut records, b"value").unwrap();
+        records.extend_from_slice(&[1, 0]);
+        let (read, entries, len) = read_records(&records[..]).unwrap();
+        assert_eq!(read.get(&b"key"[..]), Some(&b"value".to_vec()));
+        assert_eq!((entries, len), (1, 8 + 3 + 5));
+
+        // Nor is a value shorter than its length prefix
+        records.truncate(len as usize);
+        write_chunk(&mut records, b"key").unwrap();
+        records.extend_from_slice(&u32::MAX.wrapping_sub(1).to_le_bytes());
+        records.extend_from_slice(b"val");
+        assert_eq!(read_records(&records[..]).unwrap().2, len);
+    }
+
+    #[test]
+    fn restore_failed_changes() {
+        let dir = tmp_dir("restore");
+
+        {
+            let mut storage = Storage::open(&dir).unwrap();
+            storage.insert(b"hash1", b"trytes1");
+            storage.insert(b"hash2", b"trytes2");
+            storage.flush().unwrap();
+
+            storage.remove(b"hash1");
+            storage.insert(b"hash3", b"trytes3");
+            let changes = storage.take_changes();
+
+            // Changed again before the failed changes are given back
+            storage.insert(b"hash1", b"trytes4");
+            storage.remove(b"hash3");
+            storage.restore_changes(changes);
+            assert!(storage.is_dirty());
+
+            storage.flush().unwrap();
+            assert!(!storage.is_dirty());
+        }
+
+        let storage = Storage::open(&dir).unwrap();
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage.get(b"hash1"), Some(&b"trytes4"[..]));
+        assert!(!storage.contains(b"hash3"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn truncate_interrupted_flush() {
+        let dir = tmp_dir("interrupted");
+
+        {
+            let mut storage = Storage::open(&dir).unwrap();
+            storage.insert(b"hash1", b"trytes1");
+            storage.flush().unwrap();
+        }
+
+        // A crash in the middle of appending a record
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.join(RECORDS_FILE))
+            .unwrap();
+        write_chunk(&mut file, b"hash2").unwrap();
+        file.write_all(&7u32.to_le_bytes()).unwrap();
+        file.write_all(b"tryt").unwrap();
+        drop(file);
+
+        {
+            let mut storage = Storage::open(&dir).unwrap();
+            assert_eq!(storage.len(), 1);
+            assert!(!storage.contains(b"hash2"));
+
+            let file_size = fs::metadata(dir.join(RECORDS_FILE)).unwrap().len();
+            assert_eq!(storage.size() as u64, file_size);
+
+            // Appending after the truncation doesn't leave the cut-off bytes in between
+            storage.insert(b"hash2", b"trytes2");
+            storage.flush().unwrap();
+        }
+
+        let storage = Storage::open(&dir).unwrap();
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage.get(b"hash2"), Some(&b"trytes2"[..]));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn append_changes_and_compact() {
+        let dir = tmp_dir("append");
+
+        {
+            let mut storage = Storage::open(&dir).unwrap();
+            storage.insert(b"hash1", b"trytes1");
+            storage.insert(b"hash2", b"trytes2");
+            storage.flush().unwrap();
+
+            storage.insert(b"hash1", b"trytes3");
+            storage.remove(b"hash2");
+            let changes = storage.take_changes();
+            assert!(!storage.is_dirty());
+            changes.append().unwrap();
+
+            let file_size = fs::metadata(dir.join(RECORDS_FILE)).unwrap().len() as usize;
+            assert_eq!(file_size, 2 * (8 + 5 + 7) + (8 + 5 + 7) + (8 + 5));
+        }
+
+        let storage = Storage::open(&dir).unwrap();
+        assert_eq!(storage.len(), 1);
+        assert_eq!(storage.get(b"hash1"), Some(&b"trytes3"[..]));
+        assert!(!storage.contains(b"hash2"));
+
+        let file_size = fs::metadata(dir.join(RECORDS_FILE)).unwrap().len();
+        assert_eq!(storage.size() as u64, file_size);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
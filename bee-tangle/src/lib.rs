@@ -1,16 +1,54 @@
+use log::trace;
+
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
-    rc::Rc,
+    sync::Arc,
 };
 
-/// A transaction hash. To be replaced later with whatever implementation is required.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub struct TxHash(u64);
+/// The length of a transaction hash in trytes.
+pub const TX_HASH_LEN: usize = 81;
+
+/// A transaction hash, stored as its tryte (`9`, `A`-`Z`) representation.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TxHash([u8; TX_HASH_LEN]);
 
 impl TxHash {
+    /// Create a transaction hash from its trytes, returning `None` if they are not exactly `TX_HASH_LEN` valid
+    /// trytes.
+    pub fn from_trytes(trytes: &str) -> Option<Self> {
+        let bytes = trytes.as_bytes();
+
+        let is_tryte = |b: &u8| *b == b'9' || b.is_ascii_uppercase();
+
+        if bytes.len() != TX_HASH_LEN || !bytes.iter().all(is_tryte) {
+            return None;
+        }
+
+        let mut hash = [0; TX_HASH_LEN];
+        hash.copy_from_slice(bytes);
+
+        Some(Self(hash))
+    }
+
+    /// The hash of the genesis transaction (all `9`s), which is considered solid by definition.
+    pub fn genesis() -> Self {
+        Self([b'9'; TX_HASH_LEN])
+    }
+
     pub fn is_genesis(&self) -> bool {
-        unimplemented!()
+        *self == Self::genesis()
+    }
+
+    pub fn as_trytes(&self) -> &str {
+        // Can't fail, the hash only ever contains ASCII trytes
+        std::str::from_utf8(&self.0).unwrap()
+    }
+}
+
+impl std::fmt::Debug for TxHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_trytes())
     }
 }
 
@@ -22,6 +60,14 @@ pub struct Tx {
 }
 
 impl Tx {
+    pub fn new(trunk: TxHash, branch: TxHash) -> Self {
+        Self {
+            trunk,
+            branch,
+            body: (),
+        }
+    }
+
     pub fn trunk_hash(&self) -> TxHash {
         self.trunk
     }
@@ -79,7 +125,8 @@ impl Deref for Vertex {
 pub struct Tangle {
     vertices: HashMap<TxHash, Vertex>,
     txs_to_approvers: HashMap<TxHash, Vec<TxHash>>,
-    missing_to_approvers: HashMap<TxHash, Vec<Rc<TxHash>>>,
+    missing_to_approvers: HashMap<TxHash, Vec<Arc<TxHash>>>,
+    solid_len: usize,
 }
 
 impl Tangle {
@@ -91,6 +138,62 @@ impl Tangle {
         self.vertices.contains_key(&hash)
     }
 
+    /// The number of vertices in the Tangle.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// The number of solid vertices in the Tangle.
+    pub fn solid_len(&self) -> usize {
+        self.solid_len
+    }
+
+    /// The number of transactions that are approved by vertices of the Tangle, but are not part of it yet.
+    pub fn missing_len(&self) -> usize {
+        self.missing_to_approvers.len()
+    }
+
+    /// The hashes of the transactions directly approving the transaction with the given hash.
+    pub fn approvers(&self, hash: TxHash) -> &[TxHash] {
+        self.txs_to_approvers
+            .get(&hash)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// The hashes of all vertices that are not approved by any other vertex yet.
+    pub fn tips(&self) -> Vec<TxHash> {
+        self.vertices
+            .keys()
+            .filter(|hash| !self.txs_to_approvers.contains_key(hash))
+            .copied()
+            .collect()
+    }
+
+    /// Whether the vertex `approver` directly or indirectly approves the transaction `approvee`.
+    pub fn approves(&self, approver: TxHash, approvee: TxHash) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![approver];
+
+        while let Some(current) = stack.pop() {
+            if current == approvee {
+                return true;
+            }
+
+            if visited.insert(current) {
+                if let Some(vertex) = self.vertices.get(&current) {
+                    stack.extend_from_slice(&vertex.approvee_hashes());
+                }
+            }
+        }
+
+        false
+    }
+
     /// Get an immutable handle to the transaction with the given hash.
     pub fn get(&self, hash: TxHash) -> Option<VertexRef> {
         if self.contains(hash) {
@@ -132,17 +235,17 @@ impl Tangle {
             if new_approvees
                 // Do any of the new vertex's approvees...
                 .iter()
-                // ...not exist yet?
-                .any(|approvee| !self.contains(*approvee))
+                // ...not exist yet? The genesis is never inserted, but always known.
+                .any(|approvee| !approvee.is_genesis() && !self.contains(*approvee))
             {
-                let new_rc = Rc::new(new_hash);
+                let new_rc = Arc::new(new_hash);
                 // For each approvee of the inserted vertex...
                 let vertices = &self.vertices;
                 let missing_to_approvers = &mut self.missing_to_approvers;
                 new_approvees
                     .iter()
                     // ...check to see whether it's missing from the tangle...
-                    .filter(|approvee| !vertices.contains_key(*approvee))
+                    .filter(|approvee| !approvee.is_genesis() && !vertices.contains_key(*approvee))
                     // ...and remember that visiting it is work we need to do later...
                     .for_each(|approvee| {
                         missing_to_approvers
@@ -151,6 +254,9 @@ impl Tangle {
                             // ...by associating it with the missing approvee.
                             .push(new_rc.clone())
                     });
+            } else {
+                // All approvees are known, so the new vertex may be solid already
+                self.try_solidify(new_hash);
             }
 
             // Attempt to propagate solidification information based on the new
@@ -160,7 +266,7 @@ impl Tangle {
                 .remove(&new_hash)
                 .into_iter()
                 .flatten()
-                .filter_map(|hash| Rc::try_unwrap(hash).ok())
+                .filter_map(|hash| Arc::try_unwrap(hash).ok())
                 .for_each(|hash| self.try_solidify(hash));
         }
 
@@ -173,6 +279,7 @@ impl Tangle {
         // Prevent borrow errors by borrowing the fields independently
         let vertices = &mut self.vertices;
         let txs_to_approvers = &self.txs_to_approvers;
+        let solid_len = &mut self.solid_len;
 
         // The algorithm is recursive, but we don't want to use the stack
         let mut stack = vec![root];
@@ -195,6 +302,8 @@ impl Tangle {
                         .get_mut(&current_vert)
                         .unwrap() // Can't fail
                         .set_solid();
+                    *solid_len += 1;
+                    trace!(hash = current_vert.as_trytes(); "vertex solidified");
 
                     // Now, propagate this information to the approvers of the current root by
                     // running the algorithm again for each of them
@@ -287,6 +396,42 @@ impl<'a> VertexRefMut<'a> {
 mod tests {
     use super::*;
 
+    fn hash(c: char) -> TxHash {
+        TxHash::from_trytes(&c.to_string().repeat(TX_HASH_LEN)).unwrap()
+    }
+
+    #[test]
+    fn solidify_out_of_order() {
+        let mut tangle = Tangle::new();
+        let genesis = TxHash::genesis();
+
+        tangle.insert(Vertex::new(hash('C'), Tx::new(hash('B'), hash('A'))));
+        tangle.insert(Vertex::new(hash('B'), Tx::new(hash('A'), genesis)));
+        assert!(!tangle.get(hash('C')).unwrap().is_solid());
+        assert_eq!(tangle.missing_len(), 1);
+        assert_eq!(tangle.solid_len(), 0);
+
+        tangle.insert(Vertex::new(hash('A'), Tx::new(genesis, genesis)));
+        assert!(tangle.get(hash('A')).unwrap().is_solid());
+        assert!(tangle.get(hash('B')).unwrap().is_solid());
+        assert!(tangle.get(hash('C')).unwrap().is_solid());
+
+        assert_eq!(tangle.len(), 3);
+        assert_eq!(tangle.solid_len(), 3);
+        assert_eq!(tangle.missing_len(), 0);
+        assert_eq!(tangle.tips(), vec![hash('C')]);
+        assert_eq!(tangle.approvers(hash('A')).len(), 2);
+        assert!(tangle.approves(hash('C'), hash('A')));
+        assert!(!tangle.approves(hash('A'), hash('C')));
+    }
+
+    #[test]
+    fn reject_invalid_hash() {
+        assert!(TxHash::from_trytes("ABC").is_none());
+        assert!(TxHash::from_trytes(&"a".repeat(TX_HASH_LEN)).is_none());
+        assert!(TxHash::genesis().is_genesis());
+    }
+
     /*
     #[test]
     fn mutate() {
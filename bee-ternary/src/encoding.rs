@@ -0,0 +1,82 @@
+use crate::Trit;
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The powers of 3 that the trits of a byte are weighted with, the least significant first.
+const WEIGHTS: [i32; 5] = [1, 3, 9, 27, 81];
+
+/// How trits are packed into bytes.
+///
+/// Each byte holds the balanced value of `TRITS_PER_BYTE` trits, the least significant trit first, so that the
+/// encodings only differ in how many trits share a byte. A last byte that isn't full has its unused trits zeroed.
+pub trait Encoding: Clone + Copy + Debug + Default + Eq + Hash {
+    /// The number of trits that are packed into one byte.
+    const TRITS_PER_BYTE: usize;
+    /// The highest value of a byte, which is the value of that many `Trit::PlusOne`s.
+    const MAX_BYTE: i8;
+
+    /// The number of bytes that hold `len` trits.
+    fn bytes_for(len: usize) -> usize {
+        len.div_ceil(Self::TRITS_PER_BYTE)
+    }
+
+    fn is_valid(byte: i8) -> bool {
+        (-Self::MAX_BYTE..=Self::MAX_BYTE).contains(&byte)
+    }
+
+    /// The trit at `position` of a valid byte.
+    fn get(byte: i8, position: usize) -> Trit {
+        let mut value = i32::from(byte);
+        for _ in 0..position {
+            value = (value - i32::from(i8::from(Trit::balanced_remainder(value)))) / 3;
+        }
+        Trit::balanced_remainder(value)
+    }
+
+    /// Replaces the trit at `position` of a valid byte.
+    fn set(byte: &mut i8, position: usize, trit: Trit) {
+        let old = i8::from(Self::get(*byte, position));
+        let delta = i32::from(i8::from(trit) - old) * WEIGHTS[position];
+        *byte = (i32::from(*byte) + delta) as i8;
+    }
+}
+
+/// One trit per byte, which is the usual representation to compute with.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct T1B1;
+
+impl Encoding for T1B1 {
+    const TRITS_PER_BYTE: usize = 1;
+    const MAX_BYTE: i8 = 1;
+
+    fn get(byte: i8, _position: usize) -> Trit {
+        match byte {
+            -1 => Trit::MinusOne,
+            0 => Trit::Zero,
+            _ => Trit::PlusOne,
+        }
+    }
+
+    fn set(byte: &mut i8, _position: usize, trit: Trit) {
+        *byte = trit.into();
+    }
+}
+
+/// One tryte per byte, so that the bytes are the values of the trytes.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct T3B1;
+
+impl Encoding for T3B1 {
+    const TRITS_PER_BYTE: usize = 3;
+    const MAX_BYTE: i8 = 13;
+}
+
+/// Five trits per byte, the densest encoding that fits a byte, e.g. to store transactions.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct T5B1;
+
+impl Encoding for T5B1 {
+    const TRITS_PER_BYTE: usize = 5;
+    const MAX_BYTE: i8 = 121;
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// The errors of checked ternary conversions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// A value that isn't a trit, i.e. not in `-1..=1`.
+    InvalidTrit(i8),
+    /// A value that isn't a tryte, i.e. not in `-13..=13`.
+    InvalidTryteValue(i8),
+    /// A character that isn't in `TRYTE_ALPHABET`.
+    InvalidTryte(char),
+    /// The byte at `index` doesn't encode trits in the expected encoding.
+    InvalidByte { index: usize, byte: i8 },
+    /// `bytes` bytes can't hold exactly `len` trits in the expected encoding.
+    InvalidLength { len: usize, bytes: usize },
+    /// The character at byte `index` of a string isn't in `TRYTE_ALPHABET`.
+    InvalidTryteAt { index: usize, character: char },
+    /// The character at byte `index` of a message isn't ASCII, so it can't be encoded as trytes.
+    NonAsciiCharacter { index: usize, character: char },
+    /// The trytes starting at `index` don't encode an ASCII character, or there is only one left.
+    InvalidAsciiTrytes { index: usize },
+    /// There are `len` trytes, more than the `max` that fit.
+    TooLong { len: usize, max: usize },
+    /// A number doesn't fit into the integer type or the number of trits it is converted to.
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidTrit(value) => write!(f, "{} is not a trit", value),
+            Error::InvalidTryteValue(value) => write!(f, "{} is not a tryte value", value),
+            Error::InvalidTryte(c) => write!(f, "{:?} is not a tryte", c),
+            Error::InvalidByte { index, byte } => {
+                write!(f, "byte {} at index {} does not encode trits", byte, index)
+            }
+            Error::InvalidLength { len, bytes } => {
+                write!(f, "{} bytes cannot hold exactly {} trits", bytes, len)
+            }
+            Error::InvalidTryteAt { index, character } => {
+                write!(f, "{:?} at index {} is not a tryte", character, index)
+            }
+            Error::NonAsciiCharacter { index, character } => {
+                write!(f, "{:?} at index {} is not ASCII", character, index)
+            }
+            Error::InvalidAsciiTrytes { index } => {
+                write!(
+                    f,
+                    "trytes at index {} do not encode an ASCII character",
+                    index
+                )
+            }
+            Error::TooLong { len, max } => write!(f, "{} trytes are more than {}", len, max),
+            Error::Overflow => write!(f, "numeric overflow"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,5 +1,16 @@
+mod encoding;
+mod error;
+mod numeric;
+mod trit;
+mod trits;
+mod tryte_string;
 mod trytes;
 
+pub use encoding::{Encoding, T1B1, T3B1, T5B1};
+pub use error::Error;
+pub use trit::Trit;
+pub use trits::{TritBuf, Trits};
+pub use tryte_string::{TryteStr, TryteString};
 pub use trytes::*;
 
 // ONLY TEMPORARY
@@ -0,0 +1,152 @@
+use crate::{Encoding, Error, Trit, TritBuf, Trits};
+
+use std::convert::TryFrom;
+
+/// The balanced ternary value of `trits`, the least significant first.
+fn value<E: Encoding>(trits: Trits<'_, E>) -> Result<i128, Error> {
+    // Summing from the least significant trit keeps every partial sum in range if the value is, and the weight
+    // only has to exist for trits that aren't zero
+    let mut weight = Some(1i128);
+    let mut value = 0i128;
+
+    for trit in trits.iter() {
+        if trit != Trit::Zero {
+            let term = weight.ok_or(Error::Overflow)? * i128::from(i8::from(trit));
+            value = value.checked_add(term).ok_or(Error::Overflow)?;
+        }
+        weight = weight.and_then(|weight| weight.checked_mul(3));
+    }
+
+    Ok(value)
+}
+
+/// The trits of `value`, the least significant first, padded to `len` trits if given.
+fn trits<E: Encoding>(mut value: i128, len: Option<usize>) -> Result<TritBuf<E>, Error> {
+    let mut buf = TritBuf::with_capacity(len.unwrap_or(0));
+
+    while value != 0 || len.is_some_and(|len| buf.len() < len) {
+        if len == Some(buf.len()) {
+            return Err(Error::Overflow);
+        }
+
+        // Rounds to the closest multiple of 3 without subtracting, which could overflow
+        let (quotient, remainder) = (value.div_euclid(3), value.rem_euclid(3));
+        let (trit, quotient) = match remainder {
+            0 => (Trit::Zero, quotient),
+            1 => (Trit::PlusOne, quotient),
+            _ => (Trit::MinusOne, quotient + 1),
+        };
+        buf.push(trit);
+        value = quotient;
+    }
+
+    Ok(buf)
+}
+
+impl<E: Encoding> TritBuf<E> {
+    /// The `len` trits of `value`, or `Error::Overflow` if it doesn't fit.
+    ///
+    /// This is meant for fixed-width fields, e.g. the 81 trits of the value of a transaction.
+    pub fn try_from_int<I: Into<i128>>(value: I, len: usize) -> Result<Self, Error> {
+        trits(value.into(), Some(len))
+    }
+}
+
+macro_rules! impl_numeric {
+    ($($int:ty),*) => {
+        $(
+            /// The value of balanced trits, the least significant first.
+            impl<E: Encoding> TryFrom<Trits<'_, E>> for $int {
+                type Error = Error;
+
+                fn try_from(trits: Trits<'_, E>) -> Result<Self, Self::Error> {
+                    <$int>::try_from(value(trits)?).map_err(|_| Error::Overflow)
+                }
+            }
+
+            impl<E: Encoding> TryFrom<&TritBuf<E>> for $int {
+                type Error = Error;
+
+                fn try_from(trits: &TritBuf<E>) -> Result<Self, Self::Error> {
+                    <$int>::try_from(trits.as_trits())
+                }
+            }
+
+            /// The fewest balanced trits of the value, the least significant first.
+            impl<E: Encoding> From<$int> for TritBuf<E> {
+                fn from(value: $int) -> Self {
+                    // Can't fail, there is no length to overflow
+                    trits(value as i128, None).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric!(i8, i16, i32, i64, u64, usize, i128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{T1B1, T5B1};
+
+    fn trits(values: &[i8]) -> TritBuf {
+        values
+            .iter()
+            .map(|&value| Trit::try_from(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn convert_small_numbers() {
+        assert_eq!(TritBuf::<T1B1>::from(0i8), trits(&[]));
+        assert_eq!(TritBuf::<T1B1>::from(5i8), trits(&[-1, -1, 1]));
+        assert_eq!(TritBuf::<T1B1>::from(-5i8), trits(&[1, 1, -1]));
+        assert_eq!(i8::try_from(&trits(&[-1, -1, 1, 0, 0])), Ok(5));
+    }
+
+    #[test]
+    fn round_trip_extreme_values() {
+        macro_rules! round_trip {
+            ($($int:ty),*) => {
+                $(
+                    for &value in &[<$int>::MIN, <$int>::MAX, 0, 1] {
+                        let buf = TritBuf::<T5B1>::from(value);
+                        assert_eq!(<$int>::try_from(&buf), Ok(value));
+                    }
+                )*
+            };
+        }
+
+        round_trip!(i8, i16, i32, i64, u64, i128);
+    }
+
+    #[test]
+    fn overflow_integers() {
+        // 3^5 - 1 / 2 = 121 is the highest value of 5 trits, 3^6 - 1 / 2 = 364 doesn't fit an i8
+        assert_eq!(i8::try_from(&trits(&[1; 5])), Ok(121));
+        assert_eq!(i8::try_from(&trits(&[1; 6])), Err(Error::Overflow));
+        assert_eq!(u64::try_from(&trits(&[-1])), Err(Error::Overflow));
+        assert_eq!(i128::try_from(&trits(&[1; 243])), Err(Error::Overflow));
+        // Leading zeros don't overflow
+        assert_eq!(i8::try_from(&TritBuf::<T1B1>::zeros(243)), Ok(0));
+    }
+
+    #[test]
+    fn pad_to_fixed_width() {
+        let buf = TritBuf::<T1B1>::try_from_int(-1i64, 27).unwrap();
+        assert_eq!(buf.len(), 27);
+        assert_eq!(i64::try_from(&buf), Ok(-1));
+
+        assert_eq!(TritBuf::<T1B1>::try_from_int(121i8, 5), Ok(trits(&[1; 5])));
+        assert_eq!(
+            TritBuf::<T1B1>::try_from_int(122i8, 5),
+            Err(Error::Overflow)
+        );
+        assert_eq!(
+            TritBuf::<T1B1>::try_from_int(i64::MAX, 27),
+            Err(Error::Overflow)
+        );
+    }
+}
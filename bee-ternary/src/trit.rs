@@ -0,0 +1,66 @@
+use crate::Error;
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Neg;
+
+/// A balanced trit.
+///
+/// Its representation is the `i8` of its value, so that a slice of trits can be viewed as the bytes of the T1B1
+/// encoding and back.
+#[repr(i8)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub enum Trit {
+    MinusOne = -1,
+    #[default]
+    Zero = 0,
+    PlusOne = 1,
+}
+
+impl Trit {
+    /// The trit of the balanced remainder of `value` divided by 3, so that `value - trit` is a multiple of 3.
+    pub(crate) fn balanced_remainder(value: i32) -> Self {
+        match value.rem_euclid(3) {
+            0 => Trit::Zero,
+            1 => Trit::PlusOne,
+            _ => Trit::MinusOne,
+        }
+    }
+}
+
+impl Neg for Trit {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self {
+            Trit::MinusOne => Trit::PlusOne,
+            Trit::Zero => Trit::Zero,
+            Trit::PlusOne => Trit::MinusOne,
+        }
+    }
+}
+
+impl TryFrom<i8> for Trit {
+    type Error = Error;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(Trit::MinusOne),
+            0 => Ok(Trit::Zero),
+            1 => Ok(Trit::PlusOne),
+            _ => Err(Error::InvalidTrit(value)),
+        }
+    }
+}
+
+impl From<Trit> for i8 {
+    fn from(trit: Trit) -> Self {
+        trit as i8
+    }
+}
+
+impl fmt::Display for Trit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as i8)
+    }
+}
@@ -0,0 +1,533 @@
+use crate::{Encoding, Error, Trit, Tryte, T1B1, T3B1};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+/// A borrowed sequence of trits, packed into bytes with the encoding `E`.
+///
+/// The trits are read where they are packed, so a view into e.g. the T5B1 bytes of a stored transaction doesn't copy
+/// or unpack anything. A view made by `slice` can start and end in the middle of a byte, so its bytes may hold other
+/// trits before and after its own.
+#[derive(Clone, Copy)]
+pub struct Trits<'a, E: Encoding = T1B1> {
+    bytes: &'a [i8],
+    /// The position of the first trit in the first byte.
+    offset: usize,
+    len: usize,
+    encoding: PhantomData<E>,
+}
+
+impl<'a, E: Encoding> Trits<'a, E> {
+    /// Views `bytes` as `len` trits, after checking that they are valid in the encoding `E`.
+    pub fn try_from_raw(bytes: &'a [i8], len: usize) -> Result<Self, Error> {
+        check_raw::<E>(bytes, len)?;
+        // Safe, the bytes were just checked
+        Ok(unsafe { Self::from_raw_unchecked(bytes, len) })
+    }
+
+    /// Views `bytes` as `len` trits without checking them.
+    ///
+    /// This is meant for bytes that are known to be valid, e.g. because they were written by a `TritBuf`.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be exactly the bytes of `len` valid trits in the encoding `E`, as `try_from_raw` checks: T1B1
+    /// views are read as `&[Trit]` by `as_slice`, so an invalid byte would be an invalid `Trit`.
+    pub unsafe fn from_raw_unchecked(bytes: &'a [i8], len: usize) -> Self {
+        debug_assert_eq!(bytes.len(), E::bytes_for(len));
+        Self {
+            bytes,
+            offset: 0,
+            len,
+            encoding: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Trit> {
+        if index < self.len {
+            Some(get::<E>(self.bytes, self.offset + index))
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Trit> + 'a {
+        let bytes = self.bytes;
+        let offset = self.offset;
+        (0..self.len).map(move |index| get::<E>(bytes, offset + index))
+    }
+
+    /// The trits in `range`, without copying them.
+    ///
+    /// Panics if `range` is out of bounds, like slicing does.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {:?} out of bounds of {} trits",
+            range,
+            self.len
+        );
+
+        let start = self.offset + range.start;
+        let offset = start % E::TRITS_PER_BYTE;
+        let len = range.end - range.start;
+        let first = start / E::TRITS_PER_BYTE;
+
+        Self {
+            bytes: &self.bytes[first..first + E::bytes_for(offset + len)],
+            offset,
+            len,
+            encoding: PhantomData,
+        }
+    }
+
+    /// The trytes of the trits, the last one padded with zero trits.
+    pub fn trytes(&self) -> impl ExactSizeIterator<Item = Tryte> + 'a {
+        let bytes = self.bytes;
+        let offset = self.offset;
+        let len = self.len;
+        (0..len.div_ceil(3)).map(move |tryte| {
+            let trit = |i| {
+                let index = 3 * tryte + i;
+                if index < len {
+                    get::<E>(bytes, offset + index)
+                } else {
+                    Trit::Zero
+                }
+            };
+            Tryte::from_trits([trit(0), trit(1), trit(2)])
+        })
+    }
+
+    /// The bytes that hold the trits, which may hold other trits too if the view was made by `slice`.
+    pub fn as_i8_slice(&self) -> &'a [i8] {
+        self.bytes
+    }
+
+    pub fn to_buf(&self) -> TritBuf<E> {
+        // Only whole bytes can be copied as they are, a sub-slice could leave other trits in the last one
+        if self.offset == 0 && self.len.is_multiple_of(E::TRITS_PER_BYTE) {
+            TritBuf {
+                bytes: self.bytes.to_vec(),
+                len: self.len,
+                encoding: PhantomData,
+            }
+        } else {
+            self.iter().collect()
+        }
+    }
+
+    /// Packs the trits into a new buffer with the encoding `F`.
+    pub fn encode<F: Encoding>(&self) -> TritBuf<F> {
+        self.iter().collect()
+    }
+}
+
+impl<'a> Trits<'a, T1B1> {
+    /// The trits as a slice, which is how T1B1 bytes are laid out anyway.
+    pub fn as_slice(&self) -> &'a [Trit] {
+        // Safe, `Trit` is represented by its `i8` value and the bytes are valid trits
+        unsafe { &*(self.bytes as *const [i8] as *const [Trit]) }
+    }
+}
+
+impl<'a> From<&'a [Trit]> for Trits<'a, T1B1> {
+    fn from(trits: &'a [Trit]) -> Self {
+        // Safe, `Trit` is represented by its `i8` value, so the bytes are valid trits
+        unsafe {
+            let bytes = &*(trits as *const [Trit] as *const [i8]);
+            Self::from_raw_unchecked(bytes, trits.len())
+        }
+    }
+}
+
+/// Views bytes as all the trits they hold.
+impl<'a, E: Encoding> TryFrom<&'a [i8]> for Trits<'a, E> {
+    type Error = Error;
+
+    fn try_from(bytes: &'a [i8]) -> Result<Self, Self::Error> {
+        Self::try_from_raw(bytes, bytes.len() * E::TRITS_PER_BYTE)
+    }
+}
+
+/// Views are equal if their trits are, whatever bytes hold them.
+impl<E: Encoding> PartialEq for Trits<'_, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<E: Encoding> Eq for Trits<'_, E> {}
+
+impl<E: Encoding> Hash for Trits<'_, E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for trit in self.iter() {
+            trit.hash(state);
+        }
+    }
+}
+
+impl<E: Encoding> fmt::Debug for Trits<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An owned, growable sequence of trits, packed into bytes with the encoding `E`.
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct TritBuf<E: Encoding = T1B1> {
+    bytes: Vec<i8>,
+    len: usize,
+    encoding: PhantomData<E>,
+}
+
+impl<E: Encoding> TritBuf<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A buffer with room for `capacity` trits before it reallocates.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(E::bytes_for(capacity)),
+            len: 0,
+            encoding: PhantomData,
+        }
+    }
+
+    /// A buffer of `len` zero trits.
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            bytes: vec![0; E::bytes_for(len)],
+            len,
+            encoding: PhantomData,
+        }
+    }
+
+    /// Takes `bytes` as `len` trits, after checking that they are valid in the encoding `E`.
+    pub fn try_from_raw(bytes: Vec<i8>, len: usize) -> Result<Self, Error> {
+        check_raw::<E>(&bytes, len)?;
+        Ok(Self {
+            bytes,
+            len,
+            encoding: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, trit: Trit) {
+        let position = self.len % E::TRITS_PER_BYTE;
+        if position == 0 {
+            self.bytes.push(0);
+        }
+        // Can't fail, there is a last byte now
+        E::set(self.bytes.last_mut().unwrap(), position, trit);
+        self.len += 1;
+    }
+
+    pub fn get(&self, index: usize) -> Option<Trit> {
+        self.as_trits().get(index)
+    }
+
+    /// Replaces the trit at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, trit: Trit) {
+        assert!(
+            index < self.len,
+            "index {} out of bounds of {} trits",
+            index,
+            self.len
+        );
+        E::set(
+            &mut self.bytes[index / E::TRITS_PER_BYTE],
+            index % E::TRITS_PER_BYTE,
+            trit,
+        );
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Trit> + '_ {
+        self.as_trits().iter()
+    }
+
+    pub fn as_trits(&self) -> Trits<'_, E> {
+        // Safe, a `TritBuf` only holds valid trits
+        unsafe { Trits::from_raw_unchecked(&self.bytes, self.len) }
+    }
+
+    /// The bytes that hold the trits.
+    pub fn as_i8_slice(&self) -> &[i8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<i8> {
+        self.bytes
+    }
+
+    /// Packs the trits into a new buffer with the encoding `F`.
+    pub fn encode<F: Encoding>(&self) -> TritBuf<F> {
+        self.as_trits().encode()
+    }
+}
+
+impl TritBuf<T1B1> {
+    pub fn as_slice(&self) -> &[Trit] {
+        self.as_trits().as_slice()
+    }
+
+    pub fn as_slice_mut(&mut self) -> &mut [Trit] {
+        // Safe, `Trit` is represented by its `i8` value and the bytes are valid trits
+        unsafe { &mut *(self.bytes.as_mut_slice() as *mut [i8] as *mut [Trit]) }
+    }
+}
+
+impl<E: Encoding> FromIterator<Trit> for TritBuf<E> {
+    fn from_iter<I: IntoIterator<Item = Trit>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut buf = Self::with_capacity(iter.size_hint().0);
+        for trit in iter {
+            buf.push(trit);
+        }
+        buf
+    }
+}
+
+impl FromIterator<Tryte> for TritBuf<T3B1> {
+    fn from_iter<I: IntoIterator<Item = Tryte>>(iter: I) -> Self {
+        let bytes = iter.into_iter().map(i8::from).collect::<Vec<_>>();
+        Self {
+            len: bytes.len() * T3B1::TRITS_PER_BYTE,
+            bytes,
+            encoding: PhantomData,
+        }
+    }
+}
+
+impl<E: Encoding> From<&[Trit]> for TritBuf<E> {
+    fn from(trits: &[Trit]) -> Self {
+        trits.iter().copied().collect()
+    }
+}
+
+impl<E: Encoding> fmt::Debug for TritBuf<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_trits().fmt(f)
+    }
+}
+
+fn get<E: Encoding>(bytes: &[i8], index: usize) -> Trit {
+    E::get(bytes[index / E::TRITS_PER_BYTE], index % E::TRITS_PER_BYTE)
+}
+
+/// Checks that `bytes` are exactly the bytes of `len` trits in the encoding `E`, with the unused trits of the last
+/// byte zeroed.
+fn check_raw<E: Encoding>(bytes: &[i8], len: usize) -> Result<(), Error> {
+    if bytes.len() != E::bytes_for(len) {
+        return Err(Error::InvalidLength {
+            len,
+            bytes: bytes.len(),
+        });
+    }
+
+    for (index, &byte) in bytes.iter().enumerate() {
+        if !E::is_valid(byte) {
+            return Err(Error::InvalidByte { index, byte });
+        }
+    }
+
+    if let Some(&last) = bytes.last() {
+        let used = len - (bytes.len() - 1) * E::TRITS_PER_BYTE;
+        if (used..E::TRITS_PER_BYTE).any(|position| E::get(last, position) != Trit::Zero) {
+            return Err(Error::InvalidByte {
+                index: bytes.len() - 1,
+                byte: last,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::T5B1;
+
+    fn trits(len: usize) -> TritBuf {
+        (0..len)
+            .map(|i| match i % 7 {
+                0 | 3 => Trit::MinusOne,
+                1 | 5 => Trit::PlusOne,
+                _ => Trit::Zero,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pack_into_bytes() {
+        let buf = TritBuf::<T5B1>::from(
+            &[
+                Trit::PlusOne,
+                Trit::PlusOne,
+                Trit::PlusOne,
+                Trit::PlusOne,
+                Trit::PlusOne,
+                Trit::MinusOne,
+                Trit::Zero,
+                Trit::PlusOne,
+            ][..],
+        );
+
+        assert_eq!(buf.len(), 8);
+        assert_eq!(buf.as_i8_slice(), &[121, -1 + 9]);
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        for len in 0..20 {
+            let original = trits(len);
+
+            let t3b1 = original.encode::<T3B1>();
+            let t5b1 = original.encode::<T5B1>();
+            assert_eq!(t3b1.as_i8_slice().len(), len.div_ceil(3));
+            assert_eq!(t5b1.as_i8_slice().len(), len.div_ceil(5));
+
+            assert_eq!(t3b1.encode::<T1B1>(), original);
+            assert_eq!(t5b1.encode::<T1B1>(), original);
+            assert_eq!(t5b1.encode::<T3B1>(), t3b1);
+        }
+    }
+
+    #[test]
+    fn set_trits() {
+        let mut buf = TritBuf::<T5B1>::zeros(12);
+        let expected = trits(12);
+
+        for (index, trit) in expected.iter().enumerate() {
+            buf.set(index, trit);
+        }
+
+        assert_eq!(buf.encode::<T1B1>(), expected);
+        assert_eq!(buf.get(12), None);
+    }
+
+    #[test]
+    fn view_valid_bytes_only() {
+        assert_eq!(
+            Trits::<T3B1>::try_from(&[13, -13, 14][..]),
+            Err(Error::InvalidByte { index: 2, byte: 14 })
+        );
+        assert_eq!(
+            Trits::<T5B1>::try_from_raw(&[1, 1], 3),
+            Err(Error::InvalidLength { len: 3, bytes: 2 })
+        );
+        // The fourth trit of the last byte is not part of the view
+        assert_eq!(
+            Trits::<T5B1>::try_from_raw(&[27], 3),
+            Err(Error::InvalidByte { index: 0, byte: 27 })
+        );
+
+        let trits = Trits::<T5B1>::try_from_raw(&[9], 3).unwrap();
+        assert_eq!(
+            trits.iter().collect::<Vec<_>>(),
+            vec![Trit::Zero, Trit::Zero, Trit::PlusOne]
+        );
+    }
+
+    #[test]
+    fn view_trit_slices_without_copying() {
+        let buf = trits(10);
+        let trits = Trits::from(buf.as_slice());
+
+        assert_eq!(trits.as_i8_slice().as_ptr(), buf.as_i8_slice().as_ptr());
+        assert_eq!(trits.as_slice(), buf.as_slice());
+    }
+
+    #[test]
+    fn slice_packed_trits() {
+        let original = trits(23);
+        let t5b1 = original.encode::<T5B1>();
+        let packed = t5b1.as_trits();
+
+        for start in 0..=23 {
+            for end in start..=23 {
+                let slice = packed.slice(start..end);
+
+                assert_eq!(slice.len(), end - start);
+                assert!(slice
+                    .iter()
+                    .eq(original.iter().skip(start).take(end - start)));
+                assert_eq!(
+                    slice.to_buf().encode::<T1B1>(),
+                    original.as_trits().slice(start..end).to_buf()
+                );
+            }
+        }
+
+        // A slice of a slice, starting and ending in the middle of bytes
+        let slice = packed.slice(3..21).slice(4..11);
+        assert_eq!(slice, original.encode::<T5B1>().as_trits().slice(7..14));
+        assert_eq!(slice.get(0), original.get(7));
+        assert_eq!(slice.get(7), None);
+        assert_eq!(
+            slice.as_i8_slice().as_ptr(),
+            packed.as_i8_slice()[1..].as_ptr()
+        );
+        assert_eq!(slice.to_buf().as_i8_slice().len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        trits(10).encode::<T5B1>().as_trits().slice(5..11);
+    }
+
+    #[test]
+    fn collect_trytes() {
+        let trytes = "Z9A"
+            .chars()
+            .map(|c| Tryte::try_from(c).unwrap())
+            .collect::<TritBuf<T3B1>>();
+
+        assert_eq!(trytes.as_i8_slice(), &[-1, 0, 1]);
+        assert_eq!(
+            trytes
+                .as_trits()
+                .trytes()
+                .map(char::from)
+                .collect::<String>(),
+            "Z9A"
+        );
+        assert_eq!(
+            trytes
+                .encode::<T5B1>()
+                .as_trits()
+                .slice(3..8)
+                .trytes()
+                .map(char::from)
+                .collect::<String>(),
+            "9A"
+        );
+    }
+}
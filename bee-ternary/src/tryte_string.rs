@@ -0,0 +1,298 @@
+use crate::{Error, Tryte, TRYTE_ALPHABET};
+
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A borrowed string of trytes, i.e. of characters of `TRYTE_ALPHABET` only.
+#[derive(Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[repr(transparent)]
+pub struct TryteStr(str);
+
+impl TryteStr {
+    /// Views `s` as trytes, or reports the first character that isn't one.
+    pub fn try_from_str(s: &str) -> Result<&Self, Error> {
+        check(s)?;
+        Ok(Self::from_str_unchecked(s))
+    }
+
+    fn from_str_unchecked(s: &str) -> &Self {
+        // Safe, `TryteStr` is represented by a `str`
+        unsafe { &*(s as *const str as *const TryteStr) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The number of trytes, which is also the number of bytes of the string.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn trytes(&self) -> impl ExactSizeIterator<Item = Tryte> + '_ {
+        // Can't fail, the characters have been checked
+        self.0.bytes().map(|b| Tryte::try_from(b as char).unwrap())
+    }
+
+    /// Decodes a message that was encoded with `TryteString::from_ascii`.
+    pub fn to_ascii(&self) -> Result<String, Error> {
+        if !self.len().is_multiple_of(2) {
+            return Err(Error::InvalidAsciiTrytes {
+                index: self.len() - 1,
+            });
+        }
+
+        self.0
+            .as_bytes()
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let byte = alphabet_index(pair[0]) + alphabet_index(pair[1]) * 27;
+                if byte < 128 {
+                    Ok(byte as u8 as char)
+                } else {
+                    Err(Error::InvalidAsciiTrytes { index: 2 * i })
+                }
+            })
+            .collect()
+    }
+
+    pub fn to_tryte_string(&self) -> TryteString {
+        TryteString(self.0.to_string())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a TryteStr {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        TryteStr::try_from_str(s)
+    }
+}
+
+impl AsRef<str> for TryteStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ToOwned for TryteStr {
+    type Owned = TryteString;
+
+    fn to_owned(&self) -> TryteString {
+        self.to_tryte_string()
+    }
+}
+
+impl fmt::Display for TryteStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// An owned string of trytes, i.e. of characters of `TRYTE_ALPHABET` only.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct TryteString(String);
+
+impl TryteString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes an ASCII message, two trytes per character.
+    ///
+    /// A character `c` becomes the trytes at `c % 27` and `c / 27` in `TRYTE_ALPHABET`, in that order.
+    pub fn from_ascii(message: &str) -> Result<Self, Error> {
+        let mut trytes = String::with_capacity(2 * message.len());
+
+        for (index, character) in message.char_indices() {
+            if !character.is_ascii() {
+                return Err(Error::NonAsciiCharacter { index, character });
+            }
+            let byte = character as usize;
+            trytes.push(TRYTE_ALPHABET[byte % 27]);
+            trytes.push(TRYTE_ALPHABET[byte / 27]);
+        }
+
+        Ok(Self(trytes))
+    }
+
+    pub fn as_tryte_str(&self) -> &TryteStr {
+        TryteStr::from_str_unchecked(&self.0)
+    }
+
+    /// Pads the trytes to `len` with '9's, or returns `Error::TooLong` if there are more already.
+    pub fn pad(mut self, len: usize) -> Result<Self, Error> {
+        if self.len() > len {
+            return Err(Error::TooLong {
+                len: self.len(),
+                max: len,
+            });
+        }
+
+        let padding = len - self.len();
+        self.0.extend(std::iter::repeat_n('9', padding));
+        Ok(self)
+    }
+
+    /// Shortens the trytes to `len`, if there are more.
+    pub fn truncate(mut self, len: usize) -> Self {
+        self.0.truncate(len);
+        self
+    }
+
+    pub fn push(&mut self, tryte: Tryte) {
+        self.0.push(tryte.into());
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for TryteString {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TryteStr::try_from_str(s)?.to_tryte_string())
+    }
+}
+
+impl TryFrom<String> for TryteString {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        check(&s)?;
+        Ok(Self(s))
+    }
+}
+
+impl Deref for TryteString {
+    type Target = TryteStr;
+
+    fn deref(&self) -> &TryteStr {
+        self.as_tryte_str()
+    }
+}
+
+impl Borrow<TryteStr> for TryteString {
+    fn borrow(&self) -> &TryteStr {
+        self.as_tryte_str()
+    }
+}
+
+impl AsRef<str> for TryteString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<TryteString> for String {
+    fn from(trytes: TryteString) -> Self {
+        trytes.0
+    }
+}
+
+impl std::iter::FromIterator<Tryte> for TryteString {
+    fn from_iter<I: IntoIterator<Item = Tryte>>(iter: I) -> Self {
+        Self(iter.into_iter().map(char::from).collect())
+    }
+}
+
+impl fmt::Display for TryteString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Checks that all characters of `s` are trytes.
+fn check(s: &str) -> Result<(), Error> {
+    match s.char_indices().find(|&(_, c)| Tryte::try_from(c).is_err()) {
+        Some((index, character)) => Err(Error::InvalidTryteAt { index, character }),
+        None => Ok(()),
+    }
+}
+
+/// The index of a tryte character in `TRYTE_ALPHABET`.
+fn alphabet_index(tryte: u8) -> usize {
+    match tryte {
+        b'9' => 0,
+        _ => (tryte - b'A' + 1) as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trytes_only() {
+        assert_eq!("ABC9".parse::<TryteString>().unwrap().as_str(), "ABC9");
+        assert_eq!(
+            "AB-C".parse::<TryteString>(),
+            Err(Error::InvalidTryteAt {
+                index: 2,
+                character: '-'
+            })
+        );
+        assert_eq!(
+            TryteStr::try_from_str("Aé"),
+            Err(Error::InvalidTryteAt {
+                index: 1,
+                character: 'é'
+            })
+        );
+    }
+
+    #[test]
+    fn pad_and_truncate() {
+        let trytes = "HELLO".parse::<TryteString>().unwrap();
+
+        assert_eq!(trytes.clone().pad(8).unwrap().as_str(), "HELLO999");
+        assert_eq!(
+            trytes.clone().pad(4),
+            Err(Error::TooLong { len: 5, max: 4 })
+        );
+        assert_eq!(trytes.truncate(4).pad(4).unwrap().as_str(), "HELL");
+    }
+
+    #[test]
+    fn encode_ascii() {
+        assert_eq!(TryteString::from_ascii("Z").unwrap().as_str(), "IC");
+        assert_eq!(
+            TryteString::from_ascii("JOTA JOTA").unwrap().as_str(),
+            "TBYBCCKBEATBYBCCKB"
+        );
+        assert_eq!(
+            TryteString::from_ascii("Bee¿"),
+            Err(Error::NonAsciiCharacter {
+                index: 3,
+                character: '¿'
+            })
+        );
+    }
+
+    #[test]
+    fn decode_ascii() {
+        let message = "Hello, Bee! ~{0}\n";
+        let trytes = TryteString::from_ascii(message).unwrap();
+
+        assert_eq!(trytes.to_ascii().unwrap(), message);
+        assert_eq!(
+            TryteStr::try_from_str("ICZ").unwrap().to_ascii(),
+            Err(Error::InvalidAsciiTrytes { index: 2 })
+        );
+        // 'Z' + 27 * 'Z' is 26 + 27 * 26, far beyond ASCII
+        assert_eq!(
+            TryteStr::try_from_str("ICZZ").unwrap().to_ascii(),
+            Err(Error::InvalidAsciiTrytes { index: 2 })
+        );
+    }
+}
@@ -1,3 +1,8 @@
+use crate::{Error, Trit};
+
+use std::convert::TryFrom;
+use std::fmt;
+
 pub const TRYTE_ALPHABET: [char; 27] = [
     '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
     'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
@@ -12,3 +17,109 @@ impl IsTryte for char {
         *self == '9' || (*self >= 'A' && *self <= 'Z')
     }
 }
+
+/// A balanced tryte, i.e. three trits with a value in `-13..=13`.
+///
+/// Its character in `TRYTE_ALPHABET` is '9' for 0, 'A' to 'M' for 1 to 13 and 'N' to 'Z' for -13 to -1.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Tryte(i8);
+
+impl Tryte {
+    pub const MIN_VALUE: i8 = -13;
+    pub const MAX_VALUE: i8 = 13;
+
+    /// The tryte of three trits, the least significant first.
+    pub fn from_trits(trits: [Trit; 3]) -> Self {
+        Self(i8::from(trits[0]) + 3 * i8::from(trits[1]) + 9 * i8::from(trits[2]))
+    }
+
+    /// The three trits of the tryte, the least significant first.
+    pub fn trits(self) -> [Trit; 3] {
+        let mut value = i32::from(self.0);
+        let mut trits = [Trit::Zero; 3];
+        for trit in trits.iter_mut() {
+            *trit = Trit::balanced_remainder(value);
+            value = (value - i32::from(i8::from(*trit))) / 3;
+        }
+        trits
+    }
+
+    pub fn value(self) -> i8 {
+        self.0
+    }
+}
+
+impl TryFrom<i8> for Tryte {
+    type Error = Error;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        if !(Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            return Err(Error::InvalidTryteValue(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl TryFrom<char> for Tryte {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '9' => Ok(Self(0)),
+            'A'..='M' => Ok(Self(c as i8 - 'A' as i8 + 1)),
+            'N'..='Z' => Ok(Self(c as i8 - 'Z' as i8 - 1)),
+            _ => Err(Error::InvalidTryte(c)),
+        }
+    }
+}
+
+impl From<Tryte> for char {
+    fn from(tryte: Tryte) -> Self {
+        TRYTE_ALPHABET[(tryte.0 + 27) as usize % 27]
+    }
+}
+
+impl From<Tryte> for i8 {
+    fn from(tryte: Tryte) -> Self {
+        tryte.0
+    }
+}
+
+impl fmt::Display for Tryte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_all_characters() {
+        for (i, &c) in TRYTE_ALPHABET.iter().enumerate() {
+            let tryte = Tryte::try_from(c).unwrap();
+            assert_eq!(char::from(tryte), c);
+            assert_eq!(
+                i32::from(tryte.value()).rem_euclid(27),
+                i as i32,
+                "wrong value of {}",
+                c
+            );
+        }
+        assert_eq!(Tryte::try_from('a'), Err(Error::InvalidTryte('a')));
+    }
+
+    #[test]
+    fn convert_trits() {
+        for value in Tryte::MIN_VALUE..=Tryte::MAX_VALUE {
+            let tryte = Tryte::try_from(value).unwrap();
+            assert_eq!(Tryte::from_trits(tryte.trits()), tryte);
+        }
+        assert_eq!(
+            Tryte::try_from('M').unwrap().trits(),
+            [Trit::PlusOne, Trit::PlusOne, Trit::PlusOne]
+        );
+        assert_eq!(Tryte::try_from(14), Err(Error::InvalidTryteValue(14)));
+    }
+}